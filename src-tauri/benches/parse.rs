@@ -0,0 +1,91 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use filturd_lib::filter::parser::block_item::parse_block_item;
+use filturd_lib::filter::parser::blocks::{
+    parse_block_with_lines, parse_block_with_lines_parallel,
+};
+use filturd_lib::filter::parser::blocks_with_lines::parse_lines;
+use filturd_lib::filter::parser::lines::content_to_lines;
+use filturd_lib::filter::parser::parse_file::parse_content;
+use std::hint::black_box;
+use std::path::PathBuf;
+
+fn read_example_filter() -> String {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("filter_examples/example_001.filter");
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn parse_example_filter(c: &mut Criterion) {
+    let content = read_example_filter();
+
+    c.bench_function("parse_content example_001.filter", |b| {
+        b.iter(|| parse_content(black_box(&content)).unwrap())
+    });
+}
+
+/// Compares sequential and parallel parsing of blocks for the example filter
+/// and for a filter made of ten copies of it.
+fn parse_blocks_sequential_vs_parallel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_block_with_lines");
+
+    for copies in [1, 10] {
+        let content = read_example_filter().repeat(copies);
+        let lines = content_to_lines(&content);
+        let blocks_with_lines = parse_lines(&lines).blocks;
+
+        group.bench_with_input(
+            BenchmarkId::new("sequential", blocks_with_lines.len()),
+            &blocks_with_lines,
+            |b, blocks| b.iter(|| parse_block_with_lines(black_box(blocks)).unwrap()),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("parallel", blocks_with_lines.len()),
+            &blocks_with_lines,
+            |b, blocks| b.iter(|| parse_block_with_lines_parallel(black_box(blocks)).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+/// Compares parsing the lines of the example filter into borrowed block items with
+/// copying their parameters into owned block items, as `Block` does.
+fn parse_block_items_borrowed_vs_owned(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_block_item");
+    let content = read_example_filter();
+    let lines = content_to_lines(&content);
+    let blocks_with_lines = parse_lines(&lines).blocks;
+    let block_lines: Vec<&str> = blocks_with_lines
+        .iter()
+        .flat_map(|block| block.lines.iter().copied())
+        .collect();
+
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            black_box(&block_lines)
+                .iter()
+                .map(|line| parse_block_item(line).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("owned", |b| {
+        b.iter(|| {
+            black_box(&block_lines)
+                .iter()
+                .map(|line| parse_block_item(line).unwrap().to_block_item())
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    parse_example_filter,
+    parse_blocks_sequential_vs_parallel,
+    parse_block_items_borrowed_vs_owned
+);
+criterion_main!(benches);
@@ -1,12 +1,19 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockId } from "./BlockId";
 import type { BlockItem } from "./BlockItem";
 import type { BlockName } from "./BlockName";
+import type { BlockTag } from "./BlockTag";
+import type { Span } from "./Span";
 
 /**
  * Represents a parsed Block which holds a block name and its parsed block items.
  */
 export type Block = {
+  id: BlockId;
   order: number;
   name: BlockName;
+  enabled: boolean;
+  tags: Array<BlockTag>;
   items: { [key in string]?: Array<BlockItem> };
+  span: Span;
 };
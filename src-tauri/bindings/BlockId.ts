@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Identity of a block that does not depend on its content or position.
+ *
+ * The blocks of a freshly parsed filter get ids in file order. After that a block keeps
+ * its id when it is edited, moved or re-parsed, and new blocks get ids that were not used before.
+ */
+export type BlockId = number;
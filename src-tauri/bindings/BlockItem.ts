@@ -3,5 +3,7 @@
 /**
  * Represents a line in the loot filter block.
  * The `name` field is the line name; `params` holds all following parameters.
+ * The parameters are owned because blocks outlive the content they were parsed from:
+ * the document keeps them while the content is replaced by edits and reloads.
  */
 export type BlockItem = { name: string; params: Array<string> };
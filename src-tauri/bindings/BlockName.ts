@@ -1,3 +1,9 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type BlockName = "Show" | "Hide";
+/**
+ * Keyword that starts a block. The line must start with exactly this word,
+ * so `ShowcaseItem` or `Hidden` do not start a block.
+ *
+ * To support a new block keyword add a variant here, its name is the keyword.
+ */
+export type BlockName = "Show" | "Hide" | "Minimal";
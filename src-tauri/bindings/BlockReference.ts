@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockId } from "./BlockId";
+
+/**
+ * A block whose `BaseType` or `Class` condition matches the looked up name.
+ */
+export type BlockReference = {
+  order: number;
+  id: BlockId;
+  exact: boolean;
+  can_fire: boolean;
+  shadowed_by: number | null;
+};
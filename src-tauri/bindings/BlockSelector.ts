@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Chooses the blocks changed by a bulk edit.
+ */
+export type BlockSelector =
+  | { type: "Tag"; name: string; value: string }
+  | { type: "Item"; name: string; value: string | null }
+  | { type: "Section"; text: string }
+  | { type: "All"; selectors: Array<BlockSelector> };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Sound } from "./Sound";
+
+/**
+ * What the game does when an item of the block drops.
+ */
+export type BlockSoundPreview = {
+  sound: Sound | null;
+  played: boolean;
+  drop_sound: boolean;
+};
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * State given to a block by a strictness rule.
+ */
+export type BlockState = "Show" | "Hide" | "Enabled" | "Disabled";
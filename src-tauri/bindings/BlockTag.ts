@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A tag written in the comment of a block name line.
+ *
+ * NeverSink tags blocks as `Show # %D6 $type->exoticbases $tier->commonexoticbaseshigh`.
+ * `$type->exoticbases` is the tag `$type` with the value `exoticbases`, and `%D6` is
+ * the tag `%D` with the value `6`, the strictness level that disables the block.
+ */
+export type BlockTag = { name: string; value: string };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockItem } from "./BlockItem";
+
+/**
+ * Change made to each selected block.
+ */
+export type BlockTransform =
+  | { type: "SetItem"; item: BlockItem }
+  | { type: "AddCondition"; item: BlockItem }
+  | { type: "RemoveItem"; name: string }
+  | { type: "SetEnabled"; enabled: boolean };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockSelector } from "./BlockSelector";
+import type { BlockTransform } from "./BlockTransform";
+
+export type BulkEdit = { selector: BlockSelector; transform: BlockTransform };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockChange } from "./BlockChange";
+
+/**
+ * Blocks changed by a bulk edit, before and after the change.
+ */
+export type BulkEditPreview = { changes: Array<BlockChange> };
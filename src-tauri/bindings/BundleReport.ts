@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Contents of an exported filter bundle.
+ */
+export type BundleReport = { files: Array<string>; missing: Array<string> };
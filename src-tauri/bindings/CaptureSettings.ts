@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Shortcuts and timing of item captures, saved in the app config directory.
+ */
+export type CaptureSettings = {
+  shortcut: string;
+  advanced_shortcut: string | null;
+  timeout_ms: number;
+  area_level: number | null;
+  max_history_entries: number | null;
+};
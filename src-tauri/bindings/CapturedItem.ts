@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Item } from "./Item";
+
+/**
+ * An item copied from the game.
+ */
+export type CapturedItem = { text: string; item: Item };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Item } from "./Item";
+
+/**
+ * An item of a corpus that filters are compared with.
+ */
+export type CorpusItem = {
+  name: string;
+  item: Item;
+  area_level: number | null;
+};
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Block } from "./Block";
+import type { CorpusItem } from "./CorpusItem";
+
+/**
+ * How the blocks of a filter handle the items of a corpus.
+ */
+export type CoverageReport = {
+  items: number;
+  unused_blocks: Array<Block>;
+  unmatched: Array<CorpusItem>;
+  hidden: Array<CorpusItem>;
+};
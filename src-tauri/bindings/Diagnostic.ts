@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Severity } from "./Severity";
+import type { Span } from "./Span";
+
+/**
+ * A problem found in the filter that does not stop it from being parsed,
+ * for example a line that is not inside any block.
+ */
+export type Diagnostic = { severity: Severity; message: string; span: Span };
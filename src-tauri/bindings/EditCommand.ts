@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockId } from "./BlockId";
+import type { BlockItem } from "./BlockItem";
+import type { BlockName } from "./BlockName";
+
+/**
+ * A change of the filter document that can be undone.
+ */
+export type EditCommand =
+  | { type: "InsertBlock"; index: number; text: string }
+  | { type: "DeleteBlock"; id: BlockId }
+  | { type: "MoveBlock"; id: BlockId; index: number }
+  | { type: "DuplicateBlock"; id: BlockId }
+  | { type: "SetItem"; id: BlockId; item: BlockItem }
+  | { type: "AddCondition"; id: BlockId; item: BlockItem }
+  | { type: "RemoveItem"; id: BlockId; name: string }
+  | { type: "ToggleEnabled"; id: BlockId }
+  | { type: "SetName"; id: BlockId; name: BlockName }
+  | { type: "Batch"; commands: Array<EditCommand> };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Beam of `PlayEffect`, such as `PlayEffect Red Temp`.
+ */
+export type Effect = { color: string; temp: boolean };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ParsedFilter } from "./ParsedFilter";
+
+/**
+ * State of the document sent to the UI after each change.
+ */
+export type FilterDocumentView = {
+  filter: ParsedFilter;
+  dirty: boolean;
+  can_undo: boolean;
+  can_redo: boolean;
+};
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FilterVersion } from "./FilterVersion";
+import type { Strictness } from "./Strictness";
+
+/**
+ * Information about the filter taken from the comment header at the top of the file.
+ *
+ * NeverSink's filters start with a header like:
+ *
+ *   # VERSION:  0.52.0b
+ *   # TYPE:     2-SEMI-STRICT
+ *   # STYLE:    DEFAULT
+ *   # AUTHOR:   NeverSink
+ *
+ * Fields that are missing from the header are `None`.
+ */
+export type FilterMetadata = {
+  version: FilterVersion | null;
+  strictness: Strictness | null;
+  style: string | null;
+  author: string | null;
+  build_notes: string | null;
+};
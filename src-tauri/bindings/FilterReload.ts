@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ParsedFilter } from "./ParsedFilter";
+
+/**
+ * Result of re-parsing the watched filter file after it changed on disk.
+ */
+export type FilterReload = {
+  path: string;
+  filter: ParsedFilter | null;
+  error: string | null;
+};
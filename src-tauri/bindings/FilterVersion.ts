@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Version of the filter, for example `0.52.0b`.
+ *
+ * Versions are ordered by major, minor and patch numbers first and then by suffix,
+ * so `0.52.0` < `0.52.0b` < `0.52.1`.
+ */
+export type FilterVersion = {
+  major: number;
+  minor: number;
+  patch: number;
+  suffix: string | null;
+};
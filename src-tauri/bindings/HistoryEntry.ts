@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Item } from "./Item";
+import type { ItemMatch } from "./ItemMatch";
+
+/**
+ * A captured item kept in the history.
+ */
+export type HistoryEntry = {
+  id: number;
+  text: string;
+  item: Item;
+  captured_at: number;
+  pinned: boolean;
+  area_level: number | null;
+  matched: ItemMatch | null;
+};
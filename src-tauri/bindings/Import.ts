@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Span } from "./Span";
+
+/**
+ * An `Import "MyRules.filter" Optional` directive.
+ */
+export type Import = { path: string; optional: boolean; span: Span };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * File `from` imports file `to`.
+ */
+export type IncludeEdge = { from: number; to: number; optional: boolean };
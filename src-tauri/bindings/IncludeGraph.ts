@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IncludeEdge } from "./IncludeEdge";
+
+/**
+ * Files of a filter and the `Import` directives between them.
+ *
+ * The file with index 0 is the opened filter file. Spans of blocks and diagnostics
+ * refer to the files by their index.
+ */
+export type IncludeGraph = { files: Array<string>; edges: Array<IncludeEdge> };
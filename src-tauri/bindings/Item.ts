@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ItemModifier } from "./ItemModifier";
+
+/**
+ * An item parsed from the description the game copies to the clipboard.
+ *
+ * Both the normal format (Ctrl+C) and the advanced format (Ctrl+Alt+C), which adds a
+ * `{ ... }` header with the name and tier above every modifier, are supported.
+ */
+export type Item = {
+  class: string;
+  rarity: string;
+  name: string | null;
+  base_type: string;
+  item_level: number | null;
+  quality: number | null;
+  stack_size: number | null;
+  map_tier: number | null;
+  sockets: string | null;
+  corrupted: boolean;
+  mirrored: boolean;
+  identified: boolean;
+  modifiers: Array<ItemModifier>;
+  advanced: boolean;
+};
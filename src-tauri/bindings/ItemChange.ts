@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Block } from "./Block";
+import type { Item } from "./Item";
+import type { ItemMatch } from "./ItemMatch";
+
+/**
+ * An item that looks different with the second filter.
+ */
+export type ItemChange = {
+  name: string;
+  item: Item;
+  before: ItemMatch;
+  after: ItemMatch;
+  before_block: Block | null;
+  after_block: Block | null;
+};
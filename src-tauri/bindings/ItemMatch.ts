@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockId } from "./BlockId";
+
+/**
+ * What the filter does with an item when it drops.
+ */
+export type ItemMatch = {
+  block: BlockId | null;
+  order: number | null;
+  visible: boolean;
+  actions: { [key in string]?: Array<string> };
+};
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A modifier line of an item.
+ */
+export type ItemModifier = {
+  text: string;
+  kind: string | null;
+  name: string | null;
+  tier: number | null;
+};
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Everything that decides how the label of an item looks.
+ */
+export type LabelStyle = {
+  text: string;
+  text_color: [number, number, number, number];
+  border_color: [number, number, number, number] | null;
+  background_color: [number, number, number, number];
+  font_size: number;
+};
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Icon of `MinimapIcon`, such as `MinimapIcon 0 Red Star`.
+ */
+export type MinimapIcon = { size: number; color: string; shape: string };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MinimapIcon } from "./MinimapIcon";
+
+/**
+ * A rendered minimap icon.
+ */
+export type MinimapIconImage = { icon: MinimapIcon; url: string };
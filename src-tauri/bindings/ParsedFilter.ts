@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Block } from "./Block";
+import type { Diagnostic } from "./Diagnostic";
+import type { FilterMetadata } from "./FilterMetadata";
+import type { Import } from "./Import";
+import type { IncludeGraph } from "./IncludeGraph";
+
+/**
+ * A parsed loot filter: the metadata from its header and its blocks.
+ */
+export type ParsedFilter = {
+  metadata: FilterMetadata;
+  blocks: Array<Block>;
+  imports: Array<Import>;
+  diagnostics: Array<Diagnostic>;
+  include_graph: IncludeGraph;
+};
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Error returned when a query can not be parsed.
+ */
+export type QueryError = { message: string; start: number; end: number };
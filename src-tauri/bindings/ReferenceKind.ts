@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Condition that names item types, looked up by [`ReferenceIndex`].
+ */
+export type ReferenceKind = "BaseType" | "Class";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ItemChange } from "./ItemChange";
+
+/**
+ * Items of a corpus whose visibility or style differs between two filters.
+ */
+export type RegressionReport = { items: number; changes: Array<ItemChange> };
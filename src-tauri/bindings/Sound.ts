@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Sound played when an item of the tier drops.
+ */
+export type Sound =
+  | { type: "Alert"; id: string; volume: number | null; positional: boolean }
+  | { type: "Custom"; path: string; volume: number | null; optional: boolean };
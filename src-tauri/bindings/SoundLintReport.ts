@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Diagnostic } from "./Diagnostic";
+
+/**
+ * Problems with the sound files of the `CustomAlertSound` actions of a filter.
+ */
+export type SoundLintReport = {
+  diagnostics: Array<Diagnostic>;
+  unused_files: Array<string>;
+};
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A directory of sound files shared as a whole.
+ */
+export type SoundPack = {
+  name: string;
+  dir: string;
+  files: Array<string>;
+  built_in: boolean;
+};
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Byte range of a part of the loot filter content, `start` is inclusive and `end` is exclusive.
+ */
+export type Span = { start: number; end: number; file: number };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Strictness level of a NeverSink filter, from the most permissive to the most strict.
+ */
+export type Strictness =
+  | "Soft"
+  | "Regular"
+  | "SemiStrict"
+  | "Strict"
+  | "VeryStrict"
+  | "UberStrict"
+  | "UberPlusStrict";
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockSelector } from "./BlockSelector";
+import type { BlockState } from "./BlockState";
+import type { Strictness } from "./Strictness";
+
+/**
+ * Decides how blocks change between strictness levels.
+ */
+export type StrictnessRule =
+  | { type: "LevelTag"; tag: string; above: BlockState; otherwise: BlockState | null }
+  | { type: "Select"; selector: BlockSelector; from: Strictness; state: BlockState };
@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TierStyle } from "./TierStyle";
+
+/**
+ * Colors, font size, effect, minimap icon and sound of the blocks of the filter by tier.
+ *
+ * The styles are looked up by the `$type` and `$tier` tags of a block, first with the
+ * `type/tier` key, such as `currency->stackable/t1`, then with the `tier` key alone.
+ *
+ * Themes are stored in TOML or JSON files:
+ *
+ * ```toml
+ * [styles.t1]
+ * text_color = [255, 0, 0, 255]
+ * font_size = 45
+ * effect = { color = "Red" }
+ * minimap_icon = { size = 0, color = "Red", shape = "Star" }
+ * sound = { type = "Alert", id = "1", volume = 300 }
+ * ```
+ */
+export type Theme = { styles: { [key in string]?: TierStyle } };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Sound } from "./Sound";
+
+/**
+ * Sound of the blocks of a tier, shown next to the tier.
+ */
+export type TierSound = { tier: string; sound: Sound | null };
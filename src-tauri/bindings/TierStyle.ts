@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Effect } from "./Effect";
+import type { MinimapIcon } from "./MinimapIcon";
+import type { Sound } from "./Sound";
+
+/**
+ * Style of the blocks of a tier. Missing fields leave the block as it is.
+ */
+export type TierStyle = {
+  text_color: Array<number> | null;
+  border_color: Array<number> | null;
+  background_color: Array<number> | null;
+  font_size: number | null;
+  effect: Effect | null;
+  minimap_icon: MinimapIcon | null;
+  sound: Sound | null;
+};
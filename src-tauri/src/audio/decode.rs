@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use rodio::{Decoder, Source};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::time::Duration;
+
+pub type SoundDecoder = Decoder<BufReader<File>>;
+
+/// Opens the sound file and prepares it for playback.
+///
+/// Returns an error if the file does not exist or its format is not supported.
+/// Nothing is played, so this works without an audio device.
+pub fn decode(path: &Path) -> Result<SoundDecoder> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open sound {}", path.display()))?;
+
+    Decoder::new(BufReader::new(file))
+        .with_context(|| format!("Unsupported sound format {}", path.display()))
+}
+
+/// Properties of a decoded sound file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoundInfo {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub duration: Duration,
+    // Highest absolute sample value, 1.0 is full scale
+    pub peak: f32,
+    // Root mean square of the samples, 1.0 is full scale
+    pub rms: f32,
+}
+
+/// Decodes the whole file to find out how long and how loud it plays.
+pub fn sound_info(path: &Path) -> Result<SoundInfo> {
+    let decoder = decode(path)?;
+    let channels = decoder.channels();
+    let sample_rate = decoder.sample_rate();
+    let mut samples = 0u64;
+    let mut peak = 0f32;
+    let mut sum_of_squares = 0f64;
+
+    for sample in decoder.convert_samples::<f32>() {
+        samples += 1;
+        peak = peak.max(sample.abs());
+        sum_of_squares += sample as f64 * sample as f64;
+    }
+
+    let frames = samples / channels.max(1) as u64;
+
+    Ok(SoundInfo {
+        channels,
+        sample_rate,
+        duration: Duration::from_secs_f64(frames as f64 / sample_rate.max(1) as f64),
+        peak,
+        rms: (sum_of_squares / samples.max(1) as f64).sqrt() as f32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn bundled_sound(name: &str) -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("resources/sounds");
+        path.push(name);
+        path
+    }
+
+    #[test]
+    fn test_decode_bundled_sound() {
+        let info = sound_info(&bundled_sound("camera_snap1.mp3")).unwrap();
+        assert!(info.channels > 0);
+        assert!(info.sample_rate > 0);
+        assert!(info.duration > Duration::ZERO);
+        assert!(info.duration < Duration::from_secs(5));
+        assert!(info.peak > 0.0 && info.peak <= 1.0);
+        assert!(info.rms > 0.0 && info.rms < info.peak);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let error = decode(&bundled_sound("missing.mp3")).err().unwrap();
+        assert!(error.to_string().starts_with("Failed to open sound"));
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("notes.mp3");
+        std::fs::write(&path, "not a sound").unwrap();
+        let error = decode(&path).err().unwrap();
+        assert!(error.to_string().starts_with("Unsupported sound format"));
+    }
+}
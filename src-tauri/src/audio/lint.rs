@@ -0,0 +1,260 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use ts_rs::TS;
+
+use super::decode::{sound_info, SoundInfo};
+use crate::filter::parser::diagnostics::Diagnostic;
+use crate::filter::parser::parse_file::ParsedFilter;
+use crate::filter::theme::Sound;
+
+/// Sounds that play longer than this are reported.
+pub const LONG_SOUND: Duration = Duration::from_secs(5);
+
+/// Sounds with a higher root mean square than this are reported, about -6 dBFS.
+pub const LOUD_SOUND_RMS: f32 = 0.5;
+
+/// File extensions of sound files that the game can play.
+pub const SOUND_EXTENSIONS: [&str; 4] = ["mp3", "wav", "ogg", "flac"];
+
+// Sound files in deeper directories of the filter directory are not listed as unused
+const MAX_SEARCH_DEPTH: usize = 3;
+
+/// Problems with the sound files of the `CustomAlertSound` actions of a filter.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct SoundLintReport {
+    // Problems found in the blocks with custom sounds
+    pub diagnostics: Vec<Diagnostic>,
+    // Sound files in the filter directory that no block plays, relative to the directory
+    pub unused_files: Vec<String>,
+}
+
+/// Checks that the custom sound files of the filter exist, can be decoded and are not
+/// too long or too loud.
+///
+/// Sound paths are relative to the directory of the file that contains the block, which
+/// is `filter_dir` for the blocks of the filter file itself. A missing file of a
+/// `CustomAlertSoundOptional` is not reported, since the game ignores it on purpose.
+///
+/// Disabled blocks are not checked, but their files are not listed as unused either, since
+/// they play again when the block is enabled.
+pub fn lint_custom_sounds(filter: &ParsedFilter, filter_dir: &Path) -> SoundLintReport {
+    let mut diagnostics = Vec::new();
+    let mut used = BTreeSet::new();
+    let mut checked: HashMap<PathBuf, Result<SoundInfo, String>> = HashMap::new();
+
+    for block in &filter.blocks {
+        let Some(Sound::Custom { path, optional, .. }) = Sound::from_block(block) else {
+            continue;
+        };
+
+        let directory = filter
+            .include_graph
+            .files
+            .get(block.span.file)
+            .filter(|_| block.span.file > 0)
+            .and_then(|file| Path::new(file).parent())
+            .unwrap_or(filter_dir);
+
+        let file = directory.join(&path);
+        used.insert(normalize(&file));
+
+        if !block.enabled {
+            continue;
+        }
+
+        if !file.exists() {
+            if !optional {
+                let message = format!("Sound file `{}` was not found", path);
+                diagnostics.push(Diagnostic::error(message, block.span));
+            }
+
+            continue;
+        }
+
+        let info = checked
+            .entry(file.clone())
+            .or_insert_with(|| sound_info(&file).map_err(|e| format!("{:#}", e)));
+
+        match info {
+            Ok(info) if info.duration > LONG_SOUND => {
+                let seconds = info.duration.as_secs_f32();
+                let message = format!("Sound file `{}` plays for {:.1} seconds", path, seconds);
+                diagnostics.push(Diagnostic::warning(message, block.span));
+            }
+            Ok(info) if info.rms > LOUD_SOUND_RMS => {
+                let message = format!("Sound file `{}` is very loud", path);
+                diagnostics.push(Diagnostic::warning(message, block.span));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let message = format!("Sound file `{}` can't be played: {}", path, e);
+                diagnostics.push(Diagnostic::error(message, block.span));
+            }
+        }
+    }
+
+    let mut unused_files = Vec::new();
+    collect_unused(filter_dir, filter_dir, 0, &used, &mut unused_files);
+    unused_files.sort();
+
+    SoundLintReport {
+        diagnostics,
+        unused_files,
+    }
+}
+
+/// Adds the sound files under `dir` that are not in `used` to `unused`.
+fn collect_unused(
+    root: &Path,
+    dir: &Path,
+    depth: usize,
+    used: &BTreeSet<PathBuf>,
+    unused: &mut Vec<String>,
+) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            if depth < MAX_SEARCH_DEPTH {
+                collect_unused(root, &path, depth + 1, used, unused);
+            }
+
+            continue;
+        }
+
+        let is_sound = path.extension().is_some_and(|extension| {
+            let extension = extension.to_string_lossy().to_lowercase();
+            SOUND_EXTENSIONS.contains(&extension.as_str())
+        });
+
+        if is_sound && !used.contains(&normalize(&path)) {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            unused.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
+
+/// Returns the canonical path of an existing file, so that differently written paths
+/// of the same file are equal.
+fn normalize(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::diagnostics::Severity;
+    use crate::filter::parser::parse_file::parse_content;
+    use tempfile::tempdir;
+
+    /// Writes a mono 16-bit PCM WAV file.
+    fn write_wav(path: &Path, samples: &[i16]) {
+        let rate: u32 = 8000;
+        let data_len = samples.len() as u32 * 2;
+        let mut bytes = Vec::new();
+        bytes.extend(b"RIFF");
+        bytes.extend((36 + data_len).to_le_bytes());
+        bytes.extend(b"WAVEfmt ");
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(rate.to_le_bytes());
+        bytes.extend((rate * 2).to_le_bytes());
+        bytes.extend(2u16.to_le_bytes());
+        bytes.extend(16u16.to_le_bytes());
+        bytes.extend(b"data");
+        bytes.extend(data_len.to_le_bytes());
+
+        for sample in samples {
+            bytes.extend(sample.to_le_bytes());
+        }
+
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    fn messages(report: &SoundLintReport) -> Vec<(Severity, &str)> {
+        report
+            .diagnostics
+            .iter()
+            .map(|d| (d.severity, d.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_custom_sounds() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sounds")).unwrap();
+        let quiet: Vec<i16> = (0..4000).map(|i| ((i % 20) * 100) as i16).collect();
+        write_wav(&dir.path().join("sounds/ok.wav"), &quiet);
+        write_wav(&dir.path().join("long.wav"), &vec![100; 8000 * 6]);
+        let loud = [i16::MAX, i16::MIN].repeat(2000);
+        write_wav(&dir.path().join("loud.wav"), &loud);
+        write_wav(&dir.path().join("unused.wav"), &quiet);
+        std::fs::write(dir.path().join("broken.mp3"), "not a sound").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a sound either").unwrap();
+
+        let content = "\
+Show
+\tCustomAlertSound \"sounds/ok.wav\" 300
+Show
+\tCustomAlertSound \"long.wav\"
+Show
+\tCustomAlertSound \"loud.wav\"
+Show
+\tCustomAlertSound \"broken.mp3\"
+Show
+\tCustomAlertSound \"missing.mp3\"
+Show
+\tCustomAlertSoundOptional \"optional.mp3\"
+";
+
+        let filter = parse_content(content).unwrap();
+        let report = lint_custom_sounds(&filter, dir.path());
+
+        let expected = [
+            (Severity::Warning, "`long.wav` plays for 6.0 seconds"),
+            (Severity::Warning, "`loud.wav` is very loud"),
+            (Severity::Error, "`broken.mp3` can't be played: "),
+            (Severity::Error, "`missing.mp3` was not found"),
+        ];
+
+        let messages = messages(&report);
+        assert_eq!(messages.len(), expected.len(), "{:?}", messages);
+
+        for (message, (severity, start)) in messages.into_iter().zip(expected) {
+            assert_eq!(message.0, severity);
+            let start = format!("Sound file {}", start);
+            assert!(message.1.starts_with(&start), "{}", message.1);
+        }
+
+        assert_eq!(report.diagnostics[3].span, filter.blocks[4].span);
+        assert_eq!(report.unused_files, ["unused.wav"]);
+    }
+
+    #[test]
+    fn test_skip_disabled_blocks() {
+        let dir = tempdir().unwrap();
+        write_wav(&dir.path().join("long.wav"), &vec![100; 8000 * 6]);
+
+        let content = "\
+#Show
+#\tCustomAlertSound \"missing.mp3\"
+
+#Show
+#\tCustomAlertSound \"long.wav\"
+";
+
+        let filter = parse_content(content).unwrap();
+        assert!(filter.blocks.iter().all(|block| !block.enabled));
+
+        let report = lint_custom_sounds(&filter, dir.path());
+        assert_eq!(report.diagnostics, vec![]);
+        assert!(report.unused_files.is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+pub mod decode;
+pub mod lint;
+pub mod preview;
+
+use anyhow::{anyhow, bail, Result};
+use rodio::{OutputStream, OutputStreamHandle, Sink};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+
+use decode::decode;
+
+/// Plays sounds without blocking the caller.
+///
+/// The audio output is opened on the first sound and reused afterwards, so the app
+/// starts without an audio device and reports the missing device when a sound is played.
+pub struct AudioEngine {
+    // Directory of the sounds bundled with the app
+    sounds_dir: PathBuf,
+    output: Mutex<Option<OutputStreamHandle>>,
+    // Sounds that are playing, with their own volume
+    playing: Mutex<Vec<(Sink, f32)>>,
+    volume: Mutex<f32>,
+}
+
+impl AudioEngine {
+    /// Creates an engine that looks up bundled sounds in `resource_dir/resources/sounds`.
+    pub fn new(resource_dir: &Path) -> Self {
+        AudioEngine {
+            sounds_dir: resource_dir.join("resources").join("sounds"),
+            output: Mutex::new(None),
+            playing: Mutex::new(Vec::new()),
+            volume: Mutex::new(1.0),
+        }
+    }
+
+    /// Directory of the sounds bundled with the app.
+    pub fn sounds_dir(&self) -> &Path {
+        &self.sounds_dir
+    }
+
+    /// Returns the path of a bundled sound, such as `camera_snap1.mp3`.
+    ///
+    /// Returns an error if the name points outside of the sounds directory.
+    pub fn bundled_sound(&self, name: &str) -> Result<PathBuf> {
+        let name = Path::new(name);
+
+        if !name.components().all(|c| matches!(c, Component::Normal(_))) {
+            bail!("Invalid sound name {}", name.display());
+        }
+
+        Ok(self.sounds_dir.join(name))
+    }
+
+    /// Starts playing the sound file at `volume` times the volume of the engine,
+    /// where 1.0 is the original volume of the file.
+    pub fn play_file(&self, path: &Path, volume: f32) -> Result<()> {
+        let source = decode(path)?;
+        let sink = Sink::try_new(&self.output()?)?;
+        sink.set_volume(self.volume() * volume);
+        sink.append(source);
+
+        let mut playing = self.playing.lock().unwrap();
+        playing.retain(|(sink, _)| !sink.empty());
+        playing.push((sink, volume));
+        Ok(())
+    }
+
+    /// Starts playing a bundled sound.
+    pub fn play_bundled(&self, name: &str, volume: f32) -> Result<()> {
+        self.play_file(&self.bundled_sound(name)?, volume)
+    }
+
+    /// Stops all sounds that are playing.
+    pub fn stop(&self) {
+        for (sink, _) in self.playing.lock().unwrap().drain(..) {
+            sink.stop();
+        }
+    }
+
+    pub fn volume(&self) -> f32 {
+        *self.volume.lock().unwrap()
+    }
+
+    /// Sets the volume of all sounds, including the ones that are playing, between 0 and 1.
+    pub fn set_volume(&self, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        *self.volume.lock().unwrap() = volume;
+
+        for (sink, sound_volume) in self.playing.lock().unwrap().iter() {
+            sink.set_volume(volume * sound_volume);
+        }
+    }
+
+    /// Returns the handle of the audio output, opening the output if needed.
+    fn output(&self) -> Result<OutputStreamHandle> {
+        let mut output = self.output.lock().unwrap();
+
+        if let Some(handle) = output.as_ref() {
+            return Ok(handle.clone());
+        }
+
+        let handle = open_output()?;
+        *output = Some(handle.clone());
+        Ok(handle)
+    }
+}
+
+/// Opens the default audio output on its own thread.
+///
+/// The output stream can't be moved between threads, so it is kept alive by a thread
+/// that lives as long as the app. Sounds are played through the returned handle.
+fn open_output() -> Result<OutputStreamHandle> {
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::Builder::new()
+        .name("audio-output".to_string())
+        .spawn(move || match OutputStream::try_default() {
+            Ok((_stream, handle)) => {
+                let _ = sender.send(Ok(handle));
+
+                loop {
+                    std::thread::park();
+                }
+            }
+            Err(e) => {
+                let _ = sender.send(Err(e));
+            }
+        })?;
+
+    receiver
+        .recv()?
+        .map_err(|e| anyhow!("Failed to open the audio output: {}", e))
+}
+
+/// Holds the audio engine in Tauri state.
+pub struct AudioState(pub AudioEngine);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_sound_path() {
+        let engine = AudioEngine::new(Path::new("app"));
+        let path = engine.bundled_sound("camera_snap1.mp3").unwrap();
+        assert_eq!(path, Path::new("app/resources/sounds/camera_snap1.mp3"));
+        assert!(engine.bundled_sound("../secret.txt").is_err());
+        assert!(engine.bundled_sound("/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_missing_file_is_an_error() {
+        // The file is decoded before the output is opened, so no audio device is needed
+        let engine = AudioEngine::new(Path::new("app"));
+        assert!(engine.play_bundled("missing.mp3", 1.0).is_err());
+    }
+
+    #[test]
+    fn test_volume_is_clamped() {
+        let engine = AudioEngine::new(Path::new("app"));
+        engine.set_volume(1.5);
+        assert_eq!(engine.volume(), 1.0);
+        engine.set_volume(-1.0);
+        assert_eq!(engine.volume(), 0.0);
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use super::AudioEngine;
+use crate::filter::parser::blocks::Block;
+use crate::filter::theme::Sound;
+
+/// Volume of a sound action without a volume.
+pub const DEFAULT_VOLUME: u32 = 100;
+
+/// Highest volume of a sound action, which plays the file at its original volume.
+pub const MAX_VOLUME: u32 = 300;
+
+/// Built-in sounds with a name instead of a number, such as `PlayAlertSound ShDivine 300`.
+pub const NAMED_ALERT_SOUNDS: [&str; 10] = [
+    "ShAlchemy",
+    "ShBlessed",
+    "ShChaos",
+    "ShDivine",
+    "ShExalted",
+    "ShFusing",
+    "ShGeneral",
+    "ShMirror",
+    "ShRegal",
+    "ShVaal",
+];
+
+/// What the game does when an item of the block drops.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct BlockSoundPreview {
+    // Alert sound of the block, if any
+    pub sound: Option<Sound>,
+    // The alert sound was played, a missing `CustomAlertSoundOptional` file plays nothing
+    pub played: bool,
+    // The default sound of the item drop is played as well
+    pub drop_sound: bool,
+}
+
+/// Converts the volume of a sound action to the volume of the audio engine.
+pub fn volume_scale(volume: Option<u32>) -> f32 {
+    let volume = volume.unwrap_or(DEFAULT_VOLUME).min(MAX_VOLUME);
+    volume as f32 / MAX_VOLUME as f32
+}
+
+/// Returns true if the game plays the default drop sound for items of the block.
+pub fn drop_sound_enabled(block: &Block) -> bool {
+    let has = |name: &str| {
+        block
+            .items
+            .get(name)
+            .and_then(|items| items.last())
+            .is_some_and(|item| item.params.first().is_none_or(|p| p != "False"))
+    };
+
+    if has("DisableDropSound") {
+        return false;
+    }
+
+    if has("EnableDropSound") {
+        return true;
+    }
+
+    match Sound::from_block(block) {
+        Some(_) => !has("DisableDropSoundIfAlertSound") || has("EnableDropSoundIfAlertSound"),
+        None => true,
+    }
+}
+
+impl AudioEngine {
+    /// Returns the path of the sound file played by the action.
+    ///
+    /// Built-in sounds are the bundled samples `alerts/AlertSound1.wav` to
+    /// `alerts/AlertSound16.wav`, and `alerts/ShDivine.wav` and so on for the named sounds.
+    /// The samples are synthesized placeholders, not the sounds of the game, which can't be
+    /// shipped with the app. Custom sounds are relative to `filter_dir`, the directory of
+    /// the filter file.
+    pub fn sound_path(&self, sound: &Sound, filter_dir: Option<&Path>) -> Result<PathBuf> {
+        match sound {
+            Sound::Alert { id, .. } => {
+                if let Ok(number @ 1..=16) = id.parse::<u8>() {
+                    return self.bundled_sound(&format!("alerts/AlertSound{}.wav", number));
+                }
+
+                // The game accepts the names in any case
+                let named = NAMED_ALERT_SOUNDS
+                    .iter()
+                    .find(|name| name.eq_ignore_ascii_case(id))
+                    .ok_or_else(|| anyhow!("There is no sample for the alert sound {}", id))?;
+
+                self.bundled_sound(&format!("alerts/{}.wav", named))
+            }
+            Sound::Custom { path, .. } => Ok(match filter_dir {
+                Some(dir) => dir.join(path),
+                None => PathBuf::from(path),
+            }),
+        }
+    }
+
+    /// Plays the alert sound of the block at the volume of its action.
+    ///
+    /// Positional sounds are played like the other sounds, since a preview has no
+    /// item position.
+    pub fn preview_block_sound(
+        &self,
+        block: &Block,
+        filter_dir: Option<&Path>,
+    ) -> Result<BlockSoundPreview> {
+        let sound = Sound::from_block(block);
+        let drop_sound = drop_sound_enabled(block);
+
+        let Some(action) = &sound else {
+            return Ok(BlockSoundPreview {
+                sound,
+                played: false,
+                drop_sound,
+            });
+        };
+
+        let path = self.sound_path(action, filter_dir)?;
+
+        let (volume, optional) = match action {
+            Sound::Alert { volume, .. } => (volume, false),
+            Sound::Custom {
+                volume, optional, ..
+            } => (volume, *optional),
+        };
+
+        let played = !optional || path.exists();
+
+        if played {
+            self.play_file(&path, volume_scale(*volume))?;
+        }
+
+        Ok(BlockSoundPreview {
+            sound,
+            played,
+            drop_sound,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::decode::decode;
+    use crate::filter::parser::parse_file::parse_content;
+    use std::path::PathBuf;
+
+    fn block(content: &str) -> Block {
+        parse_content(content).unwrap().blocks.remove(0)
+    }
+
+    fn engine() -> AudioEngine {
+        AudioEngine::new(Path::new(env!("CARGO_MANIFEST_DIR")))
+    }
+
+    #[test]
+    fn test_volume_scale() {
+        assert_eq!(volume_scale(Some(300)), 1.0);
+        assert_eq!(volume_scale(Some(600)), 1.0);
+        assert_eq!(volume_scale(Some(150)), 0.5);
+        assert_eq!(volume_scale(None), volume_scale(Some(DEFAULT_VOLUME)));
+    }
+
+    #[test]
+    fn test_drop_sound() {
+        let cases = [
+            ("Show\n\tRarity Rare\n", true),
+            ("Show\n\tDisableDropSound True\n", false),
+            ("Show\n\tDisableDropSound\n", false),
+            ("Show\n\tDisableDropSound False\n", true),
+            (
+                "Show\n\tPlayAlertSound 1\n\tDisableDropSoundIfAlertSound",
+                false,
+            ),
+            ("Show\n\tRarity Rare\n\tDisableDropSoundIfAlertSound", true),
+        ];
+
+        for (content, expected) in cases {
+            assert_eq!(drop_sound_enabled(&block(content)), expected, "{}", content);
+        }
+    }
+
+    #[test]
+    fn test_sound_paths() {
+        let engine = engine();
+        let filter_dir = Path::new("filters");
+
+        let sound = Sound::from_block(&block("Show\n\tPlayAlertSound 6 300\n")).unwrap();
+        let path = engine.sound_path(&sound, Some(filter_dir)).unwrap();
+        assert!(path.ends_with("resources/sounds/alerts/AlertSound6.wav"));
+
+        let sound = Sound::from_block(&block("Show\n\tPlayAlertSound 17\n")).unwrap();
+        assert!(engine.sound_path(&sound, Some(filter_dir)).is_err());
+
+        let sound = Sound::from_block(&block("Show\n\tPlayAlertSound ShDivine 300\n")).unwrap();
+        let path = engine.sound_path(&sound, Some(filter_dir)).unwrap();
+        assert!(path.ends_with("resources/sounds/alerts/ShDivine.wav"));
+
+        let sound = Sound::from_block(&block("Show\n\tPlayAlertSound shvaal\n")).unwrap();
+        let path = engine.sound_path(&sound, Some(filter_dir)).unwrap();
+        assert!(path.ends_with("resources/sounds/alerts/ShVaal.wav"));
+
+        let sound = Sound::from_block(&block("Show\n\tPlayAlertSound ShNothing\n")).unwrap();
+        assert!(engine.sound_path(&sound, Some(filter_dir)).is_err());
+
+        let content = "Show\n\tCustomAlertSound \"sounds/divine.mp3\" 200\n";
+        let sound = Sound::from_block(&block(content)).unwrap();
+        let path = engine.sound_path(&sound, Some(filter_dir)).unwrap();
+        assert_eq!(path, PathBuf::from("filters/sounds/divine.mp3"));
+    }
+
+    #[test]
+    fn test_bundled_alert_sounds_decode() {
+        let engine = engine();
+
+        let numbers = (1..=16).map(|number: u8| number.to_string());
+        let names = NAMED_ALERT_SOUNDS.iter().map(|name| name.to_string());
+
+        for id in numbers.chain(names) {
+            let sound = Sound::Alert {
+                id,
+                volume: None,
+                positional: false,
+            };
+
+            let path = engine.sound_path(&sound, None).unwrap();
+            assert!(decode(&path).is_ok(), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn test_preview_without_sound() {
+        // Nothing is played, so no audio device is needed
+        let engine = engine();
+        let content = "Show\n\tCustomAlertSoundOptional \"missing.mp3\"\n";
+        let preview = engine.preview_block_sound(&block(content), None).unwrap();
+        assert!(!preview.played);
+        assert!(preview.drop_sound);
+
+        let preview = engine
+            .preview_block_sound(&block("Show\n\tDisableDropSound True\n"), None)
+            .unwrap();
+
+        assert_eq!(preview.sound, None);
+        assert!(!preview.drop_sound);
+    }
+}
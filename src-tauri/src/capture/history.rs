@@ -0,0 +1,264 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ts_rs::TS;
+
+use super::item::Item;
+use super::CapturedItem;
+use crate::filter::matcher::{match_item, ItemMatch};
+use crate::filter::parser::blocks::Block;
+
+/// Name of the file in the app data directory that the capture history is saved to.
+pub const HISTORY_FILE: &str = "capture_history.json";
+
+/// A captured item kept in the history.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct HistoryEntry {
+    pub id: u32,
+    // Description as copied by the game
+    pub text: String,
+    pub item: Item,
+    // Milliseconds since the Unix epoch
+    #[ts(type = "number")]
+    pub captured_at: u64,
+    // Pinned entries are never removed to make room for new ones
+    pub pinned: bool,
+    // Level of the area the item was captured in, if known
+    #[serde(default)]
+    pub area_level: Option<u32>,
+    // What the filter did with the item when it was captured or last re-evaluated,
+    // `None` if no filter was opened
+    pub matched: Option<ItemMatch>,
+}
+
+/// Every item captured from the game, oldest first.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CaptureHistory {
+    next_id: u32,
+    entries: Vec<HistoryEntry>,
+}
+
+impl CaptureHistory {
+    /// Loads the history from a JSON file. Returns an empty history if there is no file yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(CaptureHistory::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read capture history {}", path.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid capture history {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write capture history {}", path.display()))
+    }
+
+    /// Adds an item captured in an area of `area_level` at the current time and returns
+    /// the new entry.
+    pub fn add(
+        &mut self,
+        captured: CapturedItem,
+        area_level: Option<u32>,
+        matched: Option<ItemMatch>,
+    ) -> HistoryEntry {
+        let captured_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_millis() as u64)
+            .unwrap_or_default();
+
+        let entry = HistoryEntry {
+            id: self.next_id,
+            text: captured.text,
+            item: captured.item,
+            captured_at,
+            pinned: false,
+            area_level,
+            matched,
+        };
+
+        self.next_id += 1;
+        self.entries.push(entry.clone());
+        entry
+    }
+
+    /// Returns the entries, pinned first and newest first.
+    pub fn list(&self) -> Vec<&HistoryEntry> {
+        let mut entries: Vec<&HistoryEntry> = self.entries.iter().rev().collect();
+        entries.sort_by_key(|entry| !entry.pinned);
+        entries
+    }
+
+    /// Returns the entries whose description contains every word of the query, ignoring
+    /// the case, in the order of [`CaptureHistory::list`].
+    pub fn search(&self, query: &str) -> Vec<&HistoryEntry> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+        self.list()
+            .into_iter()
+            .filter(|entry| {
+                let text = entry.text.to_lowercase();
+                words.iter().all(|word| text.contains(word))
+            })
+            .collect()
+    }
+
+    pub fn set_pinned(&mut self, id: u32, pinned: bool) -> Result<()> {
+        match self.entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => entry.pinned = pinned,
+            None => bail!("Capture {} was not found", id),
+        }
+
+        Ok(())
+    }
+
+    pub fn delete(&mut self, id: u32) -> Result<()> {
+        let count = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+
+        if self.entries.len() == count {
+            bail!("Capture {} was not found", id);
+        }
+
+        Ok(())
+    }
+
+    /// Matches every entry against the blocks again, after the filter was edited or
+    /// another filter was opened. Returns the number of entries whose match changed.
+    pub fn reevaluate(&mut self, blocks: &[Block]) -> usize {
+        let mut changed = 0;
+
+        for entry in &mut self.entries {
+            let matched = Some(match_item(blocks, &entry.item, entry.area_level));
+
+            if entry.matched != matched {
+                entry.matched = matched;
+                changed += 1;
+            }
+        }
+
+        changed
+    }
+
+    /// Removes the oldest entries that are not pinned above `max_entries` entries.
+    pub fn remove_oldest(&mut self, max_entries: usize) {
+        let mut excess = self.entries.len().saturating_sub(max_entries);
+
+        self.entries.retain(|entry| {
+            let remove = excess > 0 && !entry.pinned;
+
+            if remove {
+                excess -= 1;
+            }
+
+            !remove
+        });
+    }
+}
+
+/// Holds the capture history in Tauri state.
+pub struct CaptureHistoryState(pub Mutex<CaptureHistory>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::parse_file::parse_content;
+    use tempfile::tempdir;
+
+    fn captured(base_type: &str) -> CapturedItem {
+        let text = format!("Item Class: Currency\nRarity: Currency\n{}\n", base_type);
+        let item = Item::parse(&text).unwrap();
+        CapturedItem { text, item }
+    }
+
+    fn base_types(entries: Vec<&HistoryEntry>) -> Vec<&str> {
+        entries
+            .into_iter()
+            .map(|entry| entry.item.base_type.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = CaptureHistory::default();
+        let chaos = history.add(captured("Chaos Orb"), None, None);
+        history.add(captured("Divine Orb"), None, None);
+        history.add(captured("Orb of Chance"), None, None);
+        history.set_pinned(chaos.id, true).unwrap();
+
+        let all = ["Chaos Orb", "Orb of Chance", "Divine Orb"];
+        assert_eq!(base_types(history.list()), all);
+        let found = ["Chaos Orb", "Orb of Chance"];
+        assert_eq!(base_types(history.search("orb CHA")), found);
+
+        history.delete(chaos.id).unwrap();
+        assert!(history.delete(chaos.id).is_err());
+        assert!(history.set_pinned(chaos.id, false).is_err());
+        assert_eq!(base_types(history.list()), ["Orb of Chance", "Divine Orb"]);
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(HISTORY_FILE);
+        history.save(&path).unwrap();
+        assert_eq!(CaptureHistory::load(&path).unwrap(), history);
+    }
+
+    #[test]
+    fn test_pinned_entries_are_kept() {
+        let mut history = CaptureHistory::default();
+        let first = history.add(captured("Chaos Orb"), None, None);
+        history.set_pinned(first.id, true).unwrap();
+
+        for _ in 0..10 {
+            history.add(captured("Divine Orb"), None, None);
+        }
+
+        assert_eq!(history.list().len(), 11);
+        history.remove_oldest(5);
+
+        let list = history.list();
+        assert_eq!(list.len(), 5);
+        assert_eq!(list[0].id, first.id);
+        assert_eq!(list[1].id, 10);
+        assert_eq!(list[4].id, 7);
+    }
+
+    #[test]
+    fn test_reevaluate() {
+        let mut history = CaptureHistory::default();
+        history.add(captured("Chaos Orb"), None, None);
+        history.add(captured("Divine Orb"), None, None);
+
+        let filter = parse_content("Hide\n\tBaseType \"Chaos Orb\"\n").unwrap();
+        assert_eq!(history.reevaluate(&filter.blocks), 2);
+        assert_eq!(history.reevaluate(&filter.blocks), 0);
+
+        let list = history.list();
+        assert!(list[0].matched.as_ref().unwrap().visible);
+        assert!(!list[1].matched.as_ref().unwrap().visible);
+    }
+
+    #[test]
+    fn test_reevaluate_with_area_level() {
+        let mut history = CaptureHistory::default();
+        history.add(captured("Chaos Orb"), Some(70), None);
+        history.add(captured("Chaos Orb"), None, None);
+
+        let content = "Hide\n\tAreaLevel >= 65\n\tBaseType \"Chaos Orb\"\n";
+        let filter = parse_content(content).unwrap();
+        history.reevaluate(&filter.blocks);
+
+        let list = history.list();
+        assert!(list[0].matched.as_ref().unwrap().visible);
+        assert!(!list[1].matched.as_ref().unwrap().visible);
+    }
+}
@@ -0,0 +1,402 @@
+use ts_rs::TS;
+
+/// Line that separates the sections of an item description.
+const SEPARATOR: &str = "--------";
+
+/// Keys of the `key: value` lines of property sections, such as `Item Level: 75`.
+///
+/// Modifiers can have the same form, such as `Grants Skill: Level 11 Lightning Bolt`, so
+/// only these keys make a section a property section.
+const PROPERTY_KEYS: &[&str] = &[
+    "Item Level",
+    "Quality",
+    "Stack Size",
+    "Map Tier",
+    "Waystone Tier",
+    "Sockets",
+    "Requires",
+    "Level",
+    "Str",
+    "Dex",
+    "Int",
+    "Armour",
+    "Evasion Rating",
+    "Energy Shield",
+    "Ward",
+    "Chance to Block",
+    "Block chance",
+    "Physical Damage",
+    "Elemental Damage",
+    "Chaos Damage",
+    "Critical Strike Chance",
+    "Critical Hit Chance",
+    "Attacks per Second",
+    "Weapon Range",
+    "Reload Time",
+    "Spirit",
+    "Item Quantity",
+    "Item Rarity",
+    "Monster Pack Size",
+    "Area Level",
+    "Limited to",
+    "Radius",
+    "Experience",
+    "Cost",
+    "Mana Cost",
+    "Cast Time",
+    "Cooldown Time",
+    "Note",
+];
+
+/// An item parsed from the description the game copies to the clipboard.
+///
+/// Both the normal format (Ctrl+C) and the advanced format (Ctrl+Alt+C), which adds a
+/// `{ ... }` header with the name and tier above every modifier, are supported.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct Item {
+    pub class: String,
+    pub rarity: String,
+    // Name of rare and unique items, such as `Dire Loop`
+    pub name: Option<String>,
+    pub base_type: String,
+    pub item_level: Option<u32>,
+    pub quality: Option<u32>,
+    pub stack_size: Option<u32>,
+    // Tier of maps and waystones
+    pub map_tier: Option<u32>,
+    pub sockets: Option<String>,
+    pub corrupted: bool,
+    pub mirrored: bool,
+    pub identified: bool,
+    pub modifiers: Vec<ItemModifier>,
+    // The description was copied with the advanced format
+    pub advanced: bool,
+}
+
+/// A modifier line of an item.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct ItemModifier {
+    // Text without the value ranges of the advanced format, such as `+27 to maximum Life`
+    pub text: String,
+    // `Prefix`, `Suffix`, `Implicit` and so on, only known in the advanced format
+    pub kind: Option<String>,
+    // Name of the affix, such as `Hale`, only known in the advanced format
+    pub name: Option<String>,
+    // Tier or rank of the affix, only known in the advanced format
+    pub tier: Option<u32>,
+}
+
+impl Item {
+    /// Parses an item description. Returns `None` if the text is not an item description,
+    /// which starts with the `Item Class` line.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.replace("\r\n", "\n");
+        let sections: Vec<Vec<&str>> = text
+            .split(SEPARATOR)
+            .map(lines)
+            .filter(|lines| !lines.is_empty())
+            .collect();
+
+        let (header, rest) = sections.split_first()?;
+        let class = header.first()?.strip_prefix("Item Class: ")?;
+
+        let mut item = Item {
+            class: class.to_string(),
+            identified: true,
+            advanced: text.lines().any(|line| line.trim_start().starts_with("{ ")),
+            ..Item::default()
+        };
+
+        let mut names = Vec::new();
+
+        for line in &header[1..] {
+            match line.strip_prefix("Rarity: ") {
+                Some(rarity) => item.rarity = rarity.to_string(),
+                None => names.push(line.to_string()),
+            }
+        }
+
+        // Rare and unique items have a name above the base type
+        item.base_type = names.pop().unwrap_or_default();
+        item.name = names.pop();
+
+        for section in rest {
+            item.read_section(section);
+        }
+
+        Some(item)
+    }
+
+    fn read_section(&mut self, lines: &[&str]) {
+        let mut is_modifier_section = true;
+        let mut header = None;
+        let mut modifiers = Vec::new();
+
+        for line in lines {
+            let property = line.split_once(": ");
+
+            if let Some((key, value)) = property.filter(|(key, _)| PROPERTY_KEYS.contains(key)) {
+                is_modifier_section = false;
+                self.read_property(key, value);
+                continue;
+            }
+
+            match *line {
+                "Corrupted" => self.corrupted = true,
+                "Mirrored" => self.mirrored = true,
+                "Unidentified" => self.identified = false,
+                _ if line.starts_with("{ ") => header = Some(*line),
+                // Title of a list of properties, such as `Requirements:`
+                _ if line.ends_with(':') => is_modifier_section = false,
+                _ => modifiers.push(modifier(header.take(), line)),
+            }
+        }
+
+        if is_modifier_section {
+            self.modifiers.extend(modifiers);
+        }
+    }
+
+    fn read_property(&mut self, key: &str, value: &str) {
+        match key {
+            "Item Level" => self.item_level = number(value),
+            "Quality" => self.quality = number(value),
+            "Stack Size" => self.stack_size = number(value.split('/').next().unwrap_or("")),
+            "Map Tier" | "Waystone Tier" => self.map_tier = number(value),
+            "Sockets" => self.sockets = Some(value.to_string()),
+            _ => {}
+        }
+    }
+}
+
+/// Returns the non-empty lines of a section without surrounding whitespace.
+fn lines(section: &str) -> Vec<&str> {
+    section
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Reads the first number of the value, such as 20 from `+20% (augmented)` or 1234 from
+/// `1,234`.
+fn number(value: &str) -> Option<u32> {
+    let digits: String = value
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit() || *c == ',')
+        .filter(char::is_ascii_digit)
+        .collect();
+
+    digits.parse().ok()
+}
+
+/// Creates a modifier from its line and the advanced format header above it, such as
+/// `{ Prefix Modifier "Hale" (Tier: 8) — Life }`.
+fn modifier(header: Option<&str>, line: &str) -> ItemModifier {
+    let mut modifier = ItemModifier {
+        text: remove_ranges(line),
+        ..ItemModifier::default()
+    };
+
+    let Some(header) = header else {
+        return modifier;
+    };
+
+    let header = header.trim_start_matches("{ ").trim_end_matches(" }");
+    let description = header.split(" — ").next().unwrap_or(header);
+
+    modifier.kind = description
+        .split_once(" Modifier")
+        .map(|(kind, _)| kind.to_string());
+
+    modifier.name = description.split('"').nth(1).map(str::to_string);
+
+    modifier.tier = ["(Tier: ", "(Rank: "]
+        .iter()
+        .find_map(|prefix| description.split_once(prefix))
+        .and_then(|(_, tier)| number(tier));
+
+    modifier
+}
+
+/// Removes the value ranges of the advanced format, `+27(25-29) to maximum Life` becomes
+/// `+27 to maximum Life`.
+fn remove_ranges(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find('(') {
+        let end = rest[start..].find(')').map(|end| start + end);
+
+        let is_range = end.is_some_and(|end| is_range(&rest[start + 1..end]));
+
+        match (is_range, end) {
+            (true, Some(end)) => {
+                text.push_str(&rest[..start]);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                text.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+
+    text.push_str(rest);
+    text
+}
+
+/// Returns true for the value range in the parentheses of `+27(25-29)`.
+fn is_range(text: &str) -> bool {
+    let is_range_char = |c: char| c.is_ascii_digit() || c == '-' || c == '.';
+    !text.is_empty() && text.chars().all(is_range_char)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RING: &str = "\
+Item Class: Rings
+Rarity: Rare
+Dire Loop
+Ruby Ring
+--------
+Quality: +5% (augmented)
+--------
+Requires: Level 30
+--------
+Item Level: 75
+--------
++12% to Fire Resistance (implicit)
+--------
++27 to maximum Life
++8% to Cold Resistance
+--------
+Corrupted
+";
+
+    const ADVANCED_RING: &str = "\
+Item Class: Rings
+Rarity: Rare
+Dire Loop
+Ruby Ring
+--------
+Item Level: 75
+--------
+{ Implicit Modifier — Elemental, Fire, Resistance }
++12(10-15)% to Fire Resistance (implicit)
+--------
+{ Prefix Modifier \"Hale\" (Tier: 8) — Life }
++27(25-29) to maximum Life
+{ Suffix Modifier \"of the Seal\" (Tier: 6) — Elemental, Cold, Resistance }
++8(6-10)% to Cold Resistance
+";
+
+    #[test]
+    fn test_parse_item() {
+        let item = Item::parse(RING).unwrap();
+        assert_eq!(item.class, "Rings");
+        assert_eq!(item.rarity, "Rare");
+        assert_eq!(item.name.as_deref(), Some("Dire Loop"));
+        assert_eq!(item.base_type, "Ruby Ring");
+        assert_eq!(item.item_level, Some(75));
+        assert_eq!(item.quality, Some(5));
+        assert!(item.corrupted);
+        assert!(item.identified);
+        assert!(!item.advanced);
+
+        let texts: Vec<&str> = item.modifiers.iter().map(|m| m.text.as_str()).collect();
+
+        assert_eq!(
+            texts,
+            [
+                "+12% to Fire Resistance (implicit)",
+                "+27 to maximum Life",
+                "+8% to Cold Resistance"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_advanced_item() {
+        let item = Item::parse(&ADVANCED_RING.replace('\n', "\r\n")).unwrap();
+        assert!(item.advanced);
+        assert_eq!(item.modifiers.len(), 3);
+
+        let life = &item.modifiers[1];
+        assert_eq!(life.text, "+27 to maximum Life");
+        assert_eq!(life.kind.as_deref(), Some("Prefix"));
+        assert_eq!(life.name.as_deref(), Some("Hale"));
+        assert_eq!(life.tier, Some(8));
+
+        let implicit = &item.modifiers[0];
+        assert_eq!(implicit.text, "+12% to Fire Resistance (implicit)");
+        assert_eq!(implicit.kind.as_deref(), Some("Implicit"));
+        assert_eq!(implicit.name, None);
+    }
+
+    #[test]
+    fn test_parse_currency() {
+        let text = "\
+Item Class: Stackable Currency
+Rarity: Currency
+Chaos Orb
+--------
+Stack Size: 1,234/5,000
+--------
+Right click this item then left click on a rare item to apply it.
+";
+
+        let item = Item::parse(text).unwrap();
+        assert_eq!(item.base_type, "Chaos Orb");
+        assert_eq!(item.name, None);
+        assert_eq!(item.stack_size, Some(1234));
+    }
+
+    #[test]
+    fn test_modifiers_with_colons() {
+        let text = "\
+Item Class: Jewels
+Rarity: Magic
+Small Cluster Jewel
+--------
+Requirements:
+Level: 54
+--------
+Item Level: 84
+--------
+Adds 2 Passive Skills (enchant)
+Added Small Passive Skills grant: 12% increased Trap Damage (enchant)
+--------
+Grants Skill: Level 11 Lightning Bolt
++8% to Cold Resistance
+--------
+Note: ~price 1 chaos
+";
+
+        let item = Item::parse(text).unwrap();
+        assert_eq!(item.item_level, Some(84));
+
+        let texts: Vec<&str> = item.modifiers.iter().map(|m| m.text.as_str()).collect();
+
+        assert_eq!(
+            texts,
+            [
+                "Adds 2 Passive Skills (enchant)",
+                "Added Small Passive Skills grant: 12% increased Trap Damage (enchant)",
+                "Grants Skill: Level 11 Lightning Bolt",
+                "+8% to Cold Resistance"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_not_an_item() {
+        assert_eq!(Item::parse("Hello"), None);
+        assert_eq!(Item::parse(""), None);
+    }
+}
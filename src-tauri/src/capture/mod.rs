@@ -0,0 +1,308 @@
+pub mod history;
+pub mod item;
+pub mod system;
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+use item::Item;
+
+/// Name of the file in the app config directory that the capture settings are saved to.
+pub const SETTINGS_FILE: &str = "capture.json";
+
+/// Text access to the system clipboard.
+pub trait ClipboardAccess {
+    /// Returns the text in the clipboard, or `None` if there is no text.
+    fn get_text(&mut self) -> Option<String>;
+    fn set_text(&mut self, text: &str) -> Result<()>;
+    fn clear(&mut self) -> Result<()>;
+}
+
+/// Sends the key presses that make the game copy the item under the cursor.
+pub trait KeySender {
+    /// Sends Ctrl+C, or Ctrl+Alt+C for the advanced item description.
+    fn send_copy(&mut self, advanced: bool) -> Result<()>;
+}
+
+/// How an item is copied from the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureOptions {
+    // Time the game has to put the item description into the clipboard
+    pub timeout: Duration,
+    pub poll_interval: Duration,
+    // Copy the advanced description with the tiers of the modifiers
+    pub advanced: bool,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        CaptureOptions {
+            timeout: Duration::from_millis(500),
+            poll_interval: Duration::from_millis(10),
+            advanced: false,
+        }
+    }
+}
+
+/// An item copied from the game.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct CapturedItem {
+    // Description as copied by the game
+    pub text: String,
+    pub item: Item,
+}
+
+/// Shortcuts and timing of item captures, saved in the app config directory.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct CaptureSettings {
+    // Global shortcut that copies the item under the cursor, such as `CommandOrControl+1`
+    pub shortcut: String,
+    // Global shortcut that copies the advanced description of the item under the cursor
+    pub advanced_shortcut: Option<String>,
+    // Time the game has to put the item description into the clipboard
+    pub timeout_ms: u32,
+    // Level of the area items are captured in, to match `AreaLevel` conditions. The item
+    // description has no area level, so blocks with the condition never match without it
+    pub area_level: Option<u32>,
+    // Oldest captures that are not pinned are removed above this many captures,
+    // `None` keeps every capture
+    pub max_history_entries: Option<u32>,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        CaptureSettings {
+            shortcut: "CommandOrControl+1".to_string(),
+            advanced_shortcut: None,
+            timeout_ms: 500,
+            area_level: None,
+            max_history_entries: None,
+        }
+    }
+}
+
+impl CaptureSettings {
+    /// Loads the settings from a JSON file. Returns the default settings if there is
+    /// no file yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(CaptureSettings::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read capture settings {}", path.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid capture settings {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write capture settings {}", path.display()))
+    }
+
+    pub fn options(&self, advanced: bool) -> CaptureOptions {
+        CaptureOptions {
+            timeout: Duration::from_millis(self.timeout_ms.into()),
+            advanced,
+            ..CaptureOptions::default()
+        }
+    }
+}
+
+/// Holds the capture settings in Tauri state.
+pub struct CaptureState(pub Mutex<CaptureSettings>);
+
+/// Copies the item under the cursor and restores the previous clipboard text afterwards.
+///
+/// The clipboard is cleared before the copy is sent and polled until the game puts the
+/// description into it or the timeout passes. Returns `None` if nothing was copied or
+/// the copied text is not an item description. Clipboard contents other than text, such
+/// as images, are not restored.
+pub fn capture(
+    clipboard: &mut impl ClipboardAccess,
+    keys: &mut impl KeySender,
+    options: &CaptureOptions,
+) -> Result<Option<CapturedItem>> {
+    let previous = clipboard.get_text();
+    clipboard.clear()?;
+
+    let copied = keys
+        .send_copy(options.advanced)
+        .map(|_| wait_for_text(clipboard, options));
+
+    match &previous {
+        Some(text) => clipboard.set_text(text)?,
+        None => clipboard.clear()?,
+    }
+
+    let Some(text) = copied? else {
+        return Ok(None);
+    };
+
+    Ok(Item::parse(&text).map(|item| CapturedItem { text, item }))
+}
+
+/// Polls the clipboard until it contains text or the timeout passes.
+fn wait_for_text(clipboard: &mut impl ClipboardAccess, options: &CaptureOptions) -> Option<String> {
+    let start = Instant::now();
+
+    loop {
+        if let Some(text) = clipboard.get_text().filter(|text| !text.is_empty()) {
+            return Some(text);
+        }
+
+        if start.elapsed() >= options.timeout {
+            return None;
+        }
+
+        std::thread::sleep(options.poll_interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use tempfile::tempdir;
+
+    const ITEM: &str = "Item Class: Stackable Currency\nRarity: Currency\nChaos Orb\n";
+
+    /// Clipboard of a game that copies `copied` a few polls after the copy keys.
+    #[derive(Default)]
+    struct Game {
+        clipboard: Option<String>,
+        copied: Option<String>,
+        polls_until_copied: usize,
+        sent: Vec<bool>,
+    }
+
+    struct FakeClipboard(Rc<RefCell<Game>>);
+    struct FakeKeys(Rc<RefCell<Game>>);
+
+    impl ClipboardAccess for FakeClipboard {
+        fn get_text(&mut self) -> Option<String> {
+            let mut game = self.0.borrow_mut();
+
+            if !game.sent.is_empty() && game.copied.is_some() {
+                match game.polls_until_copied {
+                    0 => game.clipboard = game.copied.take(),
+                    _ => game.polls_until_copied -= 1,
+                }
+            }
+
+            game.clipboard.clone()
+        }
+
+        fn set_text(&mut self, text: &str) -> Result<()> {
+            self.0.borrow_mut().clipboard = Some(text.to_string());
+            Ok(())
+        }
+
+        fn clear(&mut self) -> Result<()> {
+            self.0.borrow_mut().clipboard = None;
+            Ok(())
+        }
+    }
+
+    impl KeySender for FakeKeys {
+        fn send_copy(&mut self, advanced: bool) -> Result<()> {
+            self.0.borrow_mut().sent.push(advanced);
+            Ok(())
+        }
+    }
+
+    fn run(game: Game, options: &CaptureOptions) -> (Option<CapturedItem>, Game) {
+        let game = Rc::new(RefCell::new(game));
+        let mut clipboard = FakeClipboard(game.clone());
+        let mut keys = FakeKeys(game.clone());
+        let captured = capture(&mut clipboard, &mut keys, options).unwrap();
+        drop((clipboard, keys));
+        (captured, Rc::into_inner(game).unwrap().into_inner())
+    }
+
+    fn options(advanced: bool) -> CaptureOptions {
+        CaptureOptions {
+            timeout: Duration::from_millis(50),
+            poll_interval: Duration::from_millis(1),
+            advanced,
+        }
+    }
+
+    #[test]
+    fn test_capture_restores_clipboard() {
+        let game = Game {
+            clipboard: Some("my notes".to_string()),
+            copied: Some(ITEM.to_string()),
+            polls_until_copied: 3,
+            ..Game::default()
+        };
+
+        let (captured, game) = run(game, &options(true));
+        let captured = captured.unwrap();
+        assert_eq!(captured.text, ITEM);
+        assert_eq!(captured.item.base_type, "Chaos Orb");
+        assert_eq!(game.sent, [true]);
+        assert_eq!(game.clipboard.as_deref(), Some("my notes"));
+    }
+
+    #[test]
+    fn test_capture_timeout() {
+        let game = Game {
+            clipboard: Some("my notes".to_string()),
+            ..Game::default()
+        };
+
+        let (captured, game) = run(game, &options(false));
+        assert_eq!(captured, None);
+        assert_eq!(game.sent, [false]);
+        assert_eq!(game.clipboard.as_deref(), Some("my notes"));
+    }
+
+    #[test]
+    fn test_capture_ignores_other_text() {
+        let game = Game {
+            copied: Some("selected text of another window".to_string()),
+            ..Game::default()
+        };
+
+        let (captured, game) = run(game, &options(false));
+        assert_eq!(captured, None);
+        assert_eq!(game.clipboard, None);
+    }
+
+    #[test]
+    fn test_settings() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config").join(SETTINGS_FILE);
+        let default = CaptureSettings::default();
+        assert_eq!(CaptureSettings::load(&path).unwrap(), default);
+
+        let settings = CaptureSettings {
+            advanced_shortcut: Some("CommandOrControl+2".to_string()),
+            timeout_ms: 300,
+            area_level: Some(68),
+            max_history_entries: Some(500),
+            ..CaptureSettings::default()
+        };
+
+        settings.save(&path).unwrap();
+        assert_eq!(CaptureSettings::load(&path).unwrap(), settings);
+
+        std::fs::write(&path, r#"{"timeout_ms": 200}"#).unwrap();
+        let loaded = CaptureSettings::load(&path).unwrap();
+        assert_eq!(loaded.shortcut, default.shortcut);
+        assert_eq!(loaded.options(true).timeout, Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Context, Result};
+use arboard::Clipboard;
+use enigo::{
+    Direction::{Click, Press, Release},
+    Enigo, Key, Keyboard, Settings,
+};
+
+use super::{ClipboardAccess, KeySender};
+
+/// The clipboard of the system.
+pub struct SystemClipboard(Clipboard);
+
+impl SystemClipboard {
+    pub fn new() -> Result<Self> {
+        let clipboard = Clipboard::new().context("Failed to access the clipboard")?;
+        Ok(SystemClipboard(clipboard))
+    }
+}
+
+impl ClipboardAccess for SystemClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.0.get_text().ok()
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<()> {
+        Ok(self.0.set_text(text)?)
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        Ok(self.0.clear()?)
+    }
+}
+
+/// Sends key presses to the focused window.
+pub struct SystemKeys(Enigo);
+
+impl SystemKeys {
+    pub fn new() -> Result<Self> {
+        let enigo = Enigo::new(&Settings::default())
+            .map_err(|e| anyhow!("Failed to access the keyboard: {}", e))?;
+
+        Ok(SystemKeys(enigo))
+    }
+}
+
+impl KeySender for SystemKeys {
+    fn send_copy(&mut self, advanced: bool) -> Result<()> {
+        let control = match cfg!(target_os = "macos") {
+            true => Key::Meta,
+            false => Key::Control,
+        };
+
+        let modifiers = match advanced {
+            true => vec![control, Key::Alt],
+            false => vec![control],
+        };
+
+        for key in &modifiers {
+            self.0.key(*key, Press)?;
+        }
+
+        let copied = self.0.key(Key::Unicode('c'), Click);
+
+        // The modifiers are released even if the copy failed, so they don't stay pressed
+        for key in modifiers.iter().rev() {
+            self.0.key(*key, Release)?;
+        }
+
+        Ok(copied?)
+    }
+}
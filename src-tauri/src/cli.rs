@@ -0,0 +1,363 @@
+use anyhow::{bail, Result};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::filter::coverage::{coverage_report, CoverageReport};
+use crate::filter::generator::{generate_items, BaseTypeDatabase};
+use crate::filter::matcher::ItemMatch;
+use crate::filter::parser::blocks::Block;
+use crate::filter::regression::{
+    compare_filters, load_corpus, load_filter, CorpusItem, ItemChange, RegressionReport,
+};
+
+const USAGE: &str = "\
+Usage:
+  filturd
+      Starts the app
+  filturd regression <before filter> <after filter> <corpus> [--area-level <level>]
+      Lists the items of the corpus that look different with the after filter, the corpus
+      is a folder or a file of copied item descriptions or a JSON list of items. Items
+      without an area level are matched in an area of the given level
+  filturd coverage <filter> [<base types>]
+      Lists the blocks that no generated item matches and the generated items that no
+      block matches or that are hidden, the items are generated from the bundled base
+      types or a JSON file in the same format";
+
+/// Runs the subcommand in `args`, the command line arguments without the program name.
+///
+/// Returns the exit code, or `None` if there is no subcommand and the app should start.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, args) = args.split_first()?;
+
+    let command: fn(&[String]) -> Result<i32> = match command.as_str() {
+        "regression" => regression,
+        "coverage" => coverage,
+        "help" | "--help" | "-h" => help,
+        _ => return None,
+    };
+
+    attach_console();
+
+    match command(args) {
+        Ok(code) => Some(code),
+        Err(e) => {
+            eprintln!("{:#}\n\n{}", e, USAGE);
+            Some(2)
+        }
+    }
+}
+
+/// Attaches the process to the console of the shell that started it.
+///
+/// Release builds on Windows are GUI apps without a console, so the output of the
+/// subcommands would be lost otherwise.
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // Fails if the process already has a console or the parent has none, which is fine
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+fn help(_args: &[String]) -> Result<i32> {
+    println!("{}", USAGE);
+    Ok(0)
+}
+
+/// Prints the regression report. The exit code is 1 if an item changed, so that scripts
+/// can stop on changes.
+fn regression(args: &[String]) -> Result<i32> {
+    let (args, area_level) = area_level_option(args)?;
+
+    let [before, after, corpus] = args.as_slice() else {
+        bail!("regression needs two filters and a corpus");
+    };
+
+    let before = load_filter(Path::new(before))?;
+    let after = load_filter(Path::new(after))?;
+    let mut corpus = load_corpus(Path::new(corpus))?;
+
+    for corpus_item in &mut corpus {
+        corpus_item.area_level = corpus_item.area_level.or(area_level);
+    }
+
+    let report = compare_filters(&before.blocks, &after.blocks, &corpus);
+    print!("{}", format_report(&report));
+
+    Ok(match report.changes.is_empty() {
+        true => 0,
+        false => 1,
+    })
+}
+
+/// Prints the coverage report of the filter for items generated from the base types.
+fn coverage(args: &[String]) -> Result<i32> {
+    let (filter, database) = match args {
+        [filter] => (filter, BaseTypeDatabase::bundled()),
+        [filter, database] => (filter, BaseTypeDatabase::load(Path::new(database))?),
+        _ => bail!("coverage needs a filter and optionally base types"),
+    };
+
+    let filter = load_filter(Path::new(filter))?;
+    let report = coverage_report(&filter.blocks, &generate_items(&database));
+    print!("{}", format_coverage(&report));
+    Ok(0)
+}
+
+/// Removes the `--area-level <level>` option from the arguments and returns its level.
+fn area_level_option(args: &[String]) -> Result<(Vec<&String>, Option<u32>)> {
+    let Some(index) = args.iter().position(|arg| arg == "--area-level") else {
+        return Ok((args.iter().collect(), None));
+    };
+
+    let Some(level) = args.get(index + 1) else {
+        bail!("--area-level needs a level");
+    };
+
+    let Ok(level) = level.parse() else {
+        bail!("Invalid area level {}", level);
+    };
+
+    let mut rest: Vec<&String> = args.iter().collect();
+    rest.drain(index..index + 2);
+    Ok((rest, Some(level)))
+}
+
+/// Formats the report as text, a paragraph for each changed item.
+pub fn format_report(report: &RegressionReport) -> String {
+    let changed = report.changes.len();
+    let mut text = format!("{} of {} items changed\n", changed, report.items);
+
+    for change in &report.changes {
+        text.push('\n');
+        format_change(&mut text, change);
+    }
+
+    text
+}
+
+fn format_change(text: &mut String, change: &ItemChange) {
+    let (before, after) = (&change.before, &change.after);
+    let _ = writeln!(text, "{} ({})", change.name, change.item.base_type);
+    let _ = writeln!(text, "  {} -> {}", describe(before), describe(after));
+
+    let names: BTreeSet<&String> = before.actions.keys().chain(after.actions.keys()).collect();
+
+    for name in names {
+        let old = before.actions.get(name);
+        let new = after.actions.get(name);
+
+        if old != new {
+            let _ = writeln!(text, "  {}: {} -> {}", name, params(old), params(new));
+        }
+    }
+}
+
+fn describe(matched: &ItemMatch) -> String {
+    let visibility = match matched.visible {
+        true => "shown",
+        false => "hidden",
+    };
+
+    match matched.order {
+        Some(order) => format!("{} by block {}", visibility, order),
+        None => format!("{} by no block", visibility),
+    }
+}
+
+fn params(params: Option<&Vec<String>>) -> String {
+    params
+        .map(|params| params.join(" "))
+        .unwrap_or_else(|| "none".to_string())
+}
+
+/// Formats the coverage report as text, with a paragraph for the unused blocks, the
+/// unmatched items and the hidden items if there are any.
+pub fn format_coverage(report: &CoverageReport) -> String {
+    let mut text = format!(
+        "{} items, {} blocks never used, {} items matched by no block, {} items hidden\n",
+        report.items,
+        report.unused_blocks.len(),
+        report.unmatched.len(),
+        report.hidden.len(),
+    );
+
+    if !report.unused_blocks.is_empty() {
+        text.push_str("\nBlocks never used:\n");
+
+        for block in &report.unused_blocks {
+            let _ = writeln!(text, "  {}", describe_block(block));
+        }
+    }
+
+    format_items(&mut text, "Items matched by no block", &report.unmatched);
+    format_items(&mut text, "Items hidden", &report.hidden);
+    text
+}
+
+/// Describes a block by its position, keyword and tags, as in `block 3 Show $tier->t1`.
+fn describe_block(block: &Block) -> String {
+    let mut text = format!("block {} {}", block.order, block.name);
+
+    for tag in &block.tags {
+        let separator = if tag.name.starts_with('$') { "->" } else { "" };
+        let _ = write!(text, " {}{}{}", tag.name, separator, tag.value);
+    }
+
+    text
+}
+
+fn format_items(text: &mut String, title: &str, items: &[CorpusItem]) {
+    if items.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(text, "\n{}:", title);
+
+    for item in items {
+        let _ = writeln!(text, "  {}", item.name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_regression() {
+        let dir = tempdir().unwrap();
+        let before = "Show\n\tBaseType \"Chaos Orb\"\n\tSetFontSize 40\n";
+        let after = "Hide\n\tBaseType \"Chaos Orb\"\n";
+        let item = "Item Class: Currency\nRarity: Currency\nChaos Orb\n";
+        let files = [
+            ("before.filter", before),
+            ("after.filter", after),
+            ("drops.txt", item),
+        ];
+
+        let mut args = vec!["regression".to_string()];
+
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            args.push(path.to_string_lossy().to_string());
+        }
+
+        assert_eq!(run(&args), Some(1));
+
+        let before = load_filter(Path::new(&args[1])).unwrap();
+        let after = load_filter(Path::new(&args[2])).unwrap();
+        let corpus = load_corpus(Path::new(&args[3])).unwrap();
+        let report = compare_filters(&before.blocks, &after.blocks, &corpus);
+
+        let expected = "\
+1 of 1 items changed
+
+drops.txt (Chaos Orb)
+  shown by block 1 -> hidden by block 1
+  SetFontSize: 40 -> none
+";
+
+        assert_eq!(format_report(&report), expected);
+
+        let unchanged = compare_filters(&before.blocks, &before.blocks, &corpus);
+        assert_eq!(format_report(&unchanged), "0 of 1 items changed\n");
+    }
+
+    #[test]
+    fn test_regression_area_level() {
+        let dir = tempdir().unwrap();
+        let before = "Hide\n\tAreaLevel >= 65\n\tBaseType \"Chaos Orb\"\nShow\n";
+        let item = "Item Class: Currency\nRarity: Currency\nChaos Orb\n";
+        let files = [
+            ("before.filter", before),
+            ("after.filter", "Show\n"),
+            ("drops.txt", item),
+        ];
+
+        let mut args = vec!["regression".to_string()];
+
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            args.push(path.to_string_lossy().to_string());
+        }
+
+        // Blocks with an `AreaLevel` condition don't match without an area level
+        assert_eq!(run(&args), Some(0));
+
+        args.splice(1..1, ["--area-level".to_string(), "70".to_string()]);
+        assert_eq!(run(&args), Some(1));
+
+        args[2] = "high".to_string();
+        assert_eq!(run(&args), Some(2));
+        assert_eq!(run(&args[..2]), Some(2));
+    }
+
+    #[test]
+    fn test_coverage() {
+        let dir = tempdir().unwrap();
+        let filter = "\
+Show # $type->rings
+\tClass \"Rings\"
+\tRarity Rare
+Hide
+\tRarity Normal
+Show
+\tClass \"Amulets\"
+";
+
+        let database = r#"{"classes": [{
+            "name": "Rings",
+            "rarities": ["Normal", "Magic"],
+            "corruptible": true,
+            "base_types": [{"name": "Breach Ring", "drop_level": 82}]
+        }]}"#;
+
+        let filter_path = dir.path().join("coverage.filter");
+        let database_path = dir.path().join("base_types.json");
+        std::fs::write(&filter_path, filter).unwrap();
+        std::fs::write(&database_path, database).unwrap();
+
+        let args = [Path::new("coverage"), &filter_path, &database_path]
+            .map(|arg| arg.to_string_lossy().to_string());
+
+        assert_eq!(run(&args), Some(0));
+
+        let filter = load_filter(&filter_path).unwrap();
+        let database = BaseTypeDatabase::load(&database_path).unwrap();
+        let report = coverage_report(&filter.blocks, &generate_items(&database));
+
+        let expected = "\
+4 items, 2 blocks never used, 2 items matched by no block, 2 items hidden
+
+Blocks never used:
+  block 1 Show $type->rings
+  block 3 Show
+
+Items matched by no block:
+  Breach Ring, Magic, item level 82
+  Breach Ring, Magic, item level 82, corrupted
+
+Items hidden:
+  Breach Ring, Normal, item level 82
+  Breach Ring, Normal, item level 82, corrupted
+";
+
+        assert_eq!(format_coverage(&report), expected);
+    }
+
+    #[test]
+    fn test_no_subcommand() {
+        assert_eq!(run(&[]), None);
+        assert_eq!(run(&["--some-app-flag".to_string()]), None);
+        assert_eq!(run(&["regression".to_string()]), Some(2));
+    }
+}
@@ -0,0 +1,125 @@
+use super::capture_history::{history_path, record_capture};
+use crate::capture::history::{CaptureHistory, CaptureHistoryState};
+use crate::capture::system::{SystemClipboard, SystemKeys};
+use crate::capture::{capture, CaptureSettings, CaptureState, SETTINGS_FILE};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+/// Event emitted with the `HistoryEntry` of an item copied with a capture shortcut.
+pub const ITEM_CAPTURED_EVENT: &str = "item-captured";
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+/// Loads a file of an app directory, or returns the default value if that fails.
+fn load_or_default<T: Default>(
+    path: Result<PathBuf, String>,
+    load: fn(&Path) -> anyhow::Result<T>,
+) -> T {
+    match path.and_then(|path| load(&path).map_err(|e| format!("{:#}", e))) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("{}", e);
+            T::default()
+        }
+    }
+}
+
+/// Loads the capture settings and history into app state and registers the shortcuts.
+pub fn setup_capture(app: &AppHandle) -> Result<(), String> {
+    let settings = load_or_default(settings_path(app), CaptureSettings::load);
+    let history = load_or_default(history_path(app), CaptureHistory::load);
+    app.manage(CaptureState(Mutex::new(settings.clone())));
+    app.manage(CaptureHistoryState(Mutex::new(history)));
+    register_shortcuts(app, &settings)
+}
+
+/// Returns the shortcuts of the settings, with true for the advanced copy.
+fn shortcuts(settings: &CaptureSettings) -> Vec<(String, bool)> {
+    let advanced = settings.advanced_shortcut.clone().map(|s| (s, true));
+    std::iter::once((settings.shortcut.clone(), false))
+        .chain(advanced)
+        .collect()
+}
+
+fn register_shortcuts(app: &AppHandle, settings: &CaptureSettings) -> Result<(), String> {
+    for (shortcut, advanced) in shortcuts(settings) {
+        app.global_shortcut()
+            .on_shortcut(shortcut.as_str(), move |app, _, event| {
+                if event.state() == ShortcutState::Pressed {
+                    capture_in_background(app.clone(), advanced);
+                }
+            })
+            .map_err(|e| format!("Failed to register shortcut {}: {}", shortcut, e))?;
+    }
+
+    Ok(())
+}
+
+fn unregister_shortcuts(app: &AppHandle, settings: &CaptureSettings) {
+    for (shortcut, _) in shortcuts(settings) {
+        let _ = app.global_shortcut().unregister(shortcut.as_str());
+    }
+}
+
+/// Copies the item under the cursor without blocking the shortcut handler, adds it to
+/// the history and emits it to the frontend.
+fn capture_in_background(app: AppHandle, advanced: bool) {
+    std::thread::spawn(move || {
+        let state = app.state::<CaptureState>();
+        let options = state.0.lock().unwrap().options(advanced);
+
+        let captured = SystemClipboard::new().and_then(|mut clipboard| {
+            let mut keys = SystemKeys::new()?;
+            capture(&mut clipboard, &mut keys, &options)
+        });
+
+        match captured {
+            Ok(Some(captured)) => match record_capture(&app, captured) {
+                Ok(entry) => {
+                    if let Err(e) = app.emit(ITEM_CAPTURED_EVENT, entry) {
+                        eprintln!("Failed to emit captured item: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("Failed to record captured item: {}", e),
+            },
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to capture item: {:#}", e),
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_capture_settings(
+    state: State<'_, CaptureState>,
+) -> Result<CaptureSettings, String> {
+    Ok(state.0.lock().unwrap().clone())
+}
+
+/// Replaces the capture shortcuts and saves the settings.
+///
+/// The previous shortcuts stay registered if one of the new shortcuts is invalid or
+/// already taken.
+#[tauri::command]
+pub async fn set_capture_settings(
+    settings: CaptureSettings,
+    app: AppHandle,
+    state: State<'_, CaptureState>,
+) -> Result<(), String> {
+    let mut current = state.0.lock().unwrap();
+    unregister_shortcuts(&app, &current);
+
+    if let Err(e) = register_shortcuts(&app, &settings) {
+        unregister_shortcuts(&app, &settings);
+        register_shortcuts(&app, &current)?;
+        return Err(e);
+    }
+
+    let path = settings_path(&app)?;
+    *current = settings;
+    current.save(&path).map_err(|e| e.to_string())
+}
@@ -0,0 +1,101 @@
+use crate::capture::history::{CaptureHistory, CaptureHistoryState, HistoryEntry, HISTORY_FILE};
+use crate::capture::{CaptureState, CapturedItem};
+use crate::filter::document::FilterDocumentState;
+use crate::filter::matcher::match_item;
+use crate::filter::parser::blocks::Block;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+
+pub fn history_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(HISTORY_FILE))
+}
+
+/// Returns the blocks of the opened filter with the blocks of its imports.
+///
+/// The blocks of the filter file alone are returned if an import can't be read.
+pub fn opened_filter_blocks(state: &FilterDocumentState) -> Option<Vec<Block>> {
+    let mut document = state.0.lock().unwrap();
+    let document = document.as_mut()?;
+
+    Some(match document.view() {
+        Ok(view) => view.filter.blocks,
+        Err(_) => document.filter().blocks.clone(),
+    })
+}
+
+/// Runs `action` with the history and saves the history afterwards.
+fn update_history<T, F>(app: &AppHandle, action: F) -> Result<T, String>
+where
+    F: FnOnce(&mut CaptureHistory) -> anyhow::Result<T>,
+{
+    let state = app.state::<CaptureHistoryState>();
+    let mut history = state.0.lock().unwrap();
+    let path = history_path(app)?;
+    let result = action(&mut history).map_err(|e| e.to_string())?;
+    history.save(&path).map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// Matches a captured item against the opened filter, in an area of the level of the
+/// capture settings, and adds it to the history, keeping at most the number of entries
+/// of the capture settings.
+pub fn record_capture(app: &AppHandle, captured: CapturedItem) -> Result<HistoryEntry, String> {
+    let settings = app.state::<CaptureState>().0.lock().unwrap().clone();
+    let area_level = settings.area_level;
+    let blocks = opened_filter_blocks(&app.state::<FilterDocumentState>());
+    let matched = blocks.map(|blocks| match_item(&blocks, &captured.item, area_level));
+
+    update_history(app, |history| {
+        let entry = history.add(captured, area_level, matched);
+
+        if let Some(max_entries) = settings.max_history_entries {
+            history.remove_oldest(max_entries as usize);
+        }
+
+        Ok(entry)
+    })
+}
+
+/// Lists the captured items, pinned first and newest first.
+#[tauri::command]
+pub async fn list_capture_history(
+    state: State<'_, CaptureHistoryState>,
+) -> Result<Vec<HistoryEntry>, String> {
+    let history = state.0.lock().unwrap();
+    Ok(history.list().into_iter().cloned().collect())
+}
+
+/// Lists the captured items whose description contains every word of the query.
+#[tauri::command]
+pub async fn search_capture_history(
+    query: String,
+    state: State<'_, CaptureHistoryState>,
+) -> Result<Vec<HistoryEntry>, String> {
+    let history = state.0.lock().unwrap();
+    Ok(history.search(&query).into_iter().cloned().collect())
+}
+
+#[tauri::command]
+pub async fn pin_capture(id: u32, pinned: bool, app: AppHandle) -> Result<(), String> {
+    update_history(&app, |history| history.set_pinned(id, pinned))
+}
+
+#[tauri::command]
+pub async fn delete_capture(id: u32, app: AppHandle) -> Result<(), String> {
+    update_history(&app, |history| history.delete(id))
+}
+
+/// Matches every captured item against the opened filter again.
+///
+/// Returns the number of items whose match changed.
+#[tauri::command]
+pub async fn reevaluate_captures(
+    app: AppHandle,
+    document: State<'_, FilterDocumentState>,
+) -> Result<usize, String> {
+    let blocks =
+        opened_filter_blocks(&document).ok_or_else(|| "No filter is opened".to_string())?;
+
+    update_history(&app, |history| Ok(history.reevaluate(&blocks)))
+}
@@ -0,0 +1,26 @@
+use super::capture_history::opened_filter_blocks;
+use crate::filter::coverage::{coverage_report, CoverageReport};
+use crate::filter::document::FilterDocumentState;
+use crate::filter::generator::{generate_items, BaseTypeDatabase};
+use std::path::PathBuf;
+use tauri::State;
+
+/// Matches items generated from a base type database with the opened filter and its
+/// unsaved edits.
+///
+/// `base_types` defaults to the database bundled with the app.
+#[tauri::command]
+pub async fn filter_coverage_report(
+    base_types: Option<PathBuf>,
+    document: State<'_, FilterDocumentState>,
+) -> Result<CoverageReport, String> {
+    let blocks =
+        opened_filter_blocks(&document).ok_or_else(|| "No filter is opened".to_string())?;
+
+    let database = match base_types {
+        Some(path) => BaseTypeDatabase::load(&path).map_err(|e| e.to_string())?,
+        None => BaseTypeDatabase::bundled(),
+    };
+
+    Ok(coverage_report(&blocks, &generate_items(&database)))
+}
@@ -0,0 +1,78 @@
+use crate::filter::document::bulk_edit::{BulkEdit, BulkEditPreview};
+use crate::filter::document::edit_command::EditCommand;
+use crate::filter::document::{FilterDocument, FilterDocumentState, FilterDocumentView};
+use anyhow::Result;
+use tauri::State;
+
+/// Runs `action` on the opened document and returns its new state.
+fn with_document<F>(state: &FilterDocumentState, action: F) -> Result<FilterDocumentView, String>
+where
+    F: FnOnce(&mut FilterDocument) -> Result<()>,
+{
+    let mut document = state.0.lock().unwrap();
+
+    let document = document
+        .as_mut()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    action(document)
+        .and_then(|_| document.view())
+        .map_err(|e| e.to_string())
+}
+
+/// Applies an edit to the opened filter. The change is saved to disk by `save_filter`.
+#[tauri::command]
+pub async fn edit_filter(
+    command: EditCommand,
+    state: State<'_, FilterDocumentState>,
+) -> Result<FilterDocumentView, String> {
+    with_document(&state, |document| document.apply(&command))
+}
+
+/// Returns the blocks that the bulk edit would change, without changing the filter.
+#[tauri::command]
+pub async fn preview_bulk_edit(
+    edit: BulkEdit,
+    state: State<'_, FilterDocumentState>,
+) -> Result<BulkEditPreview, String> {
+    let document = state.0.lock().unwrap();
+
+    let document = document
+        .as_ref()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    document.preview_bulk_edit(&edit).map_err(|e| e.to_string())
+}
+
+/// Applies the bulk edit to all selected blocks as one undoable step.
+#[tauri::command]
+pub async fn apply_bulk_edit(
+    edit: BulkEdit,
+    state: State<'_, FilterDocumentState>,
+) -> Result<FilterDocumentView, String> {
+    with_document(&state, |document| {
+        document.apply_bulk_edit(&edit).map(|_| ())
+    })
+}
+
+#[tauri::command]
+pub async fn undo_filter_edit(
+    state: State<'_, FilterDocumentState>,
+) -> Result<FilterDocumentView, String> {
+    with_document(&state, |document| document.undo().map(|_| ()))
+}
+
+#[tauri::command]
+pub async fn redo_filter_edit(
+    state: State<'_, FilterDocumentState>,
+) -> Result<FilterDocumentView, String> {
+    with_document(&state, |document| document.redo().map(|_| ()))
+}
+
+/// Writes the opened filter to its file.
+#[tauri::command]
+pub async fn save_filter(
+    state: State<'_, FilterDocumentState>,
+) -> Result<FilterDocumentView, String> {
+    with_document(&state, |document| document.save())
+}
@@ -0,0 +1,28 @@
+use crate::filter::document::strictness::{neversink_rules, StrictnessRule};
+use crate::filter::document::FilterDocumentState;
+use crate::filter::parser::metadata::Strictness;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Writes the opened filter at another strictness level to `path`.
+///
+/// The rules default to the `%H` and `%D` tags of NeverSink filters.
+#[tauri::command]
+pub async fn export_strictness_variant(
+    path: PathBuf,
+    strictness: Strictness,
+    rules: Option<Vec<StrictnessRule>>,
+    state: State<'_, FilterDocumentState>,
+) -> Result<(), String> {
+    let document = state.0.lock().unwrap();
+
+    let document = document
+        .as_ref()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    let rules = rules.unwrap_or_else(neversink_rules);
+
+    document
+        .export_strictness_variant(&path, strictness, &rules)
+        .map_err(|e| e.to_string())
+}
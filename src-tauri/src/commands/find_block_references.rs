@@ -0,0 +1,21 @@
+use crate::filter::document::FilterDocumentState;
+use crate::filter::parser::reference_index::{find_references, BlockReference, ReferenceKind};
+use tauri::State;
+
+/// Lists the blocks of the opened filter that reference the base type or class `name`.
+#[tauri::command]
+pub async fn find_block_references(
+    kind: ReferenceKind,
+    name: String,
+    state: State<'_, FilterDocumentState>,
+) -> Result<Vec<BlockReference>, String> {
+    let mut document = state.0.lock().unwrap();
+
+    let document = document
+        .as_mut()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    let filter = document.view().map_err(|e| e.to_string())?.filter;
+    let references = find_references(&filter.blocks, &filter.reference_index, kind, &name);
+    Ok(references)
+}
@@ -0,0 +1,24 @@
+use crate::audio::lint::{lint_custom_sounds, SoundLintReport};
+use crate::filter::document::FilterDocumentState;
+use tauri::State;
+
+/// Checks the custom sound files used by the opened filter and its imports.
+#[tauri::command]
+pub async fn lint_sound_files(
+    state: State<'_, FilterDocumentState>,
+) -> Result<SoundLintReport, String> {
+    let mut document = state.0.lock().unwrap();
+
+    let document = document
+        .as_mut()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    let view = document.view().map_err(|e| e.to_string())?;
+
+    let filter_dir = document
+        .path()
+        .and_then(|path| path.parent())
+        .ok_or_else(|| "The filter has no file".to_string())?;
+
+    Ok(lint_custom_sounds(&view.filter, filter_dir))
+}
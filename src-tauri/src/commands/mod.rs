@@ -1,4 +1,17 @@
-pub mod copy_item_desc;
+pub mod capture;
+pub mod capture_history;
+pub mod coverage;
+pub mod edit_filter;
+pub mod export_strictness_variant;
+pub mod find_block_references;
 pub mod greet;
+pub mod lint_sound_files;
 pub mod open_file;
 pub mod play_sound;
+pub mod preview_block_sound;
+pub mod regression;
+pub mod render_icons;
+pub mod render_label;
+pub mod search_blocks;
+pub mod sound_packs;
+pub mod theme;
@@ -1,10 +1,15 @@
-use crate::filter::parser::parse_file::parse_file;
+use crate::filter::document::{FilterDocument, FilterDocumentState};
+use crate::filter::parser::parse_file::ParsedFilter;
 use crate::filter::parser::blocks::Block;
+use crate::filter::watcher::{
+    filter_files, FilterReload, FilterWatcher, FilterWatcherState, FILTER_RELOADED_EVENT,
+};
 use std::path::Path;
 use std::fs::File;
 use std::io::Write;
 use rand::seq::SliceRandom;
 use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 fn save_sample_json(blocks: Vec<Block>) -> Result<(), Box<dyn std::error::Error>> {
     let mut rng = rand::rng();
@@ -20,20 +25,107 @@ fn save_sample_json(blocks: Vec<Block>) -> Result<(), Box<dyn std::error::Error>
     Ok(())
 }
 
+/// Reloads the opened document after one of its files changed on disk.
+///
+/// Returns the reload to send to the frontend and the files of the reloaded filter.
+/// The reload is `None` if neither the file nor its imports changed, which happens
+/// after the document is saved.
+fn reload_document(path: &Path, app: &AppHandle) -> (Option<FilterReload>, Vec<PathBuf>) {
+    let document_state = app.state::<FilterDocumentState>();
+    let mut document = document_state.0.lock().unwrap();
+
+    let Some(document) = document.as_mut() else {
+        return (None, Vec::new());
+    };
+
+    let result = document.reload().and_then(|reloaded| match reloaded {
+        true => document.view().map(Some),
+        false => Ok(None),
+    });
+
+    let reload = FilterReload {
+        path: path.to_string_lossy().to_string(),
+        filter: None,
+        error: None,
+    };
+
+    match result {
+        Ok(Some(view)) => {
+            let files = filter_files(path, &view.filter).map(Path::to_path_buf);
+            let files = files.collect();
+
+            let reload = FilterReload {
+                filter: Some(view.filter),
+                ..reload
+            };
+
+            (Some(reload), files)
+        }
+        Ok(None) => (None, Vec::new()),
+        Err(e) => {
+            let reload = FilterReload {
+                error: Some(e.to_string()),
+                ..reload
+            };
+
+            (Some(reload), Vec::new())
+        }
+    }
+}
+
+/// Starts watching the opened filter file, replacing the watcher of the previously opened file.
+fn watch_filter(
+    path: &Path,
+    filter: &ParsedFilter,
+    app: AppHandle,
+    watcher_state: &FilterWatcherState,
+) {
+    let reload_path = path.to_path_buf();
+
+    let watcher = FilterWatcher::start(path, filter, move |_| {
+        let (reload, files) = reload_document(&reload_path, &app);
+
+        if let Some(reload) = reload {
+            if let Err(e) = app.emit(FILTER_RELOADED_EVENT, reload) {
+                eprintln!("Failed to emit filter reload: {}", e);
+            }
+        }
+
+        files
+    });
+
+    let watcher = match watcher {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            eprintln!("Failed to watch filter file: {}", e);
+            None
+        }
+    };
+
+    *watcher_state.0.lock().unwrap() = watcher;
+}
+
 #[tauri::command]
-pub async fn open_file(path: &str) -> Result<Vec<Block>, ()> {
+pub async fn open_file(
+    path: &str,
+    app: AppHandle,
+    watcher_state: State<'_, FilterWatcherState>,
+    document_state: State<'_, FilterDocumentState>,
+) -> Result<ParsedFilter, String> {
     let start = std::time::Instant::now();
-    let result = parse_file(Path::new(path)).await;
+    let result = FilterDocument::open(Path::new(path)).await;
     let duration = start.elapsed();
-    println!("parse_file() took {:?}", duration);
-    assert!(result.is_ok());
-    let blocks = result.unwrap();
+    println!("FilterDocument::open() took {:?}", duration);
+    let mut document = result.map_err(|e| e.to_string())?;
+    let filter = document.view().map_err(|e| e.to_string())?.filter;
 
-    if blocks.len() == 3234 {
-        if let Err(e) = save_sample_json(blocks.clone()) {
+    if filter.blocks.len() == 3234 {
+        if let Err(e) = save_sample_json(filter.blocks.clone()) {
             eprintln!("Failed to save sample JSON: {}", e);
         }
     }
 
-    Ok(blocks)
+    *document_state.0.lock().unwrap() = Some(document);
+    watch_filter(Path::new(path), &filter, app, &watcher_state);
+    Ok(filter)
 }
@@ -1,14 +1,22 @@
-use std::io::BufReader;
-use std::path::Path;
+use crate::audio::AudioState;
+use tauri::State;
 
+/// Starts playing a sound bundled with the app, such as `camera_snap1.mp3`.
 #[tauri::command]
-pub async fn play_sound(file: String) -> Result<(), ()> {
-    let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
-    let sink = rodio::Sink::try_new(&handle).unwrap();
-    let path = Path::new("resources/sounds/").join(file);
-    let file = std::fs::File::open(path).unwrap();
-    let source = rodio::Decoder::new(BufReader::new(file)).unwrap();
-    sink.append(source);
-    sink.sleep_until_end();
+pub async fn play_sound(file: String, state: State<'_, AudioState>) -> Result<(), String> {
+    state.0.play_bundled(&file, 1.0).map_err(|e| e.to_string())
+}
+
+/// Stops all sounds that are playing.
+#[tauri::command]
+pub async fn stop_sounds(state: State<'_, AudioState>) -> Result<(), String> {
+    state.0.stop();
+    Ok(())
+}
+
+/// Sets the volume of the sounds played by the app, between 0 and 1.
+#[tauri::command]
+pub async fn set_sound_volume(volume: f32, state: State<'_, AudioState>) -> Result<(), String> {
+    state.0.set_volume(volume);
     Ok(())
 }
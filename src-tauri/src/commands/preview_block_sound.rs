@@ -0,0 +1,33 @@
+use crate::audio::preview::BlockSoundPreview;
+use crate::audio::AudioState;
+use crate::filter::document::FilterDocumentState;
+use crate::filter::parser::block_id::BlockId;
+use tauri::State;
+
+/// Plays the alert sound of a block of the opened filter, including unsaved changes.
+#[tauri::command]
+pub async fn preview_block_sound(
+    id: BlockId,
+    document: State<'_, FilterDocumentState>,
+    audio: State<'_, AudioState>,
+) -> Result<BlockSoundPreview, String> {
+    let document = document.0.lock().unwrap();
+
+    let document = document
+        .as_ref()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    let block = document
+        .filter()
+        .blocks
+        .iter()
+        .find(|block| block.id == id)
+        .ok_or_else(|| format!("Block {} was not found", id.0))?;
+
+    let filter_dir = document.path().and_then(|path| path.parent());
+
+    audio
+        .0
+        .preview_block_sound(block, filter_dir)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,34 @@
+use super::capture_history::opened_filter_blocks;
+use crate::capture::history::CaptureHistoryState;
+use crate::filter::document::FilterDocumentState;
+use crate::filter::parser::parse_file::parse_file;
+use crate::filter::regression::{compare_filters, history_corpus, load_corpus, RegressionReport};
+use std::path::PathBuf;
+use tauri::State;
+
+/// Lists the items of a corpus that look different with the `after` filter.
+///
+/// `after` defaults to the opened filter with its unsaved edits and `corpus` defaults to
+/// the capture history.
+#[tauri::command]
+pub async fn item_regression_report(
+    before: PathBuf,
+    after: Option<PathBuf>,
+    corpus: Option<PathBuf>,
+    document: State<'_, FilterDocumentState>,
+    history: State<'_, CaptureHistoryState>,
+) -> Result<RegressionReport, String> {
+    let before = parse_file(&before).await.map_err(|e| e.to_string())?;
+
+    let after = match after {
+        Some(path) => parse_file(&path).await.map_err(|e| e.to_string())?.blocks,
+        None => opened_filter_blocks(&document).ok_or_else(|| "No filter is opened".to_string())?,
+    };
+
+    let corpus = match corpus {
+        Some(path) => load_corpus(&path).map_err(|e| e.to_string())?,
+        None => history_corpus(&history.0.lock().unwrap()),
+    };
+
+    Ok(compare_filters(&before.blocks, &after, &corpus))
+}
@@ -0,0 +1,46 @@
+use crate::filter::theme::{Effect, MinimapIcon};
+use crate::render::beam::{all_beams, render_beam, BeamImage};
+use crate::render::minimap::{all_minimap_icons, render_minimap_icon, MinimapIconImage};
+use crate::render::{encode_png, png_data_url};
+use tiny_skia::Pixmap;
+
+fn data_url(pixmap: anyhow::Result<Pixmap>) -> Result<String, String> {
+    let png = pixmap.and_then(|pixmap| encode_png(&pixmap));
+    png.map(|png| png_data_url(&png)).map_err(|e| e.to_string())
+}
+
+/// Renders a `MinimapIcon` as a PNG `data:` URL.
+#[tauri::command]
+pub async fn render_minimap_icon_preview(icon: MinimapIcon) -> Result<String, String> {
+    data_url(render_minimap_icon(&icon))
+}
+
+/// Renders the beam of a `PlayEffect` as a PNG `data:` URL.
+#[tauri::command]
+pub async fn render_beam_preview(effect: Effect) -> Result<String, String> {
+    data_url(render_beam(&effect))
+}
+
+/// Renders every shape of minimap icon in every color and size.
+#[tauri::command]
+pub async fn list_minimap_icon_images() -> Result<Vec<MinimapIconImage>, String> {
+    all_minimap_icons()
+        .into_iter()
+        .map(|icon| {
+            let url = data_url(render_minimap_icon(&icon))?;
+            Ok(MinimapIconImage { icon, url })
+        })
+        .collect()
+}
+
+/// Renders the beam of every color, lasting and `Temp`.
+#[tauri::command]
+pub async fn list_beam_images() -> Result<Vec<BeamImage>, String> {
+    all_beams()
+        .into_iter()
+        .map(|effect| {
+            let url = data_url(render_beam(&effect))?;
+            Ok(BeamImage { effect, url })
+        })
+        .collect()
+}
@@ -0,0 +1,55 @@
+use crate::filter::document::FilterDocumentState;
+use crate::filter::parser::block_id::BlockId;
+use crate::render::label::{render_label, LabelStyle};
+use crate::render::{encode_png, png_data_url};
+use std::path::PathBuf;
+use tauri::State;
+
+/// Returns the style of a block of the opened filter, including unsaved changes.
+fn block_label_style(id: BlockId, state: &FilterDocumentState) -> Result<LabelStyle, String> {
+    let document = state.0.lock().unwrap();
+
+    let document = document
+        .as_ref()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    let block = document
+        .filter()
+        .blocks
+        .iter()
+        .find(|block| block.id == id)
+        .ok_or_else(|| format!("Block {} was not found", id.0))?;
+
+    Ok(LabelStyle::for_block(block))
+}
+
+fn label_png(style: &LabelStyle) -> Result<Vec<u8>, String> {
+    encode_png(&render_label(style)).map_err(|e| e.to_string())
+}
+
+/// Renders the label of an item with the style as a PNG `data:` URL.
+#[tauri::command]
+pub async fn render_label_preview(style: LabelStyle) -> Result<String, String> {
+    Ok(png_data_url(&label_png(&style)?))
+}
+
+/// Renders the label of a block of the opened filter as a PNG `data:` URL.
+#[tauri::command]
+pub async fn render_block_label(
+    id: BlockId,
+    document: State<'_, FilterDocumentState>,
+) -> Result<String, String> {
+    let style = block_label_style(id, &document)?;
+    Ok(png_data_url(&label_png(&style)?))
+}
+
+/// Saves the label of a block of the opened filter as a PNG file.
+#[tauri::command]
+pub async fn export_block_label(
+    id: BlockId,
+    path: PathBuf,
+    document: State<'_, FilterDocumentState>,
+) -> Result<(), String> {
+    let style = block_label_style(id, &document)?;
+    std::fs::write(&path, label_png(&style)?).map_err(|e| e.to_string())
+}
@@ -0,0 +1,23 @@
+use crate::filter::document::FilterDocumentState;
+use crate::filter::query::error::QueryError;
+use crate::filter::query::search;
+use tauri::State;
+
+/// Returns the orders of the blocks of the opened filter that match the query.
+#[tauri::command]
+pub async fn search_blocks(
+    query: String,
+    state: State<'_, FilterDocumentState>,
+) -> Result<Vec<usize>, QueryError> {
+    let mut document = state.0.lock().unwrap();
+
+    let Some(document) = document.as_mut() else {
+        return Ok(Vec::new());
+    };
+
+    let view = document
+        .view()
+        .map_err(|e| QueryError::new(e.to_string(), 0..0))?;
+
+    search(&view.filter.blocks, &query)
+}
@@ -0,0 +1,89 @@
+use crate::audio::AudioState;
+use crate::filter::document::{FilterDocument, FilterDocumentState};
+use crate::filter::theme::Theme;
+use crate::sounds::bundle::{export_bundle, BundleReport};
+use crate::sounds::{import_pack, list_packs, tier_sounds, use_pack, SoundPack, TierSound};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Runs `action` with the opened document and the directory of its file.
+fn with_filter_dir<T, F>(state: &FilterDocumentState, action: F) -> Result<T, String>
+where
+    F: FnOnce(&FilterDocument, &Path) -> anyhow::Result<T>,
+{
+    let document = state.0.lock().unwrap();
+
+    let document = document
+        .as_ref()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    let filter_dir = document
+        .path()
+        .and_then(|path| path.parent())
+        .ok_or_else(|| "The filter has no file".to_string())?;
+
+    action(document, filter_dir).map_err(|e| e.to_string())
+}
+
+/// Lists the built-in sound pack and the packs imported into the filter directory.
+#[tauri::command]
+pub async fn list_sound_packs(
+    document: State<'_, FilterDocumentState>,
+    audio: State<'_, AudioState>,
+) -> Result<Vec<SoundPack>, String> {
+    with_filter_dir(&document, |_, filter_dir| {
+        list_packs(audio.0.sounds_dir(), filter_dir)
+    })
+}
+
+/// Imports a folder or a `.zip` file of sounds into the filter directory.
+#[tauri::command]
+pub async fn import_sound_pack(
+    source: PathBuf,
+    document: State<'_, FilterDocumentState>,
+) -> Result<SoundPack, String> {
+    with_filter_dir(&document, |_, filter_dir| import_pack(&source, filter_dir))
+}
+
+/// Points the custom sounds of the theme file at the files of the pack.
+///
+/// Returns the number of changed tier styles.
+#[tauri::command]
+pub async fn use_sound_pack(
+    theme_path: PathBuf,
+    pack: SoundPack,
+    document: State<'_, FilterDocumentState>,
+) -> Result<usize, String> {
+    with_filter_dir(&document, |_, filter_dir| {
+        let mut theme = Theme::load(&theme_path)?;
+        let count = use_pack(&mut theme, &pack, filter_dir)?;
+        theme.save(&theme_path)?;
+        Ok(count)
+    })
+}
+
+/// Returns the sound of each tier of the opened filter.
+#[tauri::command]
+pub async fn list_tier_sounds(
+    state: State<'_, FilterDocumentState>,
+) -> Result<Vec<TierSound>, String> {
+    let document = state.0.lock().unwrap();
+
+    let document = document
+        .as_ref()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    Ok(tier_sounds(&document.filter().blocks))
+}
+
+/// Writes the opened filter and its custom sounds to a zip file.
+#[tauri::command]
+pub async fn export_filter_bundle(
+    destination: PathBuf,
+    document: State<'_, FilterDocumentState>,
+) -> Result<BundleReport, String> {
+    with_filter_dir(&document, |document, _| {
+        let path = document.path().unwrap_or(Path::new("filter.filter"));
+        export_bundle(path, document.content(), document.format(), &destination)
+    })
+}
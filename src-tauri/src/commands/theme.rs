@@ -0,0 +1,40 @@
+use crate::filter::document::{FilterDocumentState, FilterDocumentView};
+use crate::filter::theme::Theme;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Re-styles the blocks of the opened filter with the theme file at `path`.
+#[tauri::command]
+pub async fn apply_theme(
+    path: PathBuf,
+    state: State<'_, FilterDocumentState>,
+) -> Result<FilterDocumentView, String> {
+    let theme = Theme::load(&path).map_err(|e| e.to_string())?;
+    let mut document = state.0.lock().unwrap();
+
+    let document = document
+        .as_mut()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    document
+        .apply_theme(&theme)
+        .and_then(|_| document.view())
+        .map_err(|e| e.to_string())
+}
+
+/// Writes the styles of the opened filter to a `.toml` or `.json` theme file.
+#[tauri::command]
+pub async fn export_theme(
+    path: PathBuf,
+    state: State<'_, FilterDocumentState>,
+) -> Result<(), String> {
+    let document = state.0.lock().unwrap();
+
+    let document = document
+        .as_ref()
+        .ok_or_else(|| "No filter is opened".to_string())?;
+
+    Theme::extract(&document.filter().blocks)
+        .save(&path)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+use ts_rs::TS;
+
+use crate::filter::matcher::applied_blocks;
+use crate::filter::parser::blocks::Block;
+use crate::filter::parser::blocks_with_lines::BlockName;
+use crate::filter::regression::CorpusItem;
+
+/// How the blocks of a filter handle the items of a corpus.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct CoverageReport {
+    // Number of items in the corpus
+    pub items: usize,
+    // Enabled blocks that no item matches, in the order of the filter
+    pub unused_blocks: Vec<Block>,
+    // Items that no block matches, shown as the game shows them without a filter
+    pub unmatched: Vec<CorpusItem>,
+    // Items that a `Hide` block matches
+    pub hidden: Vec<CorpusItem>,
+}
+
+/// Matches every item of the corpus with the blocks, usually items from
+/// [`generate_items`](crate::filter::generator::generate_items).
+///
+/// A block that applies its actions and continues counts as used, even if a block below
+/// decides the visibility of the item.
+pub fn coverage_report(blocks: &[Block], corpus: &[CorpusItem]) -> CoverageReport {
+    let mut used = HashSet::new();
+    let mut unmatched = Vec::new();
+    let mut hidden = Vec::new();
+
+    for corpus_item in corpus {
+        let applied = applied_blocks(blocks, &corpus_item.item, corpus_item.area_level);
+        used.extend(applied.iter().map(|block| block.id));
+
+        match applied.last() {
+            None => unmatched.push(corpus_item.clone()),
+            Some(block) if block.name == BlockName::Hide => hidden.push(corpus_item.clone()),
+            Some(_) => {}
+        }
+    }
+
+    let unused_blocks = blocks
+        .iter()
+        .filter(|block| block.enabled && !used.contains(&block.id))
+        .cloned()
+        .collect();
+
+    CoverageReport {
+        items: corpus.len(),
+        unused_blocks,
+        unmatched,
+        hidden,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::generator::{generate_items, BaseTypeDatabase};
+    use crate::filter::parser::parse_file::parse_content;
+
+    fn names(items: &[CorpusItem]) -> Vec<&str> {
+        items.iter().map(|item| item.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        let filter = parse_content(
+            "\
+Show
+\tRarity Unique
+\tSetFontSize 45
+\tContinue
+Hide
+\tClass \"Rings\"
+\tRarity Normal
+\tAreaLevel >= 65
+Show
+\tClass \"Rings\"
+Show
+\tBaseType \"Mirror of Kalandra\"
+#Show
+#\tClass \"Amulets\"
+",
+        )
+        .unwrap();
+
+        let database: BaseTypeDatabase = serde_json::from_str(
+            r#"{"classes": [
+                {
+                    "name": "Rings",
+                    "rarities": ["Normal", "Unique"],
+                    "base_types": [{"name": "Breach Ring", "drop_level": 62}]
+                },
+                {
+                    "name": "Amulets",
+                    "rarities": ["Rare"],
+                    "base_types": [{"name": "Solar Amulet", "drop_level": 75}]
+                }
+            ]}"#,
+        )
+        .unwrap();
+
+        let corpus = generate_items(&database);
+        let report = coverage_report(&filter.blocks, &corpus);
+        assert_eq!(report.items, 10);
+
+        assert_eq!(report.unused_blocks.len(), 1);
+        assert_eq!(report.unused_blocks[0].order, 4);
+
+        assert_eq!(
+            names(&report.unmatched),
+            [
+                "Solar Amulet, Rare, item level 75",
+                "Solar Amulet, Rare, item level 82"
+            ]
+        );
+
+        assert_eq!(
+            names(&report.hidden),
+            [
+                "Breach Ring, Normal, item level 65",
+                "Breach Ring, Normal, item level 75",
+                "Breach Ring, Normal, item level 82"
+            ]
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use anyhow::Result;
+use ts_rs::TS;
+
+use super::edit_command::EditCommand;
+use super::FilterDocument;
+use crate::filter::parser::block_id::BlockId;
+use crate::filter::parser::block_item::BlockItem;
+use crate::filter::parser::blocks::Block;
+use crate::filter::parser::sections::block_sections;
+
+/// Chooses the blocks changed by a bulk edit.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum BlockSelector {
+    /// Blocks with the tag, for example `$tier` with the value `t1`.
+    Tag { name: String, value: String },
+    /// Blocks with a condition or an action with the name. If `value` is set,
+    /// one of the parameters of the item must be equal to it, ignoring case.
+    Item { name: String, value: Option<String> },
+    /// Blocks under a section heading that contains the text, ignoring case.
+    Section { text: String },
+    /// Blocks matched by all the selectors.
+    All { selectors: Vec<BlockSelector> },
+}
+
+/// Change made to each selected block.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum BlockTransform {
+    /// Replaces the item with the same name or adds it to the block.
+    SetItem {
+        item: BlockItem,
+    },
+    /// Replaces the condition with the same name and operator or adds it to the block.
+    AddCondition {
+        item: BlockItem,
+    },
+    RemoveItem {
+        name: String,
+    },
+    SetEnabled {
+        enabled: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct BulkEdit {
+    pub selector: BlockSelector,
+    pub transform: BlockTransform,
+}
+
+/// Blocks changed by a bulk edit, before and after the change.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct BulkEditPreview {
+    pub changes: Vec<BlockChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct BlockChange {
+    pub before: Block,
+    pub after: Block,
+}
+
+impl BlockSelector {
+    /// Returns true if the block is selected. `section` is the heading of the block's section.
+    pub fn matches(&self, block: &Block, section: Option<&str>) -> bool {
+        match self {
+            BlockSelector::Tag { name, value } => block
+                .tags
+                .iter()
+                .any(|tag| tag.name == *name && tag.value == *value),
+            BlockSelector::Item { name, value } => {
+                let Some(items) = block.items.get(name.as_str()) else {
+                    return false;
+                };
+
+                let Some(value) = value else {
+                    return true;
+                };
+
+                items
+                    .iter()
+                    .flat_map(|item| &item.params)
+                    .any(|param| param.eq_ignore_ascii_case(value))
+            }
+            BlockSelector::Section { text } => {
+                section.is_some_and(|section| section.to_lowercase().contains(&text.to_lowercase()))
+            }
+            BlockSelector::All { selectors } => selectors
+                .iter()
+                .all(|selector| selector.matches(block, section)),
+        }
+    }
+}
+
+impl BlockTransform {
+    /// Returns the command that changes the block, or `None` if the block needs no change.
+    fn command(&self, block: &Block) -> Option<EditCommand> {
+        let id = block.id;
+
+        match self {
+            BlockTransform::SetItem { item } => {
+                let current = block.items.get(&item.name).and_then(|items| items.first());
+
+                (current != Some(item)).then(|| EditCommand::SetItem {
+                    id,
+                    item: item.clone(),
+                })
+            }
+            BlockTransform::AddCondition { item } => {
+                let items = block.items.get(&item.name);
+
+                (!items.is_some_and(|items| items.contains(item))).then(|| {
+                    EditCommand::AddCondition {
+                        id,
+                        item: item.clone(),
+                    }
+                })
+            }
+            BlockTransform::RemoveItem { name } => {
+                block
+                    .items
+                    .contains_key(name.as_str())
+                    .then(|| EditCommand::RemoveItem {
+                        id,
+                        name: name.clone(),
+                    })
+            }
+            BlockTransform::SetEnabled { enabled } => {
+                (block.enabled != *enabled).then_some(EditCommand::ToggleEnabled { id })
+            }
+        }
+    }
+}
+
+impl FilterDocument {
+    /// Returns the ids of the blocks matched by the selector, in file order.
+    pub fn select_blocks(&self, selector: &BlockSelector) -> Vec<BlockId> {
+        self.selected_blocks(selector)
+            .iter()
+            .map(|block| block.id)
+            .collect()
+    }
+
+    /// Returns the blocks that would be changed by the edit, without changing the document.
+    pub fn preview_bulk_edit(&self, edit: &BulkEdit) -> Result<BulkEditPreview> {
+        let (changed, commands): (Vec<&Block>, Vec<EditCommand>) =
+            self.bulk_edit_commands(edit).into_iter().unzip();
+
+        let content = self.content().to_string();
+        let mut copy = FilterDocument::with_content(None, self.format, content)?;
+        copy.parser.set_block_ids(&self.block_ids());
+        copy.apply(&EditCommand::Batch { commands })?;
+
+        let changes = changed
+            .into_iter()
+            .filter_map(|before| {
+                let after = copy.filter().blocks.iter().find(|b| b.id == before.id)?;
+
+                Some(BlockChange {
+                    before: before.clone(),
+                    after: after.clone(),
+                })
+            })
+            .collect();
+
+        Ok(BulkEditPreview { changes })
+    }
+
+    /// Applies the edit to all selected blocks as a single step of the undo history.
+    ///
+    /// Returns the number of changed blocks.
+    pub fn apply_bulk_edit(&mut self, edit: &BulkEdit) -> Result<usize> {
+        let commands: Vec<EditCommand> = self
+            .bulk_edit_commands(edit)
+            .into_iter()
+            .map(|(_, command)| command)
+            .collect();
+
+        let count = commands.len();
+        self.apply(&EditCommand::Batch { commands })?;
+        Ok(count)
+    }
+
+    fn selected_blocks(&self, selector: &BlockSelector) -> Vec<&Block> {
+        let blocks = &self.filter().blocks;
+        let sections = block_sections(self.content(), blocks);
+
+        blocks
+            .iter()
+            .zip(sections)
+            .filter(|(block, section)| selector.matches(block, *section))
+            .map(|(block, _)| block)
+            .collect()
+    }
+
+    /// Returns the selected blocks that need a change, with the commands that change them.
+    fn bulk_edit_commands(&self, edit: &BulkEdit) -> Vec<(&Block, EditCommand)> {
+        self.selected_blocks(&edit.selector)
+            .into_iter()
+            .filter_map(|block| Some((block, edit.transform.command(block)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::keywords::intern_keyword;
+
+    const CONTENT: &str = "\
+# [[0100]] Gold
+Show # $type->gold $tier->t1
+\tBaseType == \"Gold\"
+\tSetFontSize 45
+
+Show # $type->gold $tier->t2
+\tBaseType == \"Gold\"
+\tSetFontSize 40
+
+# [[0200]] Waystones
+Show # $type->waystones $tier->t1
+\tClass == \"Waystones\"
+\tSetFontSize 45
+";
+
+    fn document() -> FilterDocument {
+        FilterDocument::new(CONTENT.to_string()).unwrap()
+    }
+
+    fn item(name: &str, params: &[&str]) -> BlockItem {
+        BlockItem {
+            name: intern_keyword(name),
+            params: params.iter().map(|param| param.to_string()).collect(),
+        }
+    }
+
+    fn tier(value: &str) -> BlockSelector {
+        BlockSelector::Tag {
+            name: "$tier".to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_select_blocks() {
+        let document = document();
+        assert_eq!(
+            document.select_blocks(&tier("t1")),
+            [BlockId(1), BlockId(3)]
+        );
+
+        let font_size = BlockSelector::Item {
+            name: "SetFontSize".to_string(),
+            value: Some("40".to_string()),
+        };
+
+        assert_eq!(document.select_blocks(&font_size), [BlockId(2)]);
+
+        let section = BlockSelector::Section {
+            text: "waystones".to_string(),
+        };
+
+        assert_eq!(document.select_blocks(&section), [BlockId(3)]);
+
+        let all = BlockSelector::All {
+            selectors: vec![
+                tier("t1"),
+                BlockSelector::Section {
+                    text: "[[0100]]".to_string(),
+                },
+            ],
+        };
+
+        assert_eq!(document.select_blocks(&all), [BlockId(1)]);
+    }
+
+    #[test]
+    fn test_bulk_edit_is_one_undo_step() {
+        let mut document = document();
+
+        let edit = BulkEdit {
+            selector: tier("t1"),
+            transform: BlockTransform::SetItem {
+                item: item("PlayAlertSound", &["6", "300"]),
+            },
+        };
+
+        assert_eq!(document.apply_bulk_edit(&edit).unwrap(), 2);
+        let blocks = &document.filter().blocks;
+        assert!(blocks[0].items.contains_key("PlayAlertSound"));
+        assert!(!blocks[1].items.contains_key("PlayAlertSound"));
+        assert!(blocks[2].items.contains_key("PlayAlertSound"));
+
+        document.undo().unwrap();
+        assert_eq!(document.content(), CONTENT);
+        assert!(!document.can_undo());
+    }
+
+    #[test]
+    fn test_preview_bulk_edit() {
+        let document = document();
+
+        let edit = BulkEdit {
+            selector: BlockSelector::Item {
+                name: "SetFontSize".to_string(),
+                value: Some("45".to_string()),
+            },
+            transform: BlockTransform::SetItem {
+                item: item("SetFontSize", &["42"]),
+            },
+        };
+
+        let preview = document.preview_bulk_edit(&edit).unwrap();
+        assert_eq!(preview.changes.len(), 2);
+        let change = &preview.changes[1];
+        assert_eq!(change.before.id, BlockId(3));
+        assert_eq!(change.after.id, BlockId(3));
+        assert_eq!(
+            change.after.items["SetFontSize"],
+            [item("SetFontSize", &["42"])]
+        );
+        assert_eq!(document.content(), CONTENT);
+    }
+
+    #[test]
+    fn test_add_condition_to_blocks() {
+        let content = "\
+Show # $tier->t1
+\tAreaLevel <= 67
+\tBaseType == \"Gold\"
+
+Show # $tier->t1
+\tAreaLevel >= 65
+";
+
+        let mut document = FilterDocument::new(content.to_string()).unwrap();
+
+        let edit = BulkEdit {
+            selector: tier("t1"),
+            transform: BlockTransform::AddCondition {
+                item: item("AreaLevel", &[">=", "65"]),
+            },
+        };
+
+        assert_eq!(document.apply_bulk_edit(&edit).unwrap(), 1);
+
+        let area_levels = &document.filter().blocks[0].items["AreaLevel"];
+        let expected = [
+            item("AreaLevel", &["<=", "67"]),
+            item("AreaLevel", &[">=", "65"]),
+        ];
+        assert_eq!(area_levels, &expected);
+    }
+
+    #[test]
+    fn test_skip_blocks_that_need_no_change() {
+        let mut document = document();
+
+        let edit = BulkEdit {
+            selector: BlockSelector::Item {
+                name: "BaseType".to_string(),
+                value: None,
+            },
+            transform: BlockTransform::SetEnabled { enabled: true },
+        };
+
+        assert_eq!(document.apply_bulk_edit(&edit).unwrap(), 0);
+        assert!(!document.can_undo());
+    }
+}
@@ -0,0 +1,762 @@
+use anyhow::{anyhow, bail, Result};
+use ts_rs::TS;
+
+use super::FilterDocument;
+use crate::filter::matcher::split_operator;
+use crate::filter::parser::block_id::BlockId;
+use crate::filter::parser::block_item::{parse_block_item, BlockItem};
+use crate::filter::parser::blocks_with_lines::BlockName;
+use crate::filter::parser::incremental::TextEdit;
+use crate::filter::parser::lines::{content_to_lines, Line};
+use crate::filter::parser::parse_file::parse_content;
+use crate::filter::parser::span::Span;
+
+/// A change of the filter document that can be undone.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum EditCommand {
+    /// Inserts a block written as filter text before the block at `index`,
+    /// or after the last block if `index` is the number of blocks.
+    InsertBlock {
+        index: usize,
+        text: String,
+    },
+    DeleteBlock {
+        id: BlockId,
+    },
+    /// Moves the block so it ends up at `index`.
+    MoveBlock {
+        id: BlockId,
+        index: usize,
+    },
+    /// Inserts a copy of the block after it.
+    DuplicateBlock {
+        id: BlockId,
+    },
+    /// Replaces the first item line with the same name, or adds the item to the end of the block.
+    SetItem {
+        id: BlockId,
+        item: BlockItem,
+    },
+    /// Replaces the condition with the same name and operator, or adds the condition to the
+    /// end of the block. Adding `AreaLevel >= 65` keeps `AreaLevel <= 67`.
+    AddCondition {
+        id: BlockId,
+        item: BlockItem,
+    },
+    /// Removes all item lines with the name.
+    RemoveItem {
+        id: BlockId,
+        name: String,
+    },
+    /// Comments out an enabled block or uncomments a disabled one.
+    ToggleEnabled {
+        id: BlockId,
+    },
+    /// Changes the block name, for example from `Show` to `Hide`.
+    SetName {
+        id: BlockId,
+        name: BlockName,
+    },
+    /// Applies the commands in order as a single step of the undo history.
+    Batch {
+        commands: Vec<EditCommand>,
+    },
+}
+
+impl FilterDocument {
+    /// Applies the text edits of the command, recording each of them in `applied`.
+    pub(super) fn run(
+        &mut self,
+        command: &EditCommand,
+        ids_before: &[BlockId],
+        applied: &mut Vec<(TextEdit, TextEdit)>,
+    ) -> Result<()> {
+        let mut ids = ids_before.to_vec();
+
+        match command {
+            EditCommand::Batch { commands } => {
+                // Each command checks the blocks it changes on its own
+                for command in commands {
+                    let ids = self.block_ids();
+                    self.run(command, &ids, applied)?;
+                }
+
+                return Ok(());
+            }
+            EditCommand::InsertBlock { index, text } => {
+                let text = block_text(text)?;
+                let id = self.parser.next_block_id();
+                self.insert_block(*index, &text, applied)?;
+                ids.insert(*index, id);
+            }
+            EditCommand::DeleteBlock { id } => {
+                let index = self.block_index(*id)?;
+                self.delete_block(index, applied)?;
+                ids.remove(index);
+            }
+            EditCommand::MoveBlock { id, index } => {
+                let from = self.block_index(*id)?;
+
+                if *index >= ids.len() {
+                    bail!("Block index {} is out of range", index);
+                }
+
+                if from == *index {
+                    return Ok(());
+                }
+
+                let text = self.block_content(from).to_string();
+                self.delete_block(from, applied)?;
+                self.insert_block(*index, &text, applied)?;
+                ids.remove(from);
+                ids.insert(*index, *id);
+            }
+            EditCommand::DuplicateBlock { id } => {
+                let index = self.block_index(*id)?;
+                let text = self.block_content(index).to_string();
+                let id = self.parser.next_block_id();
+                self.insert_block(index + 1, &text, applied)?;
+                ids.insert(index + 1, id);
+            }
+            EditCommand::SetItem { id, item } => {
+                let index = self.block_index(*id)?;
+                self.set_item(index, item, applied)?;
+            }
+            EditCommand::AddCondition { id, item } => {
+                let index = self.block_index(*id)?;
+                self.add_condition(index, item, applied)?;
+            }
+            EditCommand::RemoveItem { id, name } => {
+                let index = self.block_index(*id)?;
+                self.remove_item(index, name, applied)?;
+            }
+            EditCommand::ToggleEnabled { id } => {
+                let index = self.block_index(*id)?;
+                self.toggle_enabled(index, applied)?;
+            }
+            EditCommand::SetName { id, name } => {
+                let index = self.block_index(*id)?;
+                self.set_name(index, name, applied)?;
+            }
+        }
+
+        if self.filter().blocks.len() != ids.len() {
+            bail!("The edit changes other blocks of the filter");
+        }
+
+        self.parser.set_block_ids(&ids);
+        Ok(())
+    }
+
+    fn block_index(&self, id: BlockId) -> Result<usize> {
+        self.filter()
+            .blocks
+            .iter()
+            .position(|block| block.id == id)
+            .ok_or_else(|| anyhow!("Block {} was not found in the filter", id.0))
+    }
+
+    /// Text of the block from its name line to the end of its last line.
+    fn block_content(&self, index: usize) -> &str {
+        let span = self.filter().blocks[index].span;
+        &self.content()[span.start..span.end]
+    }
+
+    fn line_ending(&self) -> &'static str {
+        self.format.line_ending.as_str()
+    }
+
+    /// Lines of the block after the name line, with spans relative to the content.
+    fn block_lines(&self, index: usize) -> Vec<Line<'_>> {
+        let block = &self.filter().blocks[index];
+        let start = block.span.start;
+
+        content_to_lines(self.block_content(index))
+            .into_iter()
+            .skip(1)
+            .filter(|line| line.disabled != block.enabled)
+            .map(|line| Line {
+                span: line.span.shifted(start as isize),
+                ..line
+            })
+            .collect()
+    }
+
+    fn insert_block(
+        &mut self,
+        index: usize,
+        text: &str,
+        applied: &mut Vec<(TextEdit, TextEdit)>,
+    ) -> Result<()> {
+        let blocks = &self.filter().blocks;
+        let line_ending = self.line_ending();
+
+        let edit = match blocks.get(index) {
+            Some(block) => insert(
+                block.span.start,
+                format!("{text}{line_ending}{line_ending}"),
+            ),
+            None if index == blocks.len() => {
+                let content = self.content();
+
+                let separator = match content {
+                    "" => "".to_string(),
+                    _ if content.ends_with('\n') => line_ending.to_string(),
+                    _ => format!("{line_ending}{line_ending}"),
+                };
+
+                insert(content.len(), format!("{separator}{text}{line_ending}"))
+            }
+            None => bail!("Block index {} is out of range", index),
+        };
+
+        self.apply_edit(edit, applied)
+    }
+
+    /// Removes the lines of the block and the empty line after it.
+    fn delete_block(
+        &mut self,
+        index: usize,
+        applied: &mut Vec<(TextEdit, TextEdit)>,
+    ) -> Result<()> {
+        let span = self.filter().blocks[index].span;
+        let content = self.content();
+        let mut end = next_line_start(content, span.end);
+
+        if content[end..].starts_with(['\r', '\n']) {
+            end = next_line_start(content, end);
+        }
+
+        self.apply_edit(remove(span.start, end), applied)
+    }
+
+    fn set_item(
+        &mut self,
+        index: usize,
+        item: &BlockItem,
+        applied: &mut Vec<(TextEdit, TextEdit)>,
+    ) -> Result<()> {
+        let existing = self
+            .item_lines(index, &item.name)
+            .first()
+            .map(|line| self.text_range(line));
+
+        self.replace_or_add_item(index, item, existing, applied)
+    }
+
+    fn add_condition(
+        &mut self,
+        index: usize,
+        item: &BlockItem,
+        applied: &mut Vec<(TextEdit, TextEdit)>,
+    ) -> Result<()> {
+        let (operator, _) = split_operator(&item.params);
+
+        let has_operator = |line: &Line| match parse_block_item(line.text) {
+            Ok(parsed) => split_operator(&parsed.to_block_item().params).0 == operator,
+            Err(_) => false,
+        };
+
+        let existing = self
+            .item_lines(index, &item.name)
+            .iter()
+            .find(|line| has_operator(line))
+            .map(|line| self.text_range(line));
+
+        self.replace_or_add_item(index, item, existing, applied)
+    }
+
+    /// Replaces the text in `existing`, the start and length of an item line, with the item,
+    /// or adds the item to the end of the block.
+    fn replace_or_add_item(
+        &mut self,
+        index: usize,
+        item: &BlockItem,
+        existing: Option<(usize, usize)>,
+        applied: &mut Vec<(TextEdit, TextEdit)>,
+    ) -> Result<()> {
+        let text = format_item(item);
+
+        // Only the text is replaced, so the indentation and the comment of the line are kept
+        let edit = match existing {
+            Some((start, len)) => TextEdit {
+                start,
+                end: start + len,
+                replacement: text,
+            },
+            None => {
+                let block = &self.filter().blocks[index];
+                let indent = if block.enabled { "\t" } else { "#\t" };
+                insert(
+                    block.span.end,
+                    format!("{}{indent}{text}", self.line_ending()),
+                )
+            }
+        };
+
+        self.apply_edit(edit, applied)
+    }
+
+    fn remove_item(
+        &mut self,
+        index: usize,
+        name: &str,
+        applied: &mut Vec<(TextEdit, TextEdit)>,
+    ) -> Result<()> {
+        let lines: Vec<Span> = self
+            .item_lines(index, name)
+            .iter()
+            .map(|line| line.span)
+            .collect();
+
+        if lines.is_empty() {
+            bail!("The block has no {} item", name);
+        }
+
+        // Removing from the end keeps the positions of the earlier lines valid
+        for span in lines.into_iter().rev() {
+            let end = next_line_start(self.content(), span.end);
+            self.apply_edit(remove(span.start, end), applied)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the name in the name line of the block, keeping its comment.
+    fn set_name(
+        &mut self,
+        index: usize,
+        name: &BlockName,
+        applied: &mut Vec<(TextEdit, TextEdit)>,
+    ) -> Result<()> {
+        let block = &self.filter().blocks[index];
+
+        if block.name == *name {
+            return Ok(());
+        }
+
+        // The name is the first word of the block, after `#` if the block is disabled
+        let old_name = block.name.to_string();
+        let content = self.block_content(index);
+        let offset = content
+            .find(&old_name)
+            .ok_or_else(|| anyhow!("Block {} has no {} name", block.id.0, old_name))?;
+        let start = block.span.start + offset;
+
+        let edit = TextEdit {
+            start,
+            end: start + old_name.len(),
+            replacement: name.to_string(),
+        };
+
+        self.apply_edit(edit, applied)
+    }
+
+    /// Comments out every line of an enabled block with `#`, or removes the `#`
+    /// from the lines of a disabled block.
+    fn toggle_enabled(
+        &mut self,
+        index: usize,
+        applied: &mut Vec<(TextEdit, TextEdit)>,
+    ) -> Result<()> {
+        let block = &self.filter().blocks[index];
+        let enabled = block.enabled;
+
+        let mut line_starts: Vec<usize> = content_to_lines(self.block_content(index))
+            .iter()
+            .filter(|line| line.disabled != enabled)
+            .map(|line| line.span.start + block.span.start)
+            .collect();
+
+        // Editing from the end keeps the positions of the earlier lines valid
+        line_starts.reverse();
+
+        for start in line_starts {
+            let edit = match enabled {
+                true => insert(start, "#".to_string()),
+                false => {
+                    let offset = self.content()[start..]
+                        .find('#')
+                        .ok_or_else(|| anyhow!("Disabled line {} has no `#`", start))?;
+                    remove(start + offset, start + offset + 1)
+                }
+            };
+
+            self.apply_edit(edit, applied)?;
+        }
+
+        Ok(())
+    }
+
+    /// Start and length of the text of a line, which is borrowed from the content.
+    fn text_range(&self, line: &Line) -> (usize, usize) {
+        let content_start = self.content().as_ptr() as usize;
+        (line.text.as_ptr() as usize - content_start, line.text.len())
+    }
+
+    /// Lines of the block with items of the given name.
+    fn item_lines(&self, index: usize, name: &str) -> Vec<Line<'_>> {
+        self.block_lines(index)
+            .into_iter()
+            .filter(|line| line.text.split_whitespace().next() == Some(name))
+            .collect()
+    }
+}
+
+/// Checks that the text is a single block and removes the line endings at its end.
+fn block_text(text: &str) -> Result<String> {
+    let filter = parse_content(text)?;
+
+    if filter.blocks.len() != 1 {
+        bail!(
+            "The text must have exactly one block, found {}",
+            filter.blocks.len()
+        );
+    }
+
+    Ok(text.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Formats the item as a filter line, quoting the parameters that contain spaces.
+fn format_item(item: &BlockItem) -> String {
+    let mut line = item.name.to_string();
+
+    for param in &item.params {
+        line.push(' ');
+
+        if param.is_empty() || param.contains(char::is_whitespace) {
+            line.push_str(&format!("\"{}\"", param));
+        } else {
+            line.push_str(param);
+        }
+    }
+
+    line
+}
+
+fn insert(position: usize, text: String) -> TextEdit {
+    TextEdit {
+        start: position,
+        end: position,
+        replacement: text,
+    }
+}
+
+fn remove(start: usize, end: usize) -> TextEdit {
+    TextEdit {
+        start,
+        end,
+        replacement: String::new(),
+    }
+}
+
+/// Returns the position after the line ending that follows `position`.
+fn next_line_start(content: &str, position: usize) -> usize {
+    match content[position..].find('\n') {
+        Some(offset) => position + offset + 1,
+        None => content.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::keywords::intern_keyword;
+
+    const CONTENT: &str =
+        "# Header\n\nShow # $tier->t1\n\tRarity Unique\n\nHide\n\tRarity Normal\n";
+
+    fn document(content: &str) -> FilterDocument {
+        FilterDocument::new(content.to_string()).unwrap()
+    }
+
+    fn ids(document: &FilterDocument) -> Vec<u32> {
+        document
+            .filter()
+            .blocks
+            .iter()
+            .map(|block| block.id.0)
+            .collect()
+    }
+
+    fn item(name: &str, params: &[&str]) -> BlockItem {
+        BlockItem {
+            name: intern_keyword(name),
+            params: params.iter().map(|param| param.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_insert_block() {
+        let mut document = document(CONTENT);
+
+        let command = EditCommand::InsertBlock {
+            index: 1,
+            text: "Show\n\tRarity Rare\n".to_string(),
+        };
+
+        document.apply(&command).unwrap();
+
+        assert_eq!(
+            document.content(),
+            "# Header\n\nShow # $tier->t1\n\tRarity Unique\n\nShow\n\tRarity Rare\n\nHide\n\tRarity Normal\n"
+        );
+
+        assert_eq!(ids(&document), [1, 3, 2]);
+    }
+
+    #[test]
+    fn test_insert_block_at_end() {
+        let mut document = document("Show\n\tRarity Unique");
+
+        let command = EditCommand::InsertBlock {
+            index: 1,
+            text: "Hide\n\tRarity Normal".to_string(),
+        };
+
+        document.apply(&command).unwrap();
+        assert_eq!(
+            document.content(),
+            "Show\n\tRarity Unique\n\nHide\n\tRarity Normal\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_invalid_block() {
+        let mut document = document(CONTENT);
+
+        let command = EditCommand::InsertBlock {
+            index: 0,
+            text: "Show\nHide\n".to_string(),
+        };
+
+        assert!(document.apply(&command).is_err());
+        assert_eq!(document.content(), CONTENT);
+        assert!(!document.can_undo());
+    }
+
+    #[test]
+    fn test_delete_block() {
+        let mut document = document(CONTENT);
+        document
+            .apply(&EditCommand::DeleteBlock { id: BlockId(1) })
+            .unwrap();
+        assert_eq!(document.content(), "# Header\n\nHide\n\tRarity Normal\n");
+        assert_eq!(ids(&document), [2]);
+    }
+
+    #[test]
+    fn test_move_block() {
+        let mut document = document(CONTENT);
+
+        let command = EditCommand::MoveBlock {
+            id: BlockId(1),
+            index: 1,
+        };
+
+        document.apply(&command).unwrap();
+
+        assert_eq!(
+            document.content(),
+            "# Header\n\nHide\n\tRarity Normal\n\nShow # $tier->t1\n\tRarity Unique\n"
+        );
+
+        assert_eq!(ids(&document), [2, 1]);
+    }
+
+    #[test]
+    fn test_duplicate_block() {
+        let mut document = document(CONTENT);
+        document
+            .apply(&EditCommand::DuplicateBlock { id: BlockId(2) })
+            .unwrap();
+
+        assert_eq!(
+            document.content(),
+            "# Header\n\nShow # $tier->t1\n\tRarity Unique\n\nHide\n\tRarity Normal\n\nHide\n\tRarity Normal\n"
+        );
+
+        assert_eq!(ids(&document), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_set_item() {
+        let mut document = document("Show\n\tRarity Unique # Uniques\n");
+
+        let command = EditCommand::SetItem {
+            id: BlockId(1),
+            item: item("Rarity", &["<=", "Rare"]),
+        };
+
+        document.apply(&command).unwrap();
+
+        let command = EditCommand::SetItem {
+            id: BlockId(1),
+            item: item("BaseType", &["==", "Gold Ring"]),
+        };
+
+        document.apply(&command).unwrap();
+
+        assert_eq!(
+            document.content(),
+            "Show\n\tRarity <= Rare # Uniques\n\tBaseType == \"Gold Ring\"\n"
+        );
+    }
+
+    #[test]
+    fn test_add_condition() {
+        let mut document = document("Show\n\tAreaLevel <= 67\n\tAreaLevel >= 60 # Maps\n");
+
+        let command = EditCommand::AddCondition {
+            id: BlockId(1),
+            item: item("AreaLevel", &[">=", "65"]),
+        };
+
+        document.apply(&command).unwrap();
+
+        let command = EditCommand::AddCondition {
+            id: BlockId(1),
+            item: item("AreaLevel", &["<", "80"]),
+        };
+
+        document.apply(&command).unwrap();
+
+        assert_eq!(
+            document.content(),
+            "Show\n\tAreaLevel <= 67\n\tAreaLevel >= 65 # Maps\n\tAreaLevel < 80\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_item() {
+        let mut document = document("Show\n\tRarity Unique\n\tSetFontSize 40\n\tRarity Rare\n");
+
+        let command = EditCommand::RemoveItem {
+            id: BlockId(1),
+            name: "Rarity".to_string(),
+        };
+
+        document.apply(&command).unwrap();
+        assert_eq!(document.content(), "Show\n\tSetFontSize 40\n");
+        assert!(document.apply(&command).is_err());
+    }
+
+    #[test]
+    fn test_toggle_enabled() {
+        let mut document = document(CONTENT);
+        let command = EditCommand::ToggleEnabled { id: BlockId(1) };
+        document.apply(&command).unwrap();
+
+        assert_eq!(
+            document.content(),
+            "# Header\n\n#Show # $tier->t1\n#\tRarity Unique\n\nHide\n\tRarity Normal\n"
+        );
+
+        assert!(!document.filter().blocks[0].enabled);
+        assert_eq!(ids(&document), [1, 2]);
+
+        document.apply(&command).unwrap();
+        assert_eq!(document.content(), CONTENT);
+        assert!(document.filter().blocks[0].enabled);
+    }
+
+    #[test]
+    fn test_toggle_enabled_keeps_comment_lines() {
+        let content = "Show\n\t# Uniques only\n\tRarity Unique\nHide\n\tRarity Normal\n";
+        let mut document = document(content);
+        let command = EditCommand::ToggleEnabled { id: BlockId(1) };
+        document.apply(&command).unwrap();
+
+        let disabled = "#Show\n\t# Uniques only\n#\tRarity Unique\nHide\n\tRarity Normal\n";
+        assert_eq!(document.content(), disabled);
+        assert!(!document.filter().blocks[0].enabled);
+        assert_eq!(document.filter().blocks.len(), 2);
+
+        document.apply(&command).unwrap();
+        assert_eq!(document.content(), content);
+    }
+
+    #[test]
+    fn test_toggle_enabled_with_unknown_keyword() {
+        let content = "Show\n\tZanaMemory True\n    UnknownKeyword 3\nHide\n\tRarity Normal\n";
+        let mut document = document(content);
+        let command = EditCommand::ToggleEnabled { id: BlockId(1) };
+        document.apply(&command).unwrap();
+
+        let block = &document.filter().blocks[0];
+        assert!(!block.enabled);
+        assert!(block.items.contains_key("ZanaMemory"));
+        assert!(block.items.contains_key("UnknownKeyword"));
+        assert_eq!(document.filter().blocks.len(), 2);
+
+        document.apply(&command).unwrap();
+        assert_eq!(document.content(), content);
+        assert!(document.filter().blocks[0].enabled);
+    }
+
+    #[test]
+    fn test_set_name() {
+        let mut document = document("#Show # %H3 $tier->t1\n#\tRarity Unique\n");
+
+        let command = EditCommand::SetName {
+            id: BlockId(1),
+            name: BlockName::Hide,
+        };
+
+        document.apply(&command).unwrap();
+        let content = "#Hide # %H3 $tier->t1\n#\tRarity Unique\n";
+        assert_eq!(document.content(), content);
+        assert_eq!(document.filter().blocks[0].name, BlockName::Hide);
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut document = document(CONTENT);
+
+        let command = EditCommand::MoveBlock {
+            id: BlockId(2),
+            index: 0,
+        };
+
+        document.apply(&command).unwrap();
+        let moved = document.content().to_string();
+        assert!(document.is_dirty());
+
+        assert!(document.undo().unwrap());
+        assert_eq!(document.content(), CONTENT);
+        assert_eq!(ids(&document), [1, 2]);
+        assert!(!document.is_dirty());
+        assert!(!document.undo().unwrap());
+
+        assert!(document.redo().unwrap());
+        assert_eq!(document.content(), moved);
+        assert_eq!(ids(&document), [2, 1]);
+        assert!(!document.redo().unwrap());
+    }
+
+    #[test]
+    fn test_unknown_block() {
+        let mut document = document(CONTENT);
+        let result = document.apply(&EditCommand::DeleteBlock { id: BlockId(9) });
+        assert!(result.is_err());
+        assert_eq!(document.content(), CONTENT);
+    }
+
+    #[test]
+    fn test_crlf_line_endings() {
+        let mut document = document("Show\r\n\tRarity Unique\r\n");
+
+        let command = EditCommand::SetItem {
+            id: BlockId(1),
+            item: item("SetFontSize", &["45"]),
+        };
+
+        document.apply(&command).unwrap();
+        assert_eq!(
+            document.content(),
+            "Show\r\n\tRarity Unique\r\n\tSetFontSize 45\r\n"
+        );
+    }
+}
@@ -0,0 +1,396 @@
+pub mod bulk_edit;
+pub mod edit_command;
+pub mod strictness;
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use crate::filter::parser::block_id::BlockId;
+use crate::filter::parser::blocks::Block;
+use crate::filter::parser::encoding::{decode, LineEnding, TextFormat};
+use crate::filter::parser::include_graph::resolve_imports_with_ids;
+use crate::filter::parser::incremental::{IncrementalParser, TextEdit};
+use crate::filter::parser::parse_file::ParsedFilter;
+use crate::filter::parser::read_from_disk::read_filter_with_format;
+use crate::filter::parser::write_to_disk::write_filter_to_disk;
+use edit_command::EditCommand;
+
+/// An opened filter that can be edited.
+///
+/// Every [`EditCommand`] is applied as text edits to the filter content, so comments and
+/// formatting of the rest of the filter are kept. Commands can be undone and redone.
+pub struct FilterDocument {
+    path: Option<PathBuf>,
+    format: TextFormat,
+    parser: IncrementalParser,
+    // Ids of the blocks of the imported files in order, they come from the ids of the
+    // document so they never collide with the ids of its blocks
+    import_ids: Vec<BlockId>,
+    // Blocks of the imported files when they were last read, to tell if they changed on disk
+    imported_blocks: Vec<Block>,
+    // Content of the file on disk, used to tell if the document has unsaved changes
+    saved_content: String,
+    undo_stack: Vec<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
+}
+
+/// Text edits made by a single command.
+struct HistoryEntry {
+    // Edits in the order they were applied
+    edits: Vec<TextEdit>,
+    // Edits that revert `edits`, in the order they need to be applied
+    inverse: Vec<TextEdit>,
+    // Block ids before and after the command, restored on undo and redo
+    ids_before: Vec<BlockId>,
+    ids_after: Vec<BlockId>,
+}
+
+/// State of the document sent to the UI after each change.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct FilterDocumentView {
+    pub filter: ParsedFilter,
+    // The document has changes that are not saved to disk
+    pub dirty: bool,
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+impl FilterDocument {
+    /// Creates a document that is not stored on disk.
+    pub fn new(content: String) -> Result<Self> {
+        let format = TextFormat {
+            line_ending: LineEnding::detect(&content),
+            ..TextFormat::default()
+        };
+
+        Self::with_content(None, format, content)
+    }
+
+    /// Opens the filter file at `path`.
+    pub async fn open(path: &Path) -> Result<Self> {
+        let (content, format) = read_filter_with_format(path).await?;
+        let mut document = Self::with_content(Some(path.to_path_buf()), format, content)?;
+        document.reload_imports()?;
+        Ok(document)
+    }
+
+    fn with_content(path: Option<PathBuf>, format: TextFormat, content: String) -> Result<Self> {
+        Ok(FilterDocument {
+            path,
+            format,
+            parser: IncrementalParser::new(content.clone())?,
+            import_ids: Vec::new(),
+            imported_blocks: Vec::new(),
+            saved_content: content,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        })
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn content(&self) -> &str {
+        self.parser.content()
+    }
+
+    /// Encoding and line endings of the file, used when the filter is written.
+    pub fn format(&self) -> &TextFormat {
+        &self.format
+    }
+
+    /// The parsed filter without the blocks of the imported files.
+    pub fn filter(&self) -> &ParsedFilter {
+        self.parser.filter()
+    }
+
+    /// Returns true if the content differs from the file on disk.
+    pub fn is_dirty(&self) -> bool {
+        self.content() != self.saved_content
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Returns the state of the document with the blocks of the imported files inlined.
+    pub fn view(&mut self) -> Result<FilterDocumentView> {
+        let filter = self.resolve_imports()?;
+
+        Ok(FilterDocumentView {
+            filter,
+            dirty: self.is_dirty(),
+            can_undo: self.can_undo(),
+            can_redo: self.can_redo(),
+        })
+    }
+
+    /// Applies the command and adds it to the undo history.
+    ///
+    /// The document is left unchanged if the command fails.
+    pub fn apply(&mut self, command: &EditCommand) -> Result<()> {
+        let ids_before = self.block_ids();
+        let mut applied = Vec::new();
+
+        if let Err(e) = self.run(command, &ids_before, &mut applied) {
+            for (_, inverse) in applied.iter().rev() {
+                self.parser.apply_edit(inverse)?;
+            }
+
+            self.parser.set_block_ids(&ids_before);
+            return Err(e);
+        }
+
+        if applied.is_empty() {
+            return Ok(());
+        }
+
+        let (edits, mut inverse): (Vec<TextEdit>, Vec<TextEdit>) = applied.into_iter().unzip();
+        inverse.reverse();
+
+        self.undo_stack.push(HistoryEntry {
+            edits,
+            inverse,
+            ids_before,
+            ids_after: self.block_ids(),
+        });
+
+        self.redo_stack.clear();
+        Ok(())
+    }
+
+    /// Reverts the last command. Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> Result<bool> {
+        let Some(entry) = self.undo_stack.pop() else {
+            return Ok(false);
+        };
+
+        for edit in &entry.inverse {
+            self.parser.apply_edit(edit)?;
+        }
+
+        self.parser.set_block_ids(&entry.ids_before);
+        self.redo_stack.push(entry);
+        Ok(true)
+    }
+
+    /// Applies the last undone command again. Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> Result<bool> {
+        let Some(entry) = self.redo_stack.pop() else {
+            return Ok(false);
+        };
+
+        for edit in &entry.edits {
+            self.parser.apply_edit(edit)?;
+        }
+
+        self.parser.set_block_ids(&entry.ids_after);
+        self.undo_stack.push(entry);
+        Ok(true)
+    }
+
+    /// Writes the content to the file in its original encoding and line endings.
+    pub fn save(&mut self) -> Result<()> {
+        let Some(path) = &self.path else {
+            bail!("The filter has no file to save to");
+        };
+
+        write_filter_to_disk(path, self.content(), &self.format)
+            .with_context(|| format!("Failed to save filter {}", path.display()))?;
+
+        self.saved_content = self.content().to_string();
+        Ok(())
+    }
+
+    /// Reads the file and its imports again after one of them was changed on disk.
+    ///
+    /// Returns false if the file has the content of the document and the blocks of the
+    /// imported files did not change, for example after the document was saved. The undo
+    /// history is cleared if the file changed, since its edits refer to the previous
+    /// content. Returns an error if the file changed and the document has unsaved changes.
+    pub fn reload(&mut self) -> Result<bool> {
+        let Some(path) = &self.path else {
+            return Ok(false);
+        };
+
+        let (content, format) = decode(&std::fs::read(path)?);
+
+        if content == self.saved_content {
+            return self.reload_imports();
+        }
+
+        if self.is_dirty() {
+            bail!("The filter was changed on disk, but it has unsaved changes");
+        }
+
+        self.parser.replace_content(content.clone())?;
+        self.format = format;
+        self.saved_content = content;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+
+        // The imported files may have changed as well
+        self.import_ids.clear();
+        self.reload_imports()?;
+        Ok(true)
+    }
+
+    /// Reads the imported files again and returns true if their blocks changed.
+    fn reload_imports(&mut self) -> Result<bool> {
+        let filter = self.resolve_imports()?;
+        let ids: HashSet<BlockId> = self.import_ids.iter().copied().collect();
+
+        let imported: Vec<Block> = filter
+            .blocks
+            .into_iter()
+            .filter(|block| ids.contains(&block.id))
+            .collect();
+
+        let changed = imported != self.imported_blocks;
+        self.imported_blocks = imported;
+        Ok(changed)
+    }
+
+    /// Returns the filter with the blocks of the imported files inlined.
+    ///
+    /// The imported blocks keep their ids each time the imports are resolved, and blocks of
+    /// imports added since the last time get new ids of the document.
+    fn resolve_imports(&mut self) -> Result<ParsedFilter> {
+        let filter = self.filter().clone();
+
+        let Some(path) = &self.path else {
+            return Ok(filter);
+        };
+
+        let (ids, parser) = (&mut self.import_ids, &mut self.parser);
+        resolve_imports_with_ids(path, filter, ids, || parser.next_block_id())
+    }
+
+    fn block_ids(&self) -> Vec<BlockId> {
+        self.filter().blocks.iter().map(|block| block.id).collect()
+    }
+
+    /// Applies the edit and records it together with the edit that reverts it.
+    fn apply_edit(
+        &mut self,
+        edit: TextEdit,
+        applied: &mut Vec<(TextEdit, TextEdit)>,
+    ) -> Result<()> {
+        let removed = self
+            .content()
+            .get(edit.start..edit.end)
+            .map(str::to_string)
+            .unwrap_or_default();
+
+        self.parser.apply_edit(&edit)?;
+
+        let inverse = TextEdit {
+            start: edit.start,
+            end: edit.start + edit.replacement.len(),
+            replacement: removed,
+        };
+
+        applied.push((edit, inverse));
+        Ok(())
+    }
+}
+
+/// Holds the opened filter document in Tauri state.
+#[derive(Default)]
+pub struct FilterDocumentState(pub Mutex<Option<FilterDocument>>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_save_keeps_format() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("main.filter");
+        std::fs::write(&path, b"\xEF\xBB\xBFShow\r\n\tRarity Unique\r\n").unwrap();
+        let mut document = FilterDocument::open(&path).await.unwrap();
+
+        let command = EditCommand::ToggleEnabled {
+            id: BlockId::from_order(1),
+        };
+
+        document.apply(&command).unwrap();
+        assert!(document.is_dirty());
+        document.save().unwrap();
+        assert!(!document.is_dirty());
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes, b"\xEF\xBB\xBF#Show\r\n#\tRarity Unique\r\n");
+        assert!(!document.reload().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_reload() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("main.filter");
+        std::fs::write(&path, "Show\n\tRarity Unique\nHide\n\tRarity Normal\n").unwrap();
+        let mut document = FilterDocument::open(&path).await.unwrap();
+
+        document
+            .apply(&EditCommand::DeleteBlock {
+                id: BlockId::from_order(1),
+            })
+            .unwrap();
+
+        std::fs::write(&path, "Hide\n\tRarity Normal\nShow\n\tRarity Unique\n").unwrap();
+        assert!(document.reload().is_err());
+
+        document.undo().unwrap();
+        assert!(document.reload().unwrap());
+        let ids: Vec<u32> = document.filter().blocks.iter().map(|b| b.id.0).collect();
+        assert_eq!(ids, [2, 1]);
+        assert!(!document.can_redo());
+    }
+
+    #[tokio::test]
+    async fn test_imported_blocks_keep_ids() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("main.filter");
+        std::fs::write(&path, "Show\n\tRarity Unique\nImport \"shared.filter\"\n").unwrap();
+        std::fs::write(dir.path().join("shared.filter"), "Hide\n\tRarity Normal\n").unwrap();
+        let mut document = FilterDocument::open(&path).await.unwrap();
+
+        let ids = |view: FilterDocumentView| -> Vec<u32> {
+            view.filter.blocks.iter().map(|b| b.id.0).collect()
+        };
+
+        assert_eq!(ids(document.view().unwrap()), [1, 2]);
+        assert_eq!(ids(document.view().unwrap()), [1, 2]);
+
+        // The copy is added at the end of the file, after the import, and doesn't take
+        // the id of the imported block
+        let command = EditCommand::DuplicateBlock { id: BlockId(1) };
+        document.apply(&command).unwrap();
+        assert_eq!(ids(document.view().unwrap()), [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_reload_imports() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("main.filter");
+        let shared = dir.path().join("shared.filter");
+        std::fs::write(&path, "Show\n\tRarity Unique\nImport \"shared.filter\"\n").unwrap();
+        std::fs::write(&shared, "Hide\n\tRarity Normal\n").unwrap();
+        let mut document = FilterDocument::open(&path).await.unwrap();
+        assert!(!document.reload().unwrap());
+
+        std::fs::write(&shared, "Hide\n\tRarity Normal\nHide\n\tRarity Magic\n").unwrap();
+        assert!(document.reload().unwrap());
+        assert!(!document.reload().unwrap());
+        assert_eq!(document.view().unwrap().filter.blocks.len(), 3);
+    }
+}
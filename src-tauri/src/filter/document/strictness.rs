@@ -0,0 +1,385 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use ts_rs::TS;
+
+use super::bulk_edit::BlockSelector;
+use super::edit_command::EditCommand;
+use super::FilterDocument;
+use crate::filter::parser::blocks::Block;
+use crate::filter::parser::blocks_with_lines::BlockName;
+use crate::filter::parser::metadata::Strictness;
+use crate::filter::parser::sections::block_sections;
+use crate::filter::parser::write_to_disk::write_filter_to_disk;
+
+/// State given to a block by a strictness rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub enum BlockState {
+    Show,
+    Hide,
+    Enabled,
+    Disabled,
+}
+
+/// Decides how blocks change between strictness levels.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum StrictnessRule {
+    /// Blocks tagged with a level, such as `%H3` for the tag `%H`, get the `above` state
+    /// at strictness levels above the tagged level and the `otherwise` state at the others.
+    /// Without an `otherwise` state the blocks are left as they are at the other levels.
+    LevelTag {
+        tag: String,
+        above: BlockState,
+        otherwise: Option<BlockState>,
+    },
+    /// Blocks matched by the selector get the state from the `from` strictness up.
+    Select {
+        selector: BlockSelector,
+        from: Strictness,
+        state: BlockState,
+    },
+}
+
+/// Rules of the tags used by NeverSink filters:
+///
+/// - `%H3` and `%HS3` blocks are hidden above Strict (3) and shown otherwise.
+/// - `%D3` blocks are disabled above Strict. Filters also disable some of these blocks at
+///   lower levels, so they are not enabled again.
+/// - `%RH3` blocks are enabled above Strict and disabled otherwise.
+pub fn neversink_rules() -> Vec<StrictnessRule> {
+    let level_tag = |tag: &str, above, otherwise| StrictnessRule::LevelTag {
+        tag: tag.to_string(),
+        above,
+        otherwise,
+    };
+
+    vec![
+        level_tag("%H", BlockState::Hide, Some(BlockState::Show)),
+        level_tag("%HS", BlockState::Hide, Some(BlockState::Show)),
+        level_tag("%D", BlockState::Disabled, None),
+        level_tag("%RH", BlockState::Enabled, Some(BlockState::Disabled)),
+    ]
+}
+
+impl StrictnessRule {
+    /// Returns the state the rule gives to the block, or `None` if the rule does not apply.
+    fn state(&self, block: &Block, section: Option<&str>, level: u8) -> Option<BlockState> {
+        match self {
+            StrictnessRule::LevelTag {
+                tag,
+                above,
+                otherwise,
+            } => {
+                let tagged_level: u8 = block.tag(tag)?.parse().ok()?;
+
+                match level > tagged_level {
+                    true => Some(*above),
+                    false => *otherwise,
+                }
+            }
+            StrictnessRule::Select {
+                selector,
+                from,
+                state,
+            } => (level >= from.level() && selector.matches(block, section)).then_some(*state),
+        }
+    }
+}
+
+impl FilterDocument {
+    /// Shows, hides, enables and disables the blocks for the strictness level as a single
+    /// step of the undo history. Later rules override earlier ones.
+    ///
+    /// Returns the number of changed blocks.
+    pub fn apply_strictness(
+        &mut self,
+        strictness: Strictness,
+        rules: &[StrictnessRule],
+    ) -> Result<usize> {
+        let blocks = &self.filter().blocks;
+        let sections = block_sections(self.content(), blocks);
+        let mut commands = Vec::new();
+        let mut count = 0;
+
+        for (block, section) in blocks.iter().zip(sections) {
+            let mut name = block.name.clone();
+            let mut enabled = block.enabled;
+
+            for rule in rules {
+                match rule.state(block, section, strictness.level()) {
+                    Some(BlockState::Show) => name = BlockName::Show,
+                    Some(BlockState::Hide) => name = BlockName::Hide,
+                    Some(BlockState::Enabled) => enabled = true,
+                    Some(BlockState::Disabled) => enabled = false,
+                    None => {}
+                }
+            }
+
+            let id = block.id;
+            let changed = name != block.name || enabled != block.enabled;
+            count += changed as usize;
+
+            if name != block.name {
+                commands.push(EditCommand::SetName { id, name });
+            }
+
+            if enabled != block.enabled {
+                commands.push(EditCommand::ToggleEnabled { id });
+            }
+        }
+
+        self.apply(&EditCommand::Batch { commands })?;
+        Ok(count)
+    }
+
+    /// Writes the variant of the document for the strictness level to `path`, in the
+    /// encoding and line endings of the document. The document itself is not changed.
+    pub fn export_strictness_variant(
+        &self,
+        path: &Path,
+        strictness: Strictness,
+        rules: &[StrictnessRule],
+    ) -> Result<()> {
+        let content = generate_strictness_variant(self.content(), strictness, rules)?;
+
+        write_filter_to_disk(path, &content, &self.format)
+            .with_context(|| format!("Failed to write filter {}", path.display()))
+    }
+}
+
+/// Generates the variant of the filter for the strictness level.
+///
+/// Only the names of the changed blocks, the `#` of the enabled and disabled lines and
+/// the `TYPE` line of the header differ from the original, so the result can be compared
+/// with the variants published by the filter author line by line.
+pub fn generate_strictness_variant(
+    content: &str,
+    strictness: Strictness,
+    rules: &[StrictnessRule],
+) -> Result<String> {
+    let mut document = FilterDocument::new(content.to_string())?;
+    document.apply_strictness(strictness, rules)?;
+    Ok(set_header_strictness(document.content(), strictness))
+}
+
+/// Replaces the value of the `TYPE` line of the header, keeping its alignment.
+fn set_header_strictness(content: &str, strictness: Strictness) -> String {
+    let mut start = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_start = start;
+        start += line.len();
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        // The header ends at the first rule
+        let Some(comment) = trimmed.strip_prefix('#') else {
+            break;
+        };
+
+        let Some((key, value)) = comment.split_once(':') else {
+            continue;
+        };
+
+        if !key.trim().eq_ignore_ascii_case("TYPE") || value.trim().is_empty() {
+            continue;
+        }
+
+        let value = value.trim();
+        let Some(value_offset) = line.find(value) else {
+            continue;
+        };
+
+        let value_start = line_start + value_offset;
+        let value_end = value_start + value.len();
+        let header_value = strictness.header_value();
+        let before = &content[..value_start];
+        return [before, &header_value, &content[value_end..]].concat();
+    }
+
+    content.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::parse_file::parse_content;
+    use std::path::PathBuf;
+
+    fn example_filter() -> String {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("filter_examples/example_001.filter");
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    /// Returns the lines that differ between the two contents, which have the same line count.
+    fn changed_lines<'a>(before: &'a str, after: &'a str) -> Vec<(&'a str, &'a str)> {
+        before
+            .lines()
+            .zip(after.lines())
+            .filter(|(a, b)| a != b)
+            .collect()
+    }
+
+    #[test]
+    fn test_same_strictness_keeps_filter() {
+        let content = example_filter();
+        let rules = neversink_rules();
+        let variant = generate_strictness_variant(&content, Strictness::SemiStrict, &rules);
+        assert_eq!(variant.unwrap(), content);
+    }
+
+    #[test]
+    fn test_stricter_variant() {
+        let content = example_filter();
+        let rules = neversink_rules();
+        let strict = generate_strictness_variant(&content, Strictness::Strict, &rules).unwrap();
+        let changes = changed_lines(&content, &strict);
+
+        assert!(changes.contains(&("# TYPE:     2-SEMI-STRICT", "# TYPE:     3-STRICT")));
+
+        assert!(changes.contains(&(
+            "Show # %H2 $type->sockets->runes $tier->t4",
+            "Hide # %H2 $type->sockets->runes $tier->t4"
+        )));
+
+        assert!(changes.contains(&(
+            "Show # %D2 $type->endgame->charms $tier->anycharm",
+            "#Show # %D2 $type->endgame->charms $tier->anycharm"
+        )));
+
+        assert!(changes.contains(&(
+            "#Hide  # # %RH2 $type->leveling->progressivehide $tier->magic1",
+            "Hide  # # %RH2 $type->leveling->progressivehide $tier->magic1"
+        )));
+
+        let filter = parse_content(&strict).unwrap();
+        let original = parse_content(&content).unwrap();
+        assert_eq!(filter.blocks.len(), original.blocks.len());
+
+        let again = generate_strictness_variant(&strict, Strictness::Strict, &rules).unwrap();
+        assert_eq!(again, strict);
+
+        // Hidden blocks are shown again at the original strictness, only the blocks
+        // disabled by `%D2` stay disabled
+        let back = generate_strictness_variant(&strict, Strictness::SemiStrict, &rules).unwrap();
+        let changes = changed_lines(&content, &back);
+        assert!(!changes.is_empty());
+
+        for (before, after) in changes {
+            assert_eq!(after, format!("#{before}"));
+        }
+    }
+
+    /// Every line of the Strict variant that differs from the Semi-Strict filter must be
+    /// one of the changes the filter author makes between the published variants:
+    ///
+    /// - the `TYPE` line of the header,
+    /// - `Show` and `Hide` swapped in the name of a block with a `%H` or `%HS` tag,
+    /// - a `#` added or removed to disable or enable a line of a block with a `%D` or
+    ///   `%RH` tag.
+    #[test]
+    fn test_strict_variant_changes_are_allowed() {
+        let content = example_filter();
+        let rules = neversink_rules();
+        let strict = generate_strictness_variant(&content, Strictness::Strict, &rules).unwrap();
+        let original = parse_content(&content).unwrap();
+        assert_eq!(content.lines().count(), strict.lines().count());
+
+        // Returns the block containing the line starting at the offset
+        let block_at = |offset: usize| {
+            let mut blocks = original.blocks.iter();
+            blocks.find(|block| block.span.start <= offset && offset < block.span.end)
+        };
+        let has_tag = |offset: usize, tags: &[&str]| {
+            let block = block_at(offset).unwrap();
+            tags.iter().any(|tag| block.tag(tag).is_some())
+        };
+
+        let mut offset = 0;
+        let mut swapped = 0;
+        let mut toggled = 0;
+
+        let lines = content.split_inclusive('\n');
+
+        for (before, after) in lines.zip(strict.split_inclusive('\n')) {
+            let start = offset;
+            offset += before.len();
+
+            if before == after {
+                continue;
+            }
+
+            if before.trim_start_matches('#').trim().starts_with("TYPE:") {
+                assert_eq!(after.trim(), "# TYPE:     3-STRICT");
+            } else if after.strip_prefix('#') == Some(before)
+                || before.strip_prefix('#') == Some(after)
+            {
+                assert!(has_tag(start, &["%D", "%RH"]), "{before:?}");
+                toggled += 1;
+            } else {
+                let hidden = before.replacen("Show", "Hide", 1);
+                let shown = before.replacen("Hide", "Show", 1);
+                assert!(after == hidden || after == shown, "{before:?} -> {after:?}");
+                assert!(has_tag(start, &["%H", "%HS"]), "{before:?}");
+                swapped += 1;
+            }
+        }
+
+        assert!(swapped > 0);
+        assert!(toggled > 0);
+    }
+
+    /// Lines of the published 3-STRICT file that the generated variant does not reproduce,
+    /// as `(line number, published line)`.
+    const KNOWN_STRICT_DIFFERENCES: &[(usize, &str)] = &[];
+
+    /// Compares the generated variant with the 3-STRICT file published by NeverSink for the
+    /// same version as `example_001.filter`, which must be placed next to it as
+    /// `example_001_strict.filter`.
+    #[test]
+    #[ignore = "needs filter_examples/example_001_strict.filter from the NeverSink release"]
+    fn test_strict_variant_matches_published_filter() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("filter_examples/example_001_strict.filter");
+        let published = std::fs::read_to_string(path).unwrap();
+
+        let content = example_filter();
+        let rules = neversink_rules();
+        let strict = generate_strictness_variant(&content, Strictness::Strict, &rules).unwrap();
+        assert_eq!(published.lines().count(), strict.lines().count());
+
+        let differences: Vec<(usize, &str)> = published
+            .lines()
+            .zip(strict.lines())
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, (a, _))| (i + 1, a))
+            .collect();
+
+        assert_eq!(differences, KNOWN_STRICT_DIFFERENCES);
+    }
+
+    #[test]
+    fn test_select_rule() {
+        let content = "# [[0100]] Gold\nShow # $tier->t2\n\tBaseType \"Gold\"\n";
+
+        let rules = [StrictnessRule::Select {
+            selector: BlockSelector::Section {
+                text: "gold".to_string(),
+            },
+            from: Strictness::VeryStrict,
+            state: BlockState::Hide,
+        }];
+
+        let strict = generate_strictness_variant(content, Strictness::Strict, &rules).unwrap();
+        assert_eq!(strict, content);
+        let uber = generate_strictness_variant(content, Strictness::UberStrict, &rules).unwrap();
+        assert_eq!(uber, content.replace("Show", "Hide"));
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::capture::item::Item;
+use crate::filter::regression::CorpusItem;
+
+/// Base types bundled with the app, a few of every class from the lowest to the highest
+/// drop levels.
+const BUNDLED_DATABASE: &str = include_str!("../../resources/base_types.json");
+
+/// Levels of the areas that items are generated for, items also drop in the area of
+/// their drop level. Generated items have the level of their area as item level.
+pub const LEVELS: [u32; 8] = [1, 15, 30, 45, 60, 65, 75, 82];
+
+/// Qualities of the variants of items that can have quality.
+const QUALITIES: [u32; 2] = [10, 20];
+
+/// Rarities of items that drop unidentified.
+const UNIDENTIFIED: [&str; 3] = ["Magic", "Rare", "Unique"];
+
+/// A base type and where it drops.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BaseType {
+    pub name: String,
+    // Lowest level of the areas the base type drops in
+    pub drop_level: u32,
+    // Largest stack, `None` if the base type doesn't stack
+    #[serde(default)]
+    pub max_stack: Option<u32>,
+    #[serde(default)]
+    pub map_tier: Option<u32>,
+}
+
+/// An item class and what its items can have.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ItemClass {
+    pub name: String,
+    // Rarities the items drop with, such as `Rare` or `Currency`
+    pub rarities: Vec<String>,
+    // Largest number of sockets
+    #[serde(default)]
+    pub sockets: u32,
+    #[serde(default)]
+    pub quality: bool,
+    #[serde(default)]
+    pub corruptible: bool,
+    pub base_types: Vec<BaseType>,
+}
+
+/// Item classes with their base types that items are generated from.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BaseTypeDatabase {
+    pub classes: Vec<ItemClass>,
+}
+
+impl BaseTypeDatabase {
+    /// Returns the database bundled with the app.
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_DATABASE).expect("The bundled base types are valid")
+    }
+
+    /// Loads a database from a JSON file in the format of `resources/base_types.json`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read base types {}", path.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid base types {}", path.display()))
+    }
+}
+
+/// Generates representative items of every base type of the database.
+///
+/// Every rarity of the class is generated for the drop level of the base type and for
+/// the higher [`LEVELS`]. Each of these items comes plain and in variants with quality,
+/// sockets, larger stacks or corrupted, as far as the class allows. Variants change one
+/// property at a time, so that the number of items stays manageable.
+pub fn generate_items(database: &BaseTypeDatabase) -> Vec<CorpusItem> {
+    let mut corpus = Vec::new();
+
+    for class in &database.classes {
+        for base_type in &class.base_types {
+            let levels = LEVELS.iter().filter(|level| **level > base_type.drop_level);
+            let levels: Vec<u32> = std::iter::once(base_type.drop_level)
+                .chain(levels.copied())
+                .collect();
+
+            for rarity in &class.rarities {
+                for &level in &levels {
+                    let item = Item {
+                        class: class.name.clone(),
+                        rarity: rarity.clone(),
+                        base_type: base_type.name.clone(),
+                        item_level: Some(level),
+                        stack_size: base_type.max_stack.map(|_| 1),
+                        map_tier: base_type.map_tier,
+                        identified: !UNIDENTIFIED.contains(&rarity.as_str()),
+                        ..Item::default()
+                    };
+
+                    corpus.extend(variants(class, base_type, item).map(|item| CorpusItem {
+                        name: describe(&item),
+                        item,
+                        area_level: Some(level),
+                    }));
+                }
+            }
+        }
+    }
+
+    corpus
+}
+
+/// Returns the item followed by its variants.
+fn variants(class: &ItemClass, base_type: &BaseType, item: Item) -> impl Iterator<Item = Item> {
+    let mut items = vec![item.clone()];
+
+    if class.quality {
+        items.extend(QUALITIES.map(|quality| Item {
+            quality: Some(quality),
+            ..item.clone()
+        }));
+    }
+
+    for count in 1..=class.sockets {
+        let sockets = vec!["S"; count as usize].join(" ");
+
+        items.push(Item {
+            sockets: Some(sockets),
+            ..item.clone()
+        });
+    }
+
+    if let Some(max) = base_type.max_stack.filter(|max| *max > 1) {
+        let mut sizes = vec![max / 2, max];
+        sizes.retain(|size| *size > 1);
+        sizes.dedup();
+
+        items.extend(sizes.into_iter().map(|size| Item {
+            stack_size: Some(size),
+            ..item.clone()
+        }));
+    }
+
+    if class.corruptible {
+        items.push(Item {
+            corrupted: true,
+            ..item
+        });
+    }
+
+    items.into_iter()
+}
+
+/// Names a generated item after its properties, such as
+/// `Ruby Ring, Rare, item level 65, corrupted`.
+fn describe(item: &Item) -> String {
+    let mut parts = vec![item.base_type.clone(), item.rarity.clone()];
+
+    if let Some(level) = item.item_level {
+        parts.push(format!("item level {}", level));
+    }
+
+    if let Some(quality) = item.quality {
+        parts.push(format!("quality {}", quality));
+    }
+
+    if let Some(sockets) = &item.sockets {
+        parts.push(match sockets.split_whitespace().count() {
+            1 => "1 socket".to_string(),
+            count => format!("{} sockets", count),
+        });
+    }
+
+    if let Some(size) = item.stack_size.filter(|size| *size > 1) {
+        parts.push(format!("stack of {}", size));
+    }
+
+    if item.corrupted {
+        parts.push("corrupted".to_string());
+    }
+
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_database() {
+        let database = BaseTypeDatabase::bundled();
+        let classes: Vec<&str> = database.classes.iter().map(|c| c.name.as_str()).collect();
+
+        for class in ["Rings", "Body Armours", "Stackable Currency", "Waystones"] {
+            assert!(classes.contains(&class), "{}", class);
+        }
+
+        for class in &database.classes {
+            assert!(!class.base_types.is_empty(), "{}", class.name);
+        }
+    }
+
+    #[test]
+    fn test_generate_items() {
+        let database: BaseTypeDatabase = serde_json::from_str(
+            r#"{"classes": [
+                {
+                    "name": "Gloves",
+                    "rarities": ["Normal", "Rare"],
+                    "sockets": 1,
+                    "quality": true,
+                    "corruptible": true,
+                    "base_types": [{"name": "Expert Intricate Gloves", "drop_level": 70}]
+                },
+                {
+                    "name": "Stackable Currency",
+                    "rarities": ["Currency"],
+                    "base_types": [{"name": "Chaos Orb", "drop_level": 16, "max_stack": 10}]
+                }
+            ]}"#,
+        )
+        .unwrap();
+
+        let corpus = generate_items(&database);
+        let names: Vec<&str> = corpus.iter().map(|item| item.name.as_str()).collect();
+
+        // 2 rarities, 3 levels and 5 variants of the gloves, 7 levels and 3 stacks of orbs
+        assert_eq!(corpus.len(), 2 * 3 * 5 + 7 * 3);
+
+        let expected = [
+            "Expert Intricate Gloves, Normal, item level 70",
+            "Expert Intricate Gloves, Normal, item level 70, quality 10",
+            "Expert Intricate Gloves, Normal, item level 70, quality 20",
+            "Expert Intricate Gloves, Normal, item level 70, 1 socket",
+            "Expert Intricate Gloves, Normal, item level 70, corrupted",
+            "Expert Intricate Gloves, Normal, item level 75",
+        ];
+
+        assert_eq!(names[..6], expected);
+        assert!(names.contains(&"Chaos Orb, Currency, item level 82, stack of 5"));
+
+        let rare = &corpus[15].item;
+        assert_eq!(rare.rarity, "Rare");
+        assert!(!rare.identified);
+        assert_eq!(corpus[15].area_level, Some(70));
+
+        let orb = corpus.last().unwrap();
+        assert_eq!(orb.item.stack_size, Some(10));
+        assert!(orb.item.identified);
+    }
+}
@@ -0,0 +1,382 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+use crate::capture::item::Item;
+use crate::filter::parser::block_id::BlockId;
+use crate::filter::parser::blocks::Block;
+use crate::filter::parser::blocks_with_lines::BlockName;
+use crate::filter::parser::keywords::is_action;
+
+/// Rarities in increasing order. Currency, gems and other items without one of these
+/// rarities are Normal.
+const RARITIES: [&str; 4] = ["Normal", "Magic", "Rare", "Unique"];
+
+/// What the filter does with an item when it drops.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct ItemMatch {
+    // Block that decided the visibility of the item, `None` if no block matched
+    pub block: Option<BlockId>,
+    // Position of the block in the file, starting from 1
+    pub order: Option<usize>,
+    pub visible: bool,
+    // Parameters of the actions applied to the item by name, including the actions of the
+    // matched `Continue` blocks above the block
+    pub actions: BTreeMap<String, Vec<String>>,
+}
+
+/// Returns what the filter does with the item, evaluating the blocks from top to bottom
+/// as the game does.
+///
+/// Disabled blocks are skipped. A matched block with `Continue` applies its actions and
+/// the evaluation goes on, so a block below can override them. An item that no block
+/// matches is shown without actions. `area_level` is the level of the area the item
+/// drops in, blocks with an `AreaLevel` condition don't match if it is unknown.
+pub fn match_item(blocks: &[Block], item: &Item, area_level: Option<u32>) -> ItemMatch {
+    let mut result = ItemMatch {
+        block: None,
+        order: None,
+        visible: true,
+        actions: BTreeMap::new(),
+    };
+
+    for block in applied_blocks(blocks, item, area_level) {
+        result.block = Some(block.id);
+        result.order = Some(block.order);
+        result.visible = block.name != BlockName::Hide;
+
+        for (name, items) in &block.items {
+            if let Some(action) = items.last().filter(|_| is_action(name)) {
+                let params = action.params.clone();
+                result.actions.insert(name.to_string(), params);
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns the blocks that apply to the item from top to bottom, the matched `Continue`
+/// blocks and the block that ends the evaluation. Empty if no block matches.
+pub fn applied_blocks<'a>(
+    blocks: &'a [Block],
+    item: &Item,
+    area_level: Option<u32>,
+) -> Vec<&'a Block> {
+    let mut applied = Vec::new();
+
+    for block in blocks.iter().filter(|block| block.enabled) {
+        if !block_matches(block, item, area_level) {
+            continue;
+        }
+
+        applied.push(block);
+
+        if !block.items.contains_key("Continue") {
+            break;
+        }
+    }
+
+    applied
+}
+
+/// Returns true if the item meets all conditions of the block.
+///
+/// Conditions on properties that [`Item`] doesn't have, such as `DropLevel` or `GemLevel`,
+/// are not met, except boolean conditions that are compared with `False`.
+pub fn block_matches(block: &Block, item: &Item, area_level: Option<u32>) -> bool {
+    block
+        .items
+        .iter()
+        .filter(|(name, _)| !is_action(name) && *name != "Continue")
+        .flat_map(|(name, items)| items.iter().map(move |item| (name, &item.params)))
+        .all(|(name, params)| condition_matches(name, params, item, area_level))
+}
+
+fn condition_matches(name: &str, params: &[String], item: &Item, area_level: Option<u32>) -> bool {
+    let (operator, values) = split_operator(params);
+    let number = |value: Option<u32>| compare_numbers(value, operator, &values);
+
+    match name {
+        "Class" => match_names(&item.class, operator, &values),
+        "BaseType" => match_names(&item.base_type, operator, &values),
+        "Rarity" => {
+            let ranks: Vec<String> = values
+                .iter()
+                .filter_map(|value| rarity_rank(value))
+                .map(|rank| rank.to_string())
+                .collect();
+
+            let ranks: Vec<&str> = ranks.iter().map(String::as_str).collect();
+            let rank = rarity_rank(&item.rarity).unwrap_or(0);
+            compare_numbers(Some(rank), operator, &ranks)
+        }
+        "ItemLevel" => number(item.item_level),
+        "Quality" => number(Some(item.quality.unwrap_or(0))),
+        "StackSize" => number(Some(item.stack_size.unwrap_or(1))),
+        "MapTier" | "WaystoneTier" => number(item.map_tier),
+        "AreaLevel" => number(area_level),
+        "Sockets" => number(Some(socket_count(item))),
+        "LinkedSockets" => number(Some(largest_link(item))),
+        "Corrupted" => match_bool(item.corrupted, &values),
+        "Mirrored" => match_bool(item.mirrored, &values),
+        "Identified" => match_bool(item.identified, &values),
+        "HasInfluence" => values.contains(&"None"),
+        "HasExplicitMod" => match_mods(item, operator, &values),
+        _ if values.iter().all(|v| *v == "True" || *v == "False") => match_bool(false, &values),
+        _ => false,
+    }
+}
+
+/// Splits the operator from the values of a condition, `=` if there is none.
+///
+/// The operator may be written before the first value without a space, as in `>=2`.
+pub fn split_operator(params: &[String]) -> (&str, Vec<&str>) {
+    let mut values: Vec<&str> = params.iter().map(String::as_str).collect();
+
+    let Some(first) = values.first().copied() else {
+        return ("=", values);
+    };
+
+    let length = first.len() - first.trim_start_matches(['<', '>', '=', '!']).len();
+
+    if length == 0 {
+        return ("=", values);
+    }
+
+    let (operator, rest) = first.split_at(length);
+
+    match rest.is_empty() {
+        true => values.remove(0),
+        false => std::mem::replace(&mut values[0], rest),
+    };
+
+    (operator, values)
+}
+
+fn compare(ordering: Ordering, operator: &str) -> bool {
+    match operator {
+        "<" => ordering.is_lt(),
+        "<=" => ordering.is_le(),
+        ">" => ordering.is_gt(),
+        ">=" => ordering.is_ge(),
+        "!" | "!=" => ordering.is_ne(),
+        _ => ordering.is_eq(),
+    }
+}
+
+/// Compares a number of the item with the values. `!=` is true if none of them is equal.
+fn compare_numbers(actual: Option<u32>, operator: &str, values: &[&str]) -> bool {
+    let Some(actual) = actual else {
+        return false;
+    };
+
+    let mut expected = values.iter().filter_map(|value| value.parse::<u32>().ok());
+
+    match operator {
+        "!" | "!=" => expected.all(|value| actual != value),
+        _ => expected.any(|value| compare(actual.cmp(&value), operator)),
+    }
+}
+
+/// Matches a class or base type, ignoring the case as the game does. `==` and `!=`
+/// compare whole names, the other operators look for the values in the name.
+fn match_names(actual: &str, operator: &str, values: &[&str]) -> bool {
+    let exact = matches!(operator, "==" | "!=");
+    let actual = actual.to_lowercase();
+
+    let found = values.iter().any(|value| {
+        let value = value.to_lowercase();
+
+        match exact {
+            true => actual == value,
+            false => actual.contains(&value),
+        }
+    });
+
+    match operator {
+        "!" | "!=" => !found,
+        _ => found,
+    }
+}
+
+fn match_bool(actual: bool, values: &[&str]) -> bool {
+    let expected = values.first().is_none_or(|value| *value == "True");
+    actual == expected
+}
+
+/// Matches the names of the explicit modifiers of items copied with the advanced format.
+///
+/// `HasExplicitMod >=2 "Tyrannical" "Merciless"` needs two of the modifiers, without a
+/// count one of them is enough.
+fn match_mods(item: &Item, operator: &str, values: &[&str]) -> bool {
+    let count = values.first().and_then(|value| value.parse::<usize>().ok());
+
+    let (operator, count, names) = match count {
+        Some(count) => (operator, count, &values[1..]),
+        None => (">=", 1, values),
+    };
+
+    let found = item
+        .modifiers
+        .iter()
+        .filter(|modifier| modifier.kind.as_deref() != Some("Implicit"))
+        .filter_map(|modifier| modifier.name.as_deref())
+        .filter(|name| names.contains(name))
+        .count();
+
+    compare(found.cmp(&count), operator)
+}
+
+fn rarity_rank(rarity: &str) -> Option<u32> {
+    RARITIES
+        .iter()
+        .position(|name| *name == rarity)
+        .map(|rank| rank as u32)
+}
+
+/// Number of sockets, `S S S` or `R-G-B B` have three and four.
+fn socket_count(item: &Item) -> u32 {
+    let sockets = item.sockets.as_deref().unwrap_or("");
+    sockets.chars().filter(char::is_ascii_alphabetic).count() as u32
+}
+
+/// Number of sockets in the largest group of linked sockets.
+fn largest_link(item: &Item) -> u32 {
+    let sockets = item.sockets.as_deref().unwrap_or("");
+
+    sockets
+        .split_whitespace()
+        .map(|group| group.chars().filter(char::is_ascii_alphabetic).count() as u32)
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::item::ItemModifier;
+    use crate::filter::parser::parse_file::parse_content;
+
+    fn item(class: &str, base_type: &str, rarity: &str) -> Item {
+        Item {
+            class: class.to_string(),
+            base_type: base_type.to_string(),
+            rarity: rarity.to_string(),
+            item_level: Some(80),
+            identified: true,
+            ..Item::default()
+        }
+    }
+
+    fn matches(condition: &str, item: &Item) -> bool {
+        let content = format!("Show\n\t{}\n", condition);
+        let filter = parse_content(&content).unwrap();
+        block_matches(&filter.blocks[0], item, Some(70))
+    }
+
+    #[test]
+    fn test_conditions() {
+        let mut ring = item("Rings", "Ruby Ring", "Rare");
+        ring.sockets = Some("R-G B".to_string());
+
+        let cases = [
+            ("Class \"Rings\"", true),
+            ("Class == \"Ring\"", false),
+            ("BaseType \"Ruby\" \"Sapphire\"", true),
+            ("BaseType == \"Ruby\"", false),
+            ("BaseType != \"Ruby Ring\"", false),
+            ("Class \"rings\"", true),
+            ("BaseType == \"ruby ring\"", true),
+            ("Rarity Rare", true),
+            ("Rarity <= Magic", false),
+            ("Rarity >= Rare", true),
+            ("Rarity Normal Magic", false),
+            ("ItemLevel >= 75", true),
+            ("ItemLevel <80", false),
+            ("ItemLevel 80", true),
+            ("AreaLevel < 68", false),
+            ("Quality 0", true),
+            ("StackSize >= 2", false),
+            ("Sockets 3", true),
+            ("LinkedSockets >= 3", false),
+            ("Corrupted False", true),
+            ("Identified True", true),
+            ("FracturedItem True", false),
+            ("FracturedItem False", true),
+            ("HasInfluence None", true),
+            ("DropLevel >= 1", false),
+        ];
+
+        for (condition, expected) in cases {
+            assert_eq!(matches(condition, &ring), expected, "{}", condition);
+        }
+    }
+
+    #[test]
+    fn test_explicit_mods() {
+        let mut ring = item("Rings", "Ruby Ring", "Rare");
+
+        ring.modifiers = ["Hale", "of the Seal"]
+            .map(|name| ItemModifier {
+                text: String::new(),
+                kind: Some("Prefix".to_string()),
+                name: Some(name.to_string()),
+                tier: None,
+            })
+            .to_vec();
+
+        let cases = [
+            ("HasExplicitMod \"Hale\" \"Vivid\"", true),
+            ("HasExplicitMod >=2 \"Hale\" \"of the Seal\"", true),
+            ("HasExplicitMod >=2 \"Hale\" \"Vivid\"", false),
+        ];
+
+        for (condition, expected) in cases {
+            assert_eq!(matches(condition, &ring), expected, "{}", condition);
+        }
+    }
+
+    #[test]
+    fn test_match_item() {
+        let content = "\
+Show
+\tClass \"Rings\"
+\tSetFontSize 40
+\tSetTextColor 255 0 0
+\tContinue
+#Show
+#\tClass \"Rings\"
+Hide
+\tClass \"Rings\"
+\tRarity Normal Magic
+\tSetFontSize 20
+Show
+\tRarity Rare
+\tSetFontSize 45
+";
+
+        let filter = parse_content(content).unwrap();
+
+        let rare = match_item(&filter.blocks, &item("Rings", "Ruby Ring", "Rare"), None);
+        assert_eq!(rare.order, Some(4));
+        assert!(rare.visible);
+        assert_eq!(rare.actions["SetFontSize"], ["45"]);
+        assert_eq!(rare.actions["SetTextColor"], ["255", "0", "0"]);
+
+        let magic = match_item(&filter.blocks, &item("Rings", "Ruby Ring", "Magic"), None);
+        assert_eq!(magic.order, Some(3));
+        assert!(!magic.visible);
+
+        let unique = match_item(&filter.blocks, &item("Rings", "Ruby Ring", "Unique"), None);
+        assert_eq!(unique.order, Some(1));
+        assert_eq!(unique.actions["SetFontSize"], ["40"]);
+
+        let currency = item("Stackable Currency", "Chaos Orb", "Currency");
+        let currency = match_item(&filter.blocks, &currency, None);
+        assert_eq!(currency.block, None);
+        assert!(currency.visible);
+        assert!(currency.actions.is_empty());
+    }
+}
@@ -1 +1,9 @@
+pub mod coverage;
+pub mod document;
+pub mod generator;
+pub mod matcher;
 pub mod parser;
+pub mod query;
+pub mod regression;
+pub mod theme;
+pub mod watcher;
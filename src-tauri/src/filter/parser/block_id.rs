@@ -0,0 +1,150 @@
+use std::collections::{HashMap, VecDeque};
+use ts_rs::TS;
+
+use crate::filter::parser::block_item::BlockItem;
+use crate::filter::parser::blocks::Block;
+use crate::filter::parser::blocks_with_lines::BlockName;
+use crate::filter::parser::keywords::Keyword;
+
+/// Identity of a block that does not depend on its content or position.
+///
+/// The blocks of a freshly parsed filter get ids in file order. After that a block keeps
+/// its id when it is edited, moved or re-parsed, and new blocks get ids that were not used before.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    TS,
+)]
+#[ts(export)]
+pub struct BlockId(pub u32);
+
+impl BlockId {
+    /// Id of a block of a freshly parsed filter, `order` starts from 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` does not fit in a `u32`, which would take a filter of more than
+    /// 16 GB since every block has a name of at least four bytes.
+    pub fn from_order(order: usize) -> Self {
+        BlockId(u32::try_from(order).expect("The filter has too many blocks"))
+    }
+}
+
+/// Creates block ids that are not used by any block of the filter.
+///
+/// The generator remembers the highest id it created, so ids of deleted blocks are not
+/// given to new blocks as long as the same generator is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockIdGenerator {
+    next: u32,
+}
+
+impl BlockIdGenerator {
+    /// Creates a generator for a filter with the given blocks.
+    pub fn after(blocks: &[Block]) -> Self {
+        let last = blocks.iter().map(|block| block.id.0).max().unwrap_or(0);
+        BlockIdGenerator { next: last + 1 }
+    }
+
+    /// Makes sure the generator does not create the ids used by the blocks.
+    pub fn skip_used(&mut self, blocks: &[Block]) {
+        self.next = self.next.max(Self::after(blocks).next);
+    }
+
+    pub fn next_id(&mut self) -> BlockId {
+        let id = BlockId(self.next);
+        self.next += 1;
+        id
+    }
+}
+
+/// Gives the blocks of a re-parsed filter the ids of the matching blocks of the previous version.
+///
+/// A block with the same content as a previous block gets its id, so moved blocks keep their ids.
+/// The remaining blocks take the unused ids of the previous blocks in order, which keeps the id
+/// of a block whose content was edited. Blocks left after that get new ids.
+pub fn reuse_block_ids(previous: &[Block], blocks: &mut [Block], ids: &mut BlockIdGenerator) {
+    let mut ids_by_content: HashMap<ContentKey, VecDeque<BlockId>> = HashMap::new();
+
+    for block in previous {
+        ids_by_content
+            .entry(content_key(block))
+            .or_default()
+            .push_back(block.id);
+    }
+
+    let matched: Vec<Option<BlockId>> = blocks
+        .iter()
+        .map(|block| {
+            ids_by_content
+                .get_mut(&content_key(block))
+                .and_then(|ids| ids.pop_front())
+        })
+        .collect();
+
+    let mut unused = ids_by_content.into_values().flatten().collect::<Vec<_>>();
+    unused.sort_unstable_by_key(|id| std::cmp::Reverse(*id));
+
+    for (block, id) in blocks.iter_mut().zip(matched) {
+        block.id = id.or_else(|| unused.pop()).unwrap_or_else(|| ids.next_id());
+    }
+}
+
+type ContentKey<'a> = (&'a BlockName, Vec<(&'a Keyword, &'a Vec<BlockItem>)>);
+
+fn content_key(block: &Block) -> ContentKey<'_> {
+    let mut items: Vec<_> = block.items.iter().collect();
+    items.sort_unstable_by_key(|(name, _)| *name);
+    (&block.name, items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::parse_file::parse_content;
+
+    fn ids(blocks: &[Block]) -> Vec<u32> {
+        blocks.iter().map(|block| block.id.0).collect()
+    }
+
+    #[test]
+    fn test_ids_follow_file_order() {
+        let filter = parse_content("Show\n\tRarity Rare\nHide\n\tRarity Normal\n").unwrap();
+        assert_eq!(ids(&filter.blocks), [1, 2]);
+        let mut ids_generator = BlockIdGenerator::after(&filter.blocks);
+        assert_eq!(ids_generator.next_id(), BlockId(3));
+    }
+
+    #[test]
+    fn test_reuse_ids_of_moved_blocks() {
+        let previous = parse_content("Show\n\tRarity Rare\nHide\n\tRarity Normal\n").unwrap();
+        let mut ids_generator = BlockIdGenerator::after(&previous.blocks);
+        let content = "Hide\n\tRarity Normal\nShow\n\tRarity Magic\nShow\n\tRarity Rare\n";
+        let mut blocks = parse_content(content).unwrap().blocks;
+
+        reuse_block_ids(&previous.blocks, &mut blocks, &mut ids_generator);
+
+        assert_eq!(ids(&blocks), [2, 3, 1]);
+    }
+
+    #[test]
+    fn test_reuse_ids_of_edited_blocks() {
+        let previous = parse_content("Show\n\tRarity Rare\nHide\n\tRarity Normal\n").unwrap();
+        let mut ids_generator = BlockIdGenerator::after(&previous.blocks);
+        let content = "Show\n\tRarity Unique\nHide\n\tRarity Normal\n";
+        let mut blocks = parse_content(content).unwrap().blocks;
+
+        reuse_block_ids(&previous.blocks, &mut blocks, &mut ids_generator);
+
+        assert_eq!(ids(&blocks), [1, 2]);
+    }
+}
@@ -1,27 +1,48 @@
 use crate::filter::parser::errors::ParseError;
+use crate::filter::parser::keywords::{intern_keyword, Keyword};
 use ts_rs::TS;
 
 /// Represents a line in the loot filter block.
 /// The `name` field is the line name; `params` holds all following parameters.
-#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, Clone, TS)]
+/// The parameters are owned because blocks outlive the content they were parsed from:
+/// the document keeps them while the content is replaced by edits and reloads.
+#[derive(Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, Clone, TS)]
 #[ts(export)]
 pub struct BlockItem {
-    pub name: String,
+    pub name: Keyword,
     pub params: Vec<String>,
 }
 
-/// Parses a loot filter block line into a BlockItem struct.
+/// A line in the loot filter block with parameters borrowed from the filter content.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BlockItemRef<'a> {
+    pub name: Keyword,
+    pub params: Vec<&'a str>,
+}
+
+impl BlockItemRef<'_> {
+    /// Copies the parameters into an owned BlockItem.
+    pub fn to_block_item(&self) -> BlockItem {
+        BlockItem {
+            name: self.name.clone(),
+            params: self.params.iter().map(|param| param.to_string()).collect(),
+        }
+    }
+}
+
+/// Parses a loot filter block line into a BlockItemRef struct.
 /// Splits the line into tokens (handling double quotes) and maps the first token into the name.
-pub fn parse_block_item(line: &str) -> Result<BlockItem, ParseError> {
-    let tokens = tokenize_line(line);
+/// Known names are interned, the parameters are borrowed from the line.
+pub fn parse_block_item(line: &str) -> Result<BlockItemRef<'_>, ParseError> {
+    let mut tokens = tokenize_line(line).into_iter();
 
-    if tokens.is_empty() {
+    let Some(name) = tokens.next() else {
         return Err(ParseError::EmptyLine);
-    }
+    };
 
-    Ok(BlockItem {
-        name: tokens[0].clone(),
-        params: tokens[1..].to_vec(),
+    Ok(BlockItemRef {
+        name: intern_keyword(name),
+        params: tokens.collect(),
     })
 }
 
@@ -34,48 +55,42 @@ pub fn parse_block_item(line: &str) -> Result<BlockItem, ParseError> {
 ///
 /// will be tokenized into:
 ///   ["BaseType", "==", "Time-Lost Emerald", "Time-Lost Ruby", "Time-Lost Sapphire"]
-fn tokenize_line(s: &str) -> Vec<String> {
+///
+/// The tokens are slices of the input line. A double quote always starts or ends a token,
+/// so `abc"def"` is tokenized into `["abc", "def"]`. Before the tokens were borrowed, the
+/// characters around the quote were joined into a single `abcdef` token.
+fn tokenize_line(s: &str) -> Vec<&str> {
     let mut tokens = Vec::new();
-    let mut current = String::new();
+    let mut token_start = 0;
     let mut in_quotes = false;
-    let mut chars = s.chars().peekable();
 
-    while let Some(c) = chars.next() {
+    for (i, c) in s.char_indices() {
         match c {
             '"' => {
+                // The quote ends the current token, both when entering and leaving a quoted section.
+                push_token(&mut tokens, &s[token_start..i]);
                 in_quotes = !in_quotes;
-
-                // When leaving a quoted section, push the current token.
-                if !in_quotes && !current.is_empty() {
-                    tokens.push(current.clone());
-                    current.clear();
-
-                    // Consume any spaces immediately following.
-                    while let Some(' ') = chars.peek() {
-                        chars.next();
-                    }
-                }
+                token_start = i + 1;
             }
             ' ' if !in_quotes => {
-                if !current.is_empty() {
-                    tokens.push(current.clone());
-                    current.clear();
-                }
-            }
-            _ => {
-                current.push(c);
+                push_token(&mut tokens, &s[token_start..i]);
+                token_start = i + 1;
             }
+            _ => {}
         }
     }
 
     // If anything remains, add it as a token.
-    if !current.is_empty() {
-        tokens.push(current);
-    }
-
+    push_token(&mut tokens, &s[token_start..]);
     tokens
 }
 
+fn push_token<'a>(tokens: &mut Vec<&'a str>, token: &'a str) {
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,6 +119,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tokenize_line_with_quote_inside_token() {
+        let line = "BaseType abc\"def ghi\" \"\" \"unclosed";
+        let tokens = tokenize_line(line);
+        assert_eq!(tokens, vec!["BaseType", "abc", "def ghi", "unclosed"]);
+    }
+
+    #[test]
+    fn test_tokenize_line_with_quote_after_token() {
+        let line = "BaseType abc\"def\"";
+        let tokens = tokenize_line(line);
+        assert_eq!(tokens, vec!["BaseType", "abc", "def"]);
+    }
+
     #[test]
     fn test_parse_block_line_rarity() {
         let line = "Rarity Normal Magic Rare";
@@ -149,7 +178,7 @@ mod tests {
     #[test]
     fn test_block_item_serialization() {
         let block_item = BlockItem {
-            name: "Rarity".to_string(),
+            name: "Rarity".into(),
             params: vec!["Normal".to_string(), "Magic".to_string(), "Rare".to_string()],
         };
 
@@ -157,4 +186,11 @@ mod tests {
         let decoded: BlockItem = serde_json::from_str(&json).unwrap();
         assert_eq!(block_item, decoded);
     }
+
+    #[test]
+    fn test_parse_block_line_interns_name() {
+        let block_item = parse_block_item("SetFontSize 45").unwrap();
+        assert!(matches!(block_item.name, Keyword::Borrowed("SetFontSize")));
+        assert_eq!(block_item.to_block_item().params, vec!["45".to_string()]);
+    }
 }
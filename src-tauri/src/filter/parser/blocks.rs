@@ -1,6 +1,11 @@
+use crate::filter::parser::block_id::BlockId;
 use crate::filter::parser::block_item::{parse_block_item, BlockItem};
 use crate::filter::parser::blocks_with_lines::{BlockName, BlockWithLines};
 use crate::filter::parser::errors::ParseError;
+use crate::filter::parser::keywords::Keyword;
+use crate::filter::parser::span::Span;
+use crate::filter::parser::tags::{parse_tags, BlockTag};
+use rayon::prelude::*;
 use ts_rs::TS;
 use std::collections::HashMap;
 
@@ -8,10 +13,27 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
 #[ts(export)]
 pub struct Block {
+    pub id: BlockId,
     // Position of the block in the file, starting from 1
-    pub order: u16,
+    pub order: usize,
     pub name: BlockName,
-    pub items: HashMap<String, Vec<BlockItem>>,
+    // Disabled blocks are commented out in the filter and ignored by the game
+    pub enabled: bool,
+    // Tags from the comment of the block name line, such as `$tier->t1`
+    pub tags: Vec<BlockTag>,
+    pub items: HashMap<Keyword, Vec<BlockItem>>,
+    // Position of the block in the filter content
+    pub span: Span,
+}
+
+impl Block {
+    /// Returns the value of the first tag with the name, for example `t1` for `$tier`.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|tag| tag.name == name)
+            .map(|tag| tag.value.as_str())
+    }
 }
 
 /// Parses an array of `BlockWithLines` into an array of `Block` by parsing each line as a block item.
@@ -26,34 +48,55 @@ pub struct Block {
 ///
 /// Returns a [`ParseError`] if any block line cannot be parsed into a block item.
 pub fn parse_block_with_lines(
-    blocks_with_lines: &[BlockWithLines],
+    blocks_with_lines: &[BlockWithLines<'_>],
 ) -> Result<Vec<Block>, ParseError> {
-    let mut blocks = Vec::new();
+    blocks_with_lines.iter().map(parse_block).collect()
+}
 
-    for block in blocks_with_lines {
-        let mut items: HashMap<String, Vec<BlockItem>> = HashMap::new();
+/// Same as [`parse_block_with_lines`] but parses the blocks on multiple threads.
+///
+/// The blocks are returned in the same order as the input. If several blocks fail to parse,
+/// the error of the first one is returned, so the result is identical to the sequential version.
+pub fn parse_block_with_lines_parallel(
+    blocks_with_lines: &[BlockWithLines<'_>],
+) -> Result<Vec<Block>, ParseError> {
+    let results: Vec<Result<Block, ParseError>> =
+        blocks_with_lines.par_iter().map(parse_block).collect();
 
-        for line in &block.lines {
-            // Propagates error if parse_block_item fails.
-            let item = parse_block_item(line)?;
+    results.into_iter().collect()
+}
 
-            items.entry(item.name.clone()).or_default().push(item);
-        }
+/// Parses the lines of a single block into block items.
+fn parse_block(block: &BlockWithLines<'_>) -> Result<Block, ParseError> {
+    let mut items: HashMap<Keyword, Vec<BlockItem>> = HashMap::new();
 
-        blocks.push(Block {
-            order: block.order,
-            name: block.name.clone(),
-            items,
-        });
+    for line in &block.lines {
+        // Propagates error if parse_block_item fails.
+        let item = parse_block_item(line)?;
+
+        // Cloning the name is free for interned keywords.
+        items
+            .entry(item.name.clone())
+            .or_default()
+            .push(item.to_block_item());
     }
 
-    Ok(blocks)
+    Ok(Block {
+        id: BlockId::from_order(block.order),
+        order: block.order,
+        name: block.name.clone(),
+        enabled: block.enabled,
+        tags: parse_tags(block.comment),
+        items,
+        span: block.span,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::filter::parser::blocks_with_lines::{BlockName, BlockWithLines};
+    use crate::filter::parser::blocks_with_lines::{parse_lines, BlockName, BlockWithLines};
+    use crate::filter::parser::lines::content_to_lines;
 
     #[test]
     fn test_parse_blocks_success() {
@@ -61,18 +104,18 @@ mod tests {
             BlockWithLines {
                 order: 1,
                 name: BlockName::Show,
-                lines: vec![
-                    "BaseType == \"Mirror of Kalandra\"".to_string(),
-                    "SetFontSize 45".to_string(),
-                ],
+                enabled: true,
+                lines: vec!["BaseType == \"Mirror of Kalandra\"", "SetFontSize 45"],
+                comment: "",
+                span: Span::new(0, 50),
             },
             BlockWithLines {
                 order: 2,
                 name: BlockName::Hide,
-                lines: vec![
-                    "Class \"Currency\"".to_string(),
-                    "SetFontSize 40".to_string(),
-                ],
+                enabled: true,
+                lines: vec!["Class \"Currency\"", "SetFontSize 40"],
+                comment: "",
+                span: Span::new(60, 100),
             },
         ];
 
@@ -85,6 +128,7 @@ mod tests {
 
         // First block
         assert_eq!(blocks[0].order, 1);
+        assert_eq!(blocks[0].id, BlockId(1));
         assert_eq!(blocks[0].name, BlockName::Show);
         assert_eq!(blocks[0].items.len(), 2);
 
@@ -92,27 +136,76 @@ mod tests {
         assert_eq!(blocks[1].order, 2);
         assert_eq!(blocks[1].name, BlockName::Hide);
         assert_eq!(blocks[1].items.len(), 2);
+        assert_eq!(blocks[1].span, Span::new(60, 100));
+    }
+
+    #[test]
+    fn test_parse_blocks_parallel_same_as_sequential() {
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("filter_examples/example_001.filter");
+        let content = std::fs::read_to_string(path).unwrap();
+        let lines = content_to_lines(&content);
+        let blocks_with_lines = parse_lines(&lines).blocks;
+
+        let sequential = parse_block_with_lines(&blocks_with_lines).unwrap();
+        let parallel = parse_block_with_lines_parallel(&blocks_with_lines).unwrap();
+
+        // Including the 75 disabled blocks of the filter
+        assert_eq!(parallel.len(), 380);
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_parse_blocks_parallel_returns_first_error() {
+        let blocks_with_lines = vec![
+            BlockWithLines {
+                order: 1,
+                name: BlockName::Show,
+                enabled: true,
+                lines: vec!["SetFontSize 45"],
+                comment: "",
+                span: Span::new(0, 20),
+            },
+            BlockWithLines {
+                order: 2,
+                name: BlockName::Hide,
+                enabled: true,
+                lines: vec!["\"\""],
+                comment: "",
+                span: Span::new(21, 30),
+            },
+        ];
+
+        let result = parse_block_with_lines_parallel(&blocks_with_lines);
+        assert_eq!(result, Err(ParseError::EmptyLine));
     }
 
     #[test]
     fn test_block_serde() {
         let block = Block {
+            id: BlockId(1),
             order: 1,
             name: BlockName::Show,
+            enabled: true,
+            tags: vec![BlockTag {
+                name: "$tier".to_string(),
+                value: "t1".to_string(),
+            }],
             items: HashMap::from([
-                ("BaseType".to_string(), vec![
+                ("BaseType".into(), vec![
                     BlockItem {
-                        name: "BaseType".to_string(),
+                        name: "BaseType".into(),
                         params: vec!["==".to_string(), "Mirror of Kalandra".to_string()],
                     },
                 ]),
-                ("SetFontSize".to_string(), vec![
+                ("SetFontSize".into(), vec![
                     BlockItem {
-                        name: "SetFontSize".to_string(),
+                        name: "SetFontSize".into(),
                         params: vec!["45".to_string()],
                     },
                 ]),
             ]),
+            span: Span::new(0, 50),
         };
 
         let json = serde_json::to_string(&block).unwrap();
@@ -120,5 +213,7 @@ mod tests {
 
         assert_eq!(block, decoded);
         assert_eq!(decoded.order, 1);
+        assert_eq!(decoded.id, BlockId(1));
+        assert_eq!(decoded.tag("$tier"), Some("t1"));
     }
 }
@@ -1,97 +1,179 @@
+use crate::filter::parser::diagnostics::Diagnostic;
+use crate::filter::parser::import::{parse_import, Import, IMPORT_KEYWORD};
+use crate::filter::parser::lines::Line;
+use crate::filter::parser::span::Span;
+use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 
-#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize, TS)]
+/// Keyword that starts a block. The line must start with exactly this word,
+/// so `ShowcaseItem` or `Hidden` do not start a block.
+///
+/// To support a new block keyword add a variant here, its name is the keyword.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Display,
+    EnumString,
+    serde::Serialize,
+    serde::Deserialize,
+    TS,
+)]
 #[ts(export)]
 pub enum BlockName {
     Show,
     Hide,
+    Minimal,
 }
 
-const BLOCK_NAMES: [&str; 2] = ["Show", "Hide"];
-
-impl BlockName {
-    fn from_str(s: &str) -> Option<Self> {
-        match s {
-            "Show" => Some(BlockName::Show),
-            "Hide" => Some(BlockName::Hide),
-            _ => None,
-        }
-    }
-}
-
+/// Lines of a block, borrowed from the filter content.
 #[derive(Debug, PartialEq)]
-pub struct BlockWithLines {
+pub struct BlockWithLines<'a> {
     // Position of the block in the file, starting from 1
-    pub order: u16,
+    pub order: usize,
     pub name: BlockName,
-    pub lines: Vec<String>,
+    // Disabled blocks are commented out and ignored by the game
+    pub enabled: bool,
+    pub lines: Vec<&'a str>,
+    // Comment of the block name line, which holds the tags of the block
+    pub comment: &'a str,
+    // From the start of the block name line to the end of the last line of the block
+    pub span: Span,
 }
 
-fn is_block_start(line: &str) -> Option<BlockName> {
-    BLOCK_NAMES
-        .iter()
-        .find(|&&name| line.starts_with(name))
-        .and_then(|&name| BlockName::from_str(name))
+/// Result of splitting the filter lines into blocks.
+#[derive(Debug, Default, PartialEq)]
+pub struct ParsedLines<'a> {
+    pub blocks: Vec<BlockWithLines<'a>>,
+    // `Import` directives found between the blocks
+    pub imports: Vec<Import>,
+    // Problems with lines that are not part of any block
+    pub diagnostics: Vec<Diagnostic>,
 }
 
-fn try_add_block_if_exists(
-    blocks: &mut Vec<BlockWithLines>,
-    lines: &[String],
-    start: Option<(usize, BlockName)>,
+/// Start of the current block: index of its name line, its name and whether it is enabled.
+type BlockStart = (usize, BlockName, bool);
+
+/// Returns the first word of the line.
+fn first_word(line: &str) -> Option<&str> {
+    line.split_whitespace().next()
+}
+
+fn try_add_block_if_exists<'a>(
+    blocks: &mut Vec<BlockWithLines<'a>>,
+    lines: &[Line<'a>],
+    start: Option<BlockStart>,
     end: usize,
-    order: &mut u16,
+    order: &mut usize,
 ) {
-    let Some((start, block_name)) = start else {
+    let Some((start, block_name, enabled)) = start else {
         return;
     };
 
-    let line_count = end.saturating_sub(start);
+    // Enabled blocks skip the commented out lines and disabled blocks consist of them
+    let block_lines: Vec<&Line<'a>> = lines[start + 1..=end]
+        .iter()
+        .filter(|line| line.disabled != enabled)
+        .collect();
 
-    if line_count == 0 {
+    let Some(last_line) = block_lines.last() else {
         // empty block
         return;
-    }
+    };
 
     blocks.push(BlockWithLines {
         order: *order,
         name: block_name,
-        lines: lines[start + 1..=end].to_vec(),
+        enabled,
+        lines: block_lines.iter().map(|line| line.text).collect(),
+        comment: lines[start].comment,
+        span: Span::new(lines[start].span.start, last_line.span.end),
     });
     *order += 1;
 }
 
-pub fn parse_lines(lines: &[String]) -> Vec<BlockWithLines> {
-    let mut blocks = Vec::new();
+/// Splits the filter lines into blocks.
+///
+/// A block starts with a block name line and ends before the next block name
+/// or `Import` line. Imports are collected separately. Other lines that are not
+/// inside a block, apart from empty lines and comments, are ignored and reported as warnings.
+///
+/// A commented out block name line starts a disabled block, which consists of
+/// the commented out lines that follow it.
+pub fn parse_lines<'a>(lines: &[Line<'a>]) -> ParsedLines<'a> {
+    let mut parsed = ParsedLines::default();
+    let blocks = &mut parsed.blocks;
     let mut current_block_start = None;
     let mut order = 1;
 
     for (i, line) in lines.iter().enumerate() {
-        let Some(block_name) = is_block_start(line) else {
+        let Some(word) = first_word(line.text) else {
             continue;
         };
 
-        try_add_block_if_exists(&mut blocks, lines, current_block_start, i.saturating_sub(1), &mut order);
-        current_block_start = Some((i, block_name));
+        if line.disabled {
+            if let Ok(block_name) = word.parse::<BlockName>() {
+                try_add_block_if_exists(blocks, lines, current_block_start, i.saturating_sub(1), &mut order);
+                current_block_start = Some((i, block_name, false));
+            }
+
+            continue;
+        }
+
+        if word == IMPORT_KEYWORD {
+            try_add_block_if_exists(blocks, lines, current_block_start.take(), i.saturating_sub(1), &mut order);
+            let (import, diagnostics) = parse_import(line.text, line.span);
+            parsed.imports.extend(import);
+            parsed.diagnostics.extend(diagnostics);
+            continue;
+        }
+
+        let Ok(block_name) = word.parse::<BlockName>() else {
+            if matches!(current_block_start, Some((_, _, false))) {
+                // An uncommented line ends a disabled block
+                try_add_block_if_exists(blocks, lines, current_block_start.take(), i.saturating_sub(1), &mut order);
+            }
+
+            if current_block_start.is_none() {
+                parsed.diagnostics.push(Diagnostic::warning(
+                    format!("`{}` is outside of a block and is ignored", word),
+                    line.span,
+                ));
+            }
+
+            continue;
+        };
+
+        try_add_block_if_exists(blocks, lines, current_block_start, i.saturating_sub(1), &mut order);
+        current_block_start = Some((i, block_name, true));
     }
 
     try_add_block_if_exists(
-        &mut blocks,
+        blocks,
         lines,
         current_block_start,
         lines.len().saturating_sub(1),
         &mut order,
     );
 
-    blocks
+    parsed
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::filter::parser::diagnostics::Severity;
+    use crate::filter::parser::lines::content_to_lines;
+
+    fn join_lines(content: &[&str]) -> String {
+        content.join("\n")
+    }
 
     #[test]
     fn test_parse_basic_blocks() {
-        let content: Vec<String> = vec![
+        let content = join_lines(&[
             "Show",
             "BaseType == \"Mirror of Kalandra\"",
             "SetFontSize 45",
@@ -101,12 +183,9 @@ mod tests {
             "Show",
             "Class \"Currency\"",
             "SetFontSize 40",
-        ]
-        .into_iter()
-        .map(Into::into)
-        .collect();
+        ]);
 
-        let blocks = parse_lines(&content);
+        let blocks = parse_lines(&content_to_lines(&content)).blocks;
 
         assert_eq!(blocks.len(), 3);
 
@@ -140,27 +219,33 @@ mod tests {
 
     #[test]
     fn test_single_block() {
-        let content: Vec<String> = vec!["Show", "BaseType == \"Mirror\""]
-            .into_iter()
-            .map(Into::into)
-            .collect();
+        let content = join_lines(&["Show", "BaseType == \"Mirror\""]);
 
-        let blocks = parse_lines(&content);
+        let blocks = parse_lines(&content_to_lines(&content)).blocks;
 
         assert_eq!(blocks.len(), 1);
         assert_eq!(blocks[0].order, 1);
         assert_eq!(blocks[0].name, BlockName::Show);
         assert_eq!(blocks[0].lines, vec!["BaseType == \"Mirror\""]);
+        assert_eq!(blocks[0].span, Span::new(0, 25));
+    }
+
+    #[test]
+    fn test_block_spans() {
+        let content = "Show\n\tBaseType \"Mirror\" # Rare\n\n# Comment\nHide\n\tClass \"Rings\"\n";
+        let blocks = parse_lines(&content_to_lines(content)).blocks;
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].span, Span::new(0, 30));
+        assert_eq!(blocks[1].span, Span::new(42, 61));
+        assert_eq!(&content[42..61], "Hide\n\tClass \"Rings\"");
     }
 
     #[test]
     fn test_ignore_block_without_content() {
-        let content: Vec<String> = vec!["Show", "Hide", "BaseType == \"Mirror\""]
-            .into_iter()
-            .map(Into::into)
-            .collect();
+        let content = join_lines(&["Show", "Hide", "BaseType == \"Mirror\""]);
 
-        let blocks = parse_lines(&content);
+        let blocks = parse_lines(&content_to_lines(&content)).blocks;
 
         assert_eq!(blocks.len(), 1);
         assert_eq!(blocks[0].name, BlockName::Hide);
@@ -169,20 +254,138 @@ mod tests {
 
     #[test]
     fn test_no_blocks() {
-        let content: Vec<String> = vec!["nothing to see here", "another one"]
-            .into_iter()
-            .map(Into::into)
-            .collect();
+        let content = join_lines(&["nothing to see here", "another one"]);
 
-        let blocks = parse_lines(&content);
+        let blocks = parse_lines(&content_to_lines(&content)).blocks;
 
         assert_eq!(blocks.len(), 0);
     }
 
     #[test]
     fn test_empty_content() {
-        let blocks = parse_lines(&[]);
+        let blocks = parse_lines(&[]).blocks;
 
         assert_eq!(blocks.len(), 0);
     }
+
+    #[test]
+    fn test_block_name_must_match_exactly() {
+        let content = join_lines(&[
+            "Show",
+            "BaseType == \"Mirror\"",
+            "ShowcaseItem",
+            "Hidden",
+            "Show#comment",
+            "Rarity Rare",
+        ]);
+
+        let blocks = parse_lines(&content_to_lines(&content)).blocks;
+
+        assert_eq!(blocks.len(), 2);
+
+        assert_eq!(
+            blocks[0].lines,
+            vec!["BaseType == \"Mirror\"", "ShowcaseItem", "Hidden"]
+        );
+
+        assert_eq!(blocks[1].lines, vec!["Rarity Rare"]);
+    }
+
+    #[test]
+    fn test_minimal_block() {
+        let content = join_lines(&["Minimal", "BaseType == \"Gold\""]);
+
+        let blocks = parse_lines(&content_to_lines(&content)).blocks;
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].name, BlockName::Minimal);
+        assert_eq!(blocks[0].name.to_string(), "Minimal");
+    }
+
+    #[test]
+    fn test_import_ends_block() {
+        let content = join_lines(&[
+            "Show",
+            "BaseType == \"Mirror\"",
+            "Import \"MyRules.filter\" Optional",
+            "Hide",
+            "Class \"Rings\"",
+        ]);
+
+        let parsed = parse_lines(&content_to_lines(&content));
+
+        assert_eq!(parsed.blocks.len(), 2);
+        assert_eq!(parsed.blocks[0].lines, vec!["BaseType == \"Mirror\""]);
+        assert_eq!(parsed.imports.len(), 1);
+        assert_eq!(parsed.imports[0].path, "MyRules.filter");
+        assert!(parsed.imports[0].optional);
+        assert_eq!(parsed.imports[0].span, Span::new(26, 58));
+        assert!(parsed.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_lines_outside_of_blocks() {
+        let content = join_lines(&[
+            "# Comment",
+            "",
+            "Sho",
+            "Show",
+            "BaseType == \"Mirror\"",
+            "Import \"MyRules.filter\"",
+            "SetFontSize 45",
+        ]);
+
+        let parsed = parse_lines(&content_to_lines(&content));
+
+        assert_eq!(parsed.blocks.len(), 1);
+        assert_eq!(parsed.diagnostics.len(), 2);
+        assert_eq!(parsed.diagnostics[0].severity, Severity::Warning);
+        assert_eq!(parsed.diagnostics[0].span, Span::new(11, 14));
+        assert_eq!(&content[65..79], "SetFontSize 45");
+        assert_eq!(parsed.diagnostics[1].span, Span::new(65, 79));
+    }
+
+    #[test]
+    fn test_disabled_block() {
+        let content = join_lines(&[
+            "Show",
+            "BaseType == \"Mirror\"",
+            "#\tSetFontSize 45",
+            "",
+            "#Hide # $tier->t1",
+            "#\tBaseType == \"Scroll of Wisdom\"",
+            "#\t# Comment",
+            "#\tSetFontSize 18",
+            "Show",
+            "Class \"Currency\"",
+        ]);
+
+        let blocks = parse_lines(&content_to_lines(&content)).blocks;
+
+        assert_eq!(blocks.len(), 3);
+        assert!(blocks[0].enabled);
+        assert_eq!(blocks[0].lines, vec!["BaseType == \"Mirror\""]);
+        assert_eq!(blocks[0].span, Span::new(0, 25));
+        assert!(!blocks[1].enabled);
+        assert_eq!(blocks[1].name, BlockName::Hide);
+        assert_eq!(blocks[1].comment, "$tier->t1");
+
+        assert_eq!(
+            blocks[1].lines,
+            vec!["BaseType == \"Scroll of Wisdom\"", "SetFontSize 18"]
+        );
+
+        assert!(blocks[2].enabled);
+    }
+
+    #[test]
+    fn test_uncommented_line_ends_disabled_block() {
+        let content = join_lines(&["#Show", "#\tRarity Rare", "Rarity Normal"]);
+
+        let parsed = parse_lines(&content_to_lines(&content));
+
+        assert_eq!(parsed.blocks.len(), 1);
+        assert_eq!(parsed.blocks[0].lines, vec!["Rarity Rare"]);
+        assert_eq!(parsed.diagnostics.len(), 1);
+    }
 }
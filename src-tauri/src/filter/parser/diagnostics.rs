@@ -0,0 +1,38 @@
+use crate::filter::parser::span::Span;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in the filter that does not stop it from being parsed,
+/// for example a line that is not inside any block.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    // Position of the problem in the filter content
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use encoding_rs::{UTF_16BE, UTF_16LE, WINDOWS_1252};
+use std::borrow::Cow;
+use std::io;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Text encoding of a filter file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    // Legacy encoding used by Windows editors, used when the file is not valid UTF-8
+    Windows1252,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the line ending used by most lines of the content, `Lf` if there are no lines.
+    pub fn detect(content: &str) -> Self {
+        let line_count = content.matches('\n').count();
+        let crlf_count = content.matches("\r\n").count();
+
+        match crlf_count * 2 > line_count {
+            true => LineEnding::CrLf,
+            false => LineEnding::Lf,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+
+    /// Returns the content with all line endings replaced with this one.
+    pub fn apply(self, content: &str) -> Cow<'_, str> {
+        let mixed = match self {
+            LineEnding::Lf => content.contains("\r\n"),
+            LineEnding::CrLf => content.matches('\n').count() != content.matches("\r\n").count(),
+        };
+
+        if !mixed {
+            return Cow::Borrowed(content);
+        }
+
+        let lines = content
+            .split_inclusive('\n')
+            .map(|line| match line.strip_suffix('\n') {
+                Some(line) => line.strip_suffix('\r').unwrap_or(line),
+                None => line,
+            });
+
+        let mut result = String::with_capacity(content.len());
+
+        for (i, line) in lines.enumerate() {
+            if i > 0 {
+                result.push_str(self.as_str());
+            }
+
+            result.push_str(line);
+        }
+
+        if content.ends_with('\n') {
+            result.push_str(self.as_str());
+        }
+
+        Cow::Owned(result)
+    }
+}
+
+/// How a filter file was stored on disk, so it can be written back the same way.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextFormat {
+    pub encoding: TextEncoding,
+    // The file started with a byte order mark
+    pub bom: bool,
+    pub line_ending: LineEnding,
+}
+
+/// Decodes the bytes of a filter file into text without the byte order mark.
+///
+/// The encoding is taken from the byte order mark. Without it the bytes are decoded as
+/// UTF-16 if they look like ASCII text with zero bytes in between, then as UTF-8,
+/// and finally as Windows-1252, which can decode any bytes. Invalid UTF-8 after a UTF-8
+/// byte order mark is decoded as Windows-1252 too, so no bytes are lost.
+pub fn decode(bytes: &[u8]) -> (String, TextFormat) {
+    let (encoding, bom, body) = if let Some(body) = bytes.strip_prefix(UTF8_BOM) {
+        let encoding = match std::str::from_utf8(body) {
+            Ok(_) => TextEncoding::Utf8,
+            Err(_) => TextEncoding::Windows1252,
+        };
+
+        (encoding, true, body)
+    } else if let Some(body) = bytes.strip_prefix(UTF16LE_BOM) {
+        (TextEncoding::Utf16Le, true, body)
+    } else if let Some(body) = bytes.strip_prefix(UTF16BE_BOM) {
+        (TextEncoding::Utf16Be, true, body)
+    } else {
+        (detect_encoding(bytes), false, bytes)
+    };
+
+    let content = match encoding {
+        TextEncoding::Utf8 => String::from_utf8(body.to_vec()).expect("checked to be UTF-8"),
+        TextEncoding::Utf16Le => UTF_16LE.decode_without_bom_handling(body).0.into_owned(),
+        TextEncoding::Utf16Be => UTF_16BE.decode_without_bom_handling(body).0.into_owned(),
+        TextEncoding::Windows1252 => WINDOWS_1252
+            .decode_without_bom_handling(body)
+            .0
+            .into_owned(),
+    };
+
+    let format = TextFormat {
+        encoding,
+        bom,
+        line_ending: LineEnding::detect(&content),
+    };
+
+    (content, format)
+}
+
+/// Encodes the content in the given format, replacing its line endings with the format's.
+///
+/// Returns an error if the content has characters that can not be stored in Windows-1252.
+pub fn encode(content: &str, format: &TextFormat) -> io::Result<Vec<u8>> {
+    let content = format.line_ending.apply(content);
+    let mut bytes = Vec::with_capacity(content.len() + 3);
+
+    match format.encoding {
+        TextEncoding::Utf8 => {
+            if format.bom {
+                bytes.extend_from_slice(UTF8_BOM);
+            }
+
+            bytes.extend_from_slice(content.as_bytes());
+        }
+        TextEncoding::Utf16Le => {
+            if format.bom {
+                bytes.extend_from_slice(UTF16LE_BOM);
+            }
+
+            bytes.extend(content.encode_utf16().flat_map(u16::to_le_bytes));
+        }
+        TextEncoding::Utf16Be => {
+            if format.bom {
+                bytes.extend_from_slice(UTF16BE_BOM);
+            }
+
+            bytes.extend(content.encode_utf16().flat_map(u16::to_be_bytes));
+        }
+        TextEncoding::Windows1252 => {
+            // Only a UTF-8 byte order mark can start a file decoded as Windows-1252
+            if format.bom {
+                bytes.extend_from_slice(UTF8_BOM);
+            }
+
+            let (encoded, _, had_errors) = WINDOWS_1252.encode(&content);
+
+            if had_errors {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "The filter has characters that can not be saved in Windows-1252 encoding",
+                ));
+            }
+
+            bytes.extend_from_slice(&encoded);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Guesses the encoding of bytes without a byte order mark.
+fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    // Zero bytes are valid UTF-8, so UTF-16 needs to be checked first
+    if looks_like_utf16(bytes, 1) {
+        TextEncoding::Utf16Le
+    } else if looks_like_utf16(bytes, 0) {
+        TextEncoding::Utf16Be
+    } else if std::str::from_utf8(bytes).is_ok() {
+        TextEncoding::Utf8
+    } else {
+        TextEncoding::Windows1252
+    }
+}
+
+/// Returns true if most bytes at odd (`zero_offset` = 1) or even (0) positions are zero,
+/// which is the case for mostly ASCII text in UTF-16.
+fn looks_like_utf16(bytes: &[u8], zero_offset: usize) -> bool {
+    let pairs = bytes.chunks_exact(2);
+
+    if pairs.len() == 0 || !pairs.remainder().is_empty() {
+        return false;
+    }
+
+    let pair_count = pairs.len();
+
+    let zeros = pairs
+        .filter(|pair| pair[zero_offset] == 0 && pair[1 - zero_offset] != 0)
+        .count();
+
+    zeros * 4 >= pair_count * 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn test_decode_utf8() {
+        let (content, format) = decode("Show\n\tBaseType \"Mjölnir\"\n".as_bytes());
+        assert_eq!(content, "Show\n\tBaseType \"Mjölnir\"\n");
+        assert_eq!(format, TextFormat::default());
+    }
+
+    #[test]
+    fn test_decode_utf8_with_bom() {
+        let (content, format) = decode(b"\xEF\xBB\xBFShow\r\n");
+        assert_eq!(content, "Show\r\n");
+        assert_eq!(format.encoding, TextEncoding::Utf8);
+        assert!(format.bom);
+        assert_eq!(format.line_ending, LineEnding::CrLf);
+    }
+
+    #[test]
+    fn test_decode_utf16_without_bom() {
+        let (content, format) = decode(&utf16le("Show\n\tRarity Rare"));
+        assert_eq!(content, "Show\n\tRarity Rare");
+        assert_eq!(format.encoding, TextEncoding::Utf16Le);
+        assert!(!format.bom);
+    }
+
+    #[test]
+    fn test_decode_windows_1252() {
+        let (content, format) = decode(b"BaseType \"Mj\xF6lnir\"");
+        assert_eq!(content, "BaseType \"Mjölnir\"");
+        assert_eq!(format.encoding, TextEncoding::Windows1252);
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8_with_bom() {
+        let bytes = b"\xEF\xBB\xBFBaseType \"Mj\xF6lnir\"";
+        let (content, format) = decode(bytes);
+        assert_eq!(content, "BaseType \"Mjölnir\"");
+        assert_eq!(format.encoding, TextEncoding::Windows1252);
+        assert!(format.bom);
+        assert_eq!(encode(&content, &format).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let variants = [
+            b"Show\n\tRarity Rare\n".to_vec(),
+            b"\xEF\xBB\xBFShow\r\n\tRarity Rare\r\n".to_vec(),
+            [UTF16LE_BOM, &utf16le("Show\r\n\tRarity Rare")].concat(),
+            b"\xFE\xFF\x00S\x00h\x00o\x00w".to_vec(),
+            b"BaseType \"Mj\xF6lnir\"\n".to_vec(),
+        ];
+
+        for bytes in variants {
+            let (content, format) = decode(&bytes);
+            assert_eq!(encode(&content, &format).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_encode_with_original_line_endings() {
+        let format = TextFormat {
+            line_ending: LineEnding::CrLf,
+            ..TextFormat::default()
+        };
+
+        let bytes = encode("Show\n\tRarity Rare\r\nHide\n", &format).unwrap();
+        assert_eq!(bytes, b"Show\r\n\tRarity Rare\r\nHide\r\n");
+    }
+
+    #[test]
+    fn test_encode_unsupported_windows_1252_character() {
+        let format = TextFormat {
+            encoding: TextEncoding::Windows1252,
+            ..TextFormat::default()
+        };
+
+        assert!(encode("Show # 😀", &format).is_err());
+    }
+
+    #[test]
+    fn test_detect_line_ending() {
+        assert_eq!(LineEnding::detect(""), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("a\r\nb\r\nc\n"), LineEnding::CrLf);
+        assert_eq!(LineEnding::detect("a\nb\nc\r\n"), LineEnding::Lf);
+    }
+
+    #[test]
+    fn test_apply_line_ending() {
+        assert_eq!(LineEnding::Lf.apply("a\r\nb\n"), "a\nb\n");
+        assert_eq!(LineEnding::CrLf.apply("a\nb"), "a\r\nb");
+        assert!(matches!(LineEnding::Lf.apply("a\nb"), Cow::Borrowed(_)));
+    }
+}
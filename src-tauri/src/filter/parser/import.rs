@@ -0,0 +1,105 @@
+use crate::filter::parser::block_item::parse_block_item;
+use crate::filter::parser::diagnostics::Diagnostic;
+use crate::filter::parser::span::Span;
+use ts_rs::TS;
+
+/// Keyword of the directive that imports the rules of another filter file.
+pub const IMPORT_KEYWORD: &str = "Import";
+
+/// An `Import "MyRules.filter" Optional` directive.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct Import {
+    // Path of the imported file as written in the filter
+    pub path: String,
+    // Optional imports are skipped if the file does not exist
+    pub optional: bool,
+    pub span: Span,
+}
+
+/// Parses an import directive line, for example `Import "MyRules.filter" Optional`.
+///
+/// Returns an error diagnostic if the file path is missing.
+/// Parameters after the path other than `Optional` are reported as warnings.
+pub fn parse_import(line: &str, span: Span) -> (Option<Import>, Vec<Diagnostic>) {
+    let mut diagnostics = Vec::new();
+
+    let params = match parse_block_item(line) {
+        Ok(item) => item.params,
+        Err(_) => Vec::new(),
+    };
+
+    let Some((path, options)) = params.split_first() else {
+        diagnostics.push(Diagnostic::error("Import is missing the file path", span));
+        return (None, diagnostics);
+    };
+
+    let mut optional = false;
+
+    for option in options {
+        match *option {
+            "Optional" => optional = true,
+            _ => diagnostics.push(Diagnostic::warning(
+                format!("Unknown Import option `{}`", option),
+                span,
+            )),
+        }
+    }
+
+    let import = Import {
+        path: path.to_string(),
+        optional,
+        span,
+    };
+
+    (Some(import), diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::diagnostics::Severity;
+
+    #[test]
+    fn test_parse_import() {
+        let span = Span::new(0, 24);
+        let (import, diagnostics) = parse_import("Import \"MyRules.filter\"", span);
+
+        assert_eq!(
+            import,
+            Some(Import {
+                path: "MyRules.filter".to_string(),
+                optional: false,
+                span,
+            })
+        );
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_parse_optional_import() {
+        let line = "Import \"My Optional Rules.filter\" Optional";
+        let (import, diagnostics) = parse_import(line, Span::default());
+        let import = import.unwrap();
+        assert_eq!(import.path, "My Optional Rules.filter");
+        assert!(import.optional);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_parse_import_without_path() {
+        let (import, diagnostics) = parse_import("Import", Span::default());
+        assert_eq!(import, None);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_parse_import_with_unknown_option() {
+        let (import, diagnostics) = parse_import("Import \"a.filter\" Maybe", Span::default());
+        assert!(!import.unwrap().optional);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+}
@@ -0,0 +1,352 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::filter::parser::block_id::{BlockId, BlockIdGenerator};
+use crate::filter::parser::blocks::Block;
+use crate::filter::parser::diagnostics::Diagnostic;
+use crate::filter::parser::import::Import;
+use crate::filter::parser::parse_file::{parse_content, ParsedFilter};
+use crate::filter::parser::read_from_disk::read_filter_from_disk_blocking;
+use crate::filter::parser::reference_index::ReferenceIndex;
+
+/// Files of a filter and the `Import` directives between them.
+///
+/// The file with index 0 is the opened filter file. Spans of blocks and diagnostics
+/// refer to the files by their index.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct IncludeGraph {
+    // Paths of the files, each file is listed once even if it is imported several times
+    pub files: Vec<String>,
+    pub edges: Vec<IncludeEdge>,
+}
+
+/// File `from` imports file `to`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct IncludeEdge {
+    pub from: usize,
+    pub to: usize,
+    pub optional: bool,
+}
+
+impl IncludeGraph {
+    /// Returns the indices of the files imported by `file`, in the order of the imports.
+    pub fn imports_of(&self, file: usize) -> Vec<usize> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.from == file)
+            .map(|edge| edge.to)
+            .collect()
+    }
+}
+
+/// Inlines the blocks of the files imported by the filter parsed from `path`.
+///
+/// Import paths are relative to the directory of the importing file. The blocks of an
+/// imported file are placed where its `Import` directive is, and the blocks are renumbered.
+/// Blocks of the filter keep their ids and the imported blocks get the ids after them.
+///
+/// A missing `Optional` import is reported as a warning, other missing imports and import
+/// cycles are reported as errors, and the rest of the filter is still used.
+///
+/// Returns an error if an imported file fails to parse.
+pub fn resolve_imports(path: &Path, filter: ParsedFilter) -> Result<ParsedFilter> {
+    let mut ids = BlockIdGenerator::after(&filter.blocks);
+    resolve_imports_with_ids(path, filter, &mut Vec::new(), || ids.next_id())
+}
+
+/// Same as [`resolve_imports`], but the imported blocks take their ids from `import_ids` in
+/// order, so that they keep their ids when the imports are resolved again.
+///
+/// Blocks without an id in `import_ids` get an id from `next_id`, which is added to
+/// `import_ids`.
+pub fn resolve_imports_with_ids(
+    path: &Path,
+    filter: ParsedFilter,
+    import_ids: &mut Vec<BlockId>,
+    mut next_id: impl FnMut() -> BlockId,
+) -> Result<ParsedFilter> {
+    let mut resolver = Resolver::default();
+    let root = resolver.add_file(path);
+    let metadata = filter.metadata.clone();
+    let imports = filter.imports.clone();
+    let mut blocks = resolver.inline(root, path, filter)?;
+    let mut imported = 0;
+
+    for (index, block) in blocks.iter_mut().enumerate() {
+        block.order = index + 1;
+
+        // Blocks of a file imported twice would have the same ids
+        if block.span.file != root {
+            if imported == import_ids.len() {
+                import_ids.push(next_id());
+            }
+
+            block.id = import_ids[imported];
+            imported += 1;
+        }
+    }
+
+    Ok(ParsedFilter {
+        metadata,
+        reference_index: ReferenceIndex::build(&blocks),
+        blocks,
+        imports,
+        diagnostics: resolver.diagnostics,
+        include_graph: resolver.graph,
+    })
+}
+
+#[derive(Default)]
+struct Resolver {
+    graph: IncludeGraph,
+    // Index of each file by its canonical path
+    file_ids: HashMap<PathBuf, usize>,
+    diagnostics: Vec<Diagnostic>,
+    // Files that are currently being inlined, used to detect import cycles
+    stack: Vec<usize>,
+}
+
+impl Resolver {
+    /// Returns the index of the file, adding it to the graph if needed.
+    fn add_file(&mut self, path: &Path) -> usize {
+        let key = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+        if let Some(&id) = self.file_ids.get(&key) {
+            return id;
+        }
+
+        let id = self.graph.files.len();
+        self.graph.files.push(path.to_string_lossy().to_string());
+        self.file_ids.insert(key, id);
+        id
+    }
+
+    /// Returns the blocks of the file with the blocks of its imports inlined.
+    fn inline(&mut self, file: usize, path: &Path, filter: ParsedFilter) -> Result<Vec<Block>> {
+        self.stack.push(file);
+
+        self.diagnostics
+            .extend(filter.diagnostics.into_iter().map(|diagnostic| Diagnostic {
+                span: diagnostic.span.in_file(file),
+                ..diagnostic
+            }));
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let mut blocks = Vec::with_capacity(filter.blocks.len());
+        let mut file_blocks = filter.blocks.into_iter().peekable();
+
+        for import in filter.imports {
+            let import_span = import.span.in_file(file);
+
+            while let Some(block) = file_blocks.next_if(|b| b.span.start < import_span.start) {
+                blocks.push(in_file(block, file));
+            }
+
+            blocks.extend(self.inline_import(file, directory, &import)?);
+        }
+
+        blocks.extend(file_blocks.map(|block| in_file(block, file)));
+        self.stack.pop();
+        Ok(blocks)
+    }
+
+    /// Reads, parses and inlines a single imported file.
+    fn inline_import(
+        &mut self,
+        file: usize,
+        directory: &Path,
+        import: &Import,
+    ) -> Result<Vec<Block>> {
+        let span = import.span.in_file(file);
+        let path = directory.join(&import.path);
+
+        let content = match read_filter_from_disk_blocking(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && import.optional => {
+                let message = format!("Optional import `{}` was not found", import.path);
+                self.diagnostics.push(Diagnostic::warning(message, span));
+                return Ok(Vec::new());
+            }
+            Err(e) => {
+                let message = format!("Failed to read import `{}`: {}", import.path, e);
+                self.diagnostics.push(Diagnostic::error(message, span));
+                return Ok(Vec::new());
+            }
+        };
+
+        let imported = self.add_file(&path);
+
+        self.graph.edges.push(IncludeEdge {
+            from: file,
+            to: imported,
+            optional: import.optional,
+        });
+
+        if let Some(position) = self.stack.iter().position(|&id| id == imported) {
+            let cycle: Vec<&str> = self.stack[position..]
+                .iter()
+                .chain([&imported])
+                .map(|&id| self.graph.files[id].as_str())
+                .collect();
+
+            let message = format!("Import cycle: {}", cycle.join(" -> "));
+            self.diagnostics.push(Diagnostic::error(message, span));
+            return Ok(Vec::new());
+        }
+
+        let filter = parse_content(&content)
+            .with_context(|| format!("Failed to parse imported file {}", path.display()))?;
+
+        self.inline(imported, &path, filter)
+    }
+}
+
+fn in_file(block: Block, file: usize) -> Block {
+    Block {
+        span: block.span.in_file(file),
+        ..block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::blocks_with_lines::BlockName;
+    use crate::filter::parser::diagnostics::Severity;
+    use crate::filter::parser::span::Span;
+    use tempfile::{tempdir, TempDir};
+
+    fn write_files(files: &[(&str, &str)]) -> TempDir {
+        let dir = tempdir().unwrap();
+
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        dir
+    }
+
+    fn resolve(dir: &TempDir, name: &str) -> ParsedFilter {
+        let path = dir.path().join(name);
+        let content = std::fs::read_to_string(&path).unwrap();
+        resolve_imports(&path, parse_content(&content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_inline_imported_blocks() {
+        let dir = write_files(&[
+            (
+                "main.filter",
+                "Show\n\tRarity Unique\nImport \"rules/shared.filter\"\nHide\n\tRarity Normal\n",
+            ),
+            ("rules/shared.filter", "Show\n\tClass \"Rings\"\n"),
+        ]);
+
+        let filter = resolve(&dir, "main.filter");
+        let blocks = &filter.blocks;
+        assert_eq!(blocks.len(), 3);
+        assert!(blocks[1].items.contains_key("Class"));
+        assert_eq!(blocks[2].name, BlockName::Hide);
+
+        assert_eq!(
+            blocks.iter().map(|b| b.order).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+
+        assert_eq!(
+            blocks.iter().map(|b| b.span.file).collect::<Vec<_>>(),
+            [0, 1, 0]
+        );
+
+        assert_eq!(blocks.iter().map(|b| b.id.0).collect::<Vec<_>>(), [1, 3, 2]);
+
+        assert_eq!(blocks[1].span, Span::new(0, 19).in_file(1));
+        assert!(filter.include_graph.files[1].ends_with("shared.filter"));
+        assert_eq!(filter.include_graph.imports_of(0), [1]);
+        assert_eq!(filter.diagnostics, vec![]);
+    }
+
+    #[test]
+    fn test_import_relative_to_importing_file() {
+        let dir = write_files(&[
+            ("main.filter", "Import \"rules/a.filter\"\n"),
+            ("rules/a.filter", "Import \"b.filter\"\n"),
+            ("rules/b.filter", "Show\n\tRarity Rare\n"),
+        ]);
+
+        let filter = resolve(&dir, "main.filter");
+        assert_eq!(filter.blocks.len(), 1);
+        assert_eq!(filter.blocks[0].span.file, 2);
+        assert_eq!(filter.include_graph.imports_of(1), [2]);
+    }
+
+    #[test]
+    fn test_same_file_imported_twice() {
+        let dir = write_files(&[
+            ("main.filter", "Import \"a.filter\"\nImport \"a.filter\"\n"),
+            ("a.filter", "Show\n\tRarity Rare\n"),
+        ]);
+
+        let filter = resolve(&dir, "main.filter");
+        assert_eq!(filter.blocks.len(), 2);
+        assert_eq!(filter.include_graph.files.len(), 2);
+        assert_eq!(filter.include_graph.imports_of(0), [1, 1]);
+        assert_eq!(filter.diagnostics, vec![]);
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let dir = write_files(&[
+            ("main.filter", "Show\n\tRarity Rare\nImport \"a.filter\"\n"),
+            (
+                "a.filter",
+                "Import \"main.filter\"\nHide\n\tRarity Normal\n",
+            ),
+        ]);
+
+        let filter = resolve(&dir, "main.filter");
+        assert_eq!(filter.blocks.len(), 2);
+        assert_eq!(filter.diagnostics.len(), 1);
+        let diagnostic = &filter.diagnostics[0];
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert!(diagnostic.message.starts_with("Import cycle"));
+        assert_eq!(diagnostic.span, Span::new(0, 20).in_file(1));
+        assert_eq!(filter.include_graph.imports_of(1), [0]);
+    }
+
+    #[test]
+    fn test_missing_imports() {
+        let dir = write_files(&[(
+            "main.filter",
+            "Import \"optional.filter\" Optional\nImport \"required.filter\"\nShow\n\tRarity Rare\n",
+        )]);
+
+        let filter = resolve(&dir, "main.filter");
+        assert_eq!(filter.blocks.len(), 1);
+
+        let severities: Vec<Severity> = filter.diagnostics.iter().map(|d| d.severity).collect();
+        assert_eq!(severities, [Severity::Warning, Severity::Error]);
+        assert_eq!(filter.include_graph.files.len(), 1);
+        assert!(filter.include_graph.edges.is_empty());
+    }
+
+    #[test]
+    fn test_imported_file_fails_to_parse() {
+        let dir = write_files(&[
+            ("main.filter", "Import \"a.filter\"\n"),
+            ("a.filter", "Show\n\t\"\"\n"),
+        ]);
+
+        let path = dir.path().join("main.filter");
+        let content = std::fs::read_to_string(&path).unwrap();
+        let result = resolve_imports(&path, parse_content(&content).unwrap());
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("a.filter"), "{}", error);
+    }
+}
@@ -0,0 +1,503 @@
+use anyhow::{bail, Result};
+use std::ops::Range;
+
+use crate::filter::parser::block_id::{reuse_block_ids, BlockId, BlockIdGenerator};
+use crate::filter::parser::blocks::{parse_block_with_lines, Block};
+use crate::filter::parser::blocks_with_lines::parse_lines;
+use crate::filter::parser::lines::{content_to_lines, is_comment_boundary};
+use crate::filter::parser::metadata::parse_metadata;
+use crate::filter::parser::parse_file::{parse_content, ParsedFilter};
+use crate::filter::parser::reference_index::ReferenceIndex;
+use crate::filter::parser::span::Span;
+
+/// A change of the filter content: the bytes in `start..end` are replaced with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+/// Keeps the filter content together with its parsed blocks and updates the blocks
+/// after an edit by re-parsing only the blocks affected by the edit.
+///
+/// The result is the same as parsing the edited content from scratch with [`parse_content`],
+/// apart from block ids. Blocks before the edited region keep their order and spans, blocks
+/// after it are moved by the length difference of the edit. All blocks that still exist after
+/// the edit keep their ids.
+pub struct IncrementalParser {
+    content: String,
+    filter: ParsedFilter,
+    ids: BlockIdGenerator,
+}
+
+impl IncrementalParser {
+    /// Parses the whole `content`.
+    pub fn new(content: String) -> Result<Self> {
+        let filter = parse_content(&content)?;
+        let ids = BlockIdGenerator::after(&filter.blocks);
+        Ok(IncrementalParser {
+            content,
+            filter,
+            ids,
+        })
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub fn filter(&self) -> &ParsedFilter {
+        &self.filter
+    }
+
+    /// Replaces the ids of the blocks in order, for example to restore them after an undo.
+    pub fn set_block_ids(&mut self, ids: &[BlockId]) {
+        for (block, id) in self.filter.blocks.iter_mut().zip(ids) {
+            block.id = *id;
+        }
+
+        self.ids.skip_used(&self.filter.blocks);
+    }
+
+    /// Returns an id that is not used by any block.
+    pub fn next_block_id(&mut self) -> BlockId {
+        self.ids.next_id()
+    }
+
+    /// Parses the whole new `content`, for example after the file was changed on disk.
+    ///
+    /// The blocks get the ids of the matching previous blocks, see [`reuse_block_ids`], and
+    /// the other blocks get ids that this parser never used, not even for deleted blocks.
+    /// The content and blocks are left unchanged if parsing fails.
+    pub fn replace_content(&mut self, content: String) -> Result<()> {
+        let mut filter = parse_content(&content)?;
+        reuse_block_ids(&self.filter.blocks, &mut filter.blocks, &mut self.ids);
+        self.content = content;
+        self.filter = filter;
+        Ok(())
+    }
+
+    /// Applies the edit to the content and re-parses the affected blocks.
+    ///
+    /// Returns the indices of the blocks that were re-parsed.
+    /// The content and blocks are left unchanged if the edit is invalid or parsing fails.
+    pub fn apply_edit(&mut self, edit: &TextEdit) -> Result<Range<usize>> {
+        if edit.start > edit.end || edit.end > self.content.len() {
+            bail!(
+                "Edit range {}..{} is outside of the content of length {}",
+                edit.start,
+                edit.end,
+                self.content.len()
+            );
+        }
+
+        if !self.content.is_char_boundary(edit.start) || !self.content.is_char_boundary(edit.end) {
+            bail!("Edit range {}..{} splits a character", edit.start, edit.end);
+        }
+
+        let delta = edit.replacement.len() as isize - (edit.end - edit.start) as isize;
+        let mut content = String::with_capacity(self.content.len().saturating_add_signed(delta));
+        content.push_str(&self.content[..edit.start]);
+        content.push_str(&edit.replacement);
+        content.push_str(&self.content[edit.end..]);
+
+        let (mut first, mut after) = affected_blocks(&self.filter.blocks, edit);
+        let blocks = &self.filter.blocks;
+
+        // Whether comment lines are a disabled block depends on the comment lines around them,
+        // so the region doesn't split consecutive comment lines
+        while first > 0 && !is_comment_boundary(&self.content, blocks[first].span.start) {
+            first -= 1;
+        }
+
+        let is_end = |block: &Block| {
+            is_comment_boundary(&content, block.span.start.saturating_add_signed(delta))
+        };
+
+        while after < blocks.len() && !is_end(&blocks[after]) {
+            after += 1;
+        }
+
+        // The affected region starts at a block name line and ends right before the next one,
+        // so it can be parsed on its own.
+        let region_start = match first {
+            0 => 0,
+            _ => blocks[first].span.start,
+        };
+
+        let region_end = blocks
+            .get(after)
+            .map_or(self.content.len(), |block| block.span.start);
+
+        let region = &content[region_start..region_end.saturating_add_signed(delta)];
+        let lines = content_to_lines(region);
+        let parsed_lines = parse_lines(&lines);
+        let mut reparsed = parse_block_with_lines(&parsed_lines.blocks)?;
+
+        for block in &mut reparsed {
+            block.span = block.span.shifted(region_start as isize);
+        }
+
+        keep_block_ids(
+            &self.filter.blocks[first..after],
+            &mut reparsed,
+            edit,
+            delta,
+            &mut self.ids,
+        );
+
+        let old_region = region_start..region_end;
+        let filter = &mut self.filter;
+
+        splice_region(
+            &mut filter.imports,
+            parsed_lines.imports,
+            &old_region,
+            delta,
+            |import| &mut import.span,
+        );
+
+        splice_region(
+            &mut filter.diagnostics,
+            parsed_lines.diagnostics,
+            &old_region,
+            delta,
+            |d| &mut d.span,
+        );
+
+        let reparsed_count = reparsed.len();
+        let blocks = &mut self.filter.blocks;
+        blocks.splice(first..after, reparsed);
+
+        for block in &mut blocks[first + reparsed_count..] {
+            block.span = block.span.shifted(delta);
+        }
+
+        for (index, block) in blocks.iter_mut().enumerate().skip(first) {
+            block.order = index + 1;
+        }
+
+        self.filter.metadata = parse_metadata(&content);
+        self.filter.reference_index = ReferenceIndex::build(&self.filter.blocks);
+        self.content = content;
+        Ok(first..first + reparsed_count)
+    }
+}
+
+/// Replaces the items that start inside `region` with `reparsed`, whose spans are relative
+/// to the region start, and moves the items after the region by `delta`.
+///
+/// The items are sorted by position, so the order stays the same as after a full parse.
+fn splice_region<T>(
+    items: &mut Vec<T>,
+    mut reparsed: Vec<T>,
+    region: &Range<usize>,
+    delta: isize,
+    span: fn(&mut T) -> &mut Span,
+) {
+    let first = items
+        .iter_mut()
+        .position(|item| span(item).start >= region.start)
+        .unwrap_or(items.len());
+
+    let after = items
+        .iter_mut()
+        .position(|item| span(item).start >= region.end)
+        .unwrap_or(items.len());
+
+    for item in &mut reparsed {
+        *span(item) = span(item).shifted(region.start as isize);
+    }
+
+    let reparsed_count = reparsed.len();
+    items.splice(first..after, reparsed);
+
+    for item in &mut items[first + reparsed_count..] {
+        *span(item) = span(item).shifted(delta);
+    }
+}
+
+/// Gives the re-parsed blocks the ids of the previous blocks.
+///
+/// A re-parsed block gets the id of the previous block whose name line starts at the same
+/// place of the edited content. The remaining blocks take the unused previous ids in order
+/// and then new ids.
+fn keep_block_ids(
+    previous: &[Block],
+    reparsed: &mut [Block],
+    edit: &TextEdit,
+    delta: isize,
+    ids: &mut BlockIdGenerator,
+) {
+    // Start of each previous block in the edited content, `None` if the edit replaced it
+    let mut previous: Vec<(Option<usize>, BlockId)> = previous
+        .iter()
+        .map(|block| {
+            let start = block.span.start;
+
+            let start = match start {
+                _ if start < edit.start => Some(start),
+                _ if start >= edit.end => Some(start.saturating_add_signed(delta)),
+                _ => None,
+            };
+
+            (start, block.id)
+        })
+        .collect();
+
+    let mut unmatched = Vec::new();
+
+    for (index, block) in reparsed.iter_mut().enumerate() {
+        let same_start = previous
+            .iter()
+            .position(|(start, _)| *start == Some(block.span.start));
+
+        match same_start {
+            Some(position) => block.id = previous.remove(position).1,
+            None => unmatched.push(index),
+        }
+    }
+
+    let mut unused = previous.into_iter().map(|(_, id)| id);
+
+    for index in unmatched {
+        reparsed[index].id = unused.next().unwrap_or_else(|| ids.next_id());
+    }
+}
+
+/// Returns the range of blocks that need to be re-parsed after the edit.
+///
+/// The block before the edited one is included as well, since removing a block name
+/// moves the lines of the block into the previous block.
+fn affected_blocks(blocks: &[Block], edit: &TextEdit) -> (usize, usize) {
+    let first = blocks
+        .iter()
+        .position(|block| block.span.end >= edit.start)
+        .unwrap_or(blocks.len())
+        .saturating_sub(1);
+
+    let after = blocks
+        .iter()
+        .rposition(|block| block.span.start <= edit.end)
+        .map_or(0, |last| last + 1)
+        .max(first);
+
+    (first, after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const CONTENT: &str = "# VERSION: 1.0.0
+Show # $tier->t1
+\tBaseType == \"Mirror of Kalandra\"
+\tSetFontSize 45
+
+Hide
+\tBaseType \"Scroll of Wisdom\"
+
+Show
+\tClass \"Currency\"
+\tSetFontSize 40
+";
+
+    fn edit(start: usize, end: usize, replacement: &str) -> TextEdit {
+        TextEdit {
+            start,
+            end,
+            replacement: replacement.to_string(),
+        }
+    }
+
+    /// Returns the filter with the block ids of a fresh parse.
+    fn with_fresh_ids(filter: &ParsedFilter) -> ParsedFilter {
+        let mut filter = filter.clone();
+
+        for block in &mut filter.blocks {
+            block.id = BlockId::from_order(block.order);
+        }
+
+        filter
+    }
+
+    fn ids(parser: &IncrementalParser) -> Vec<u32> {
+        parser
+            .filter()
+            .blocks
+            .iter()
+            .map(|block| block.id.0)
+            .collect()
+    }
+
+    fn assert_same_as_full_parse(parser: &IncrementalParser) {
+        let expected = parse_content(parser.content()).unwrap();
+        assert_eq!(with_fresh_ids(parser.filter()), expected);
+    }
+
+    #[test]
+    fn test_edit_inside_block() {
+        let mut parser = IncrementalParser::new(CONTENT.to_string()).unwrap();
+        let start = CONTENT.find("45").unwrap();
+        let reparsed = parser.apply_edit(&edit(start, start + 2, "42")).unwrap();
+
+        assert_eq!(reparsed, 0..1);
+        assert_same_as_full_parse(&parser);
+        let font_size = &parser.filter().blocks[0].items["SetFontSize"][0];
+        assert_eq!(font_size.params, vec!["42"]);
+    }
+
+    #[test]
+    fn test_edit_keeps_spans_of_other_blocks() {
+        let mut parser = IncrementalParser::new(CONTENT.to_string()).unwrap();
+        let before = parser.filter().blocks.clone();
+        let start = CONTENT.find("Scroll").unwrap();
+        parser.apply_edit(&edit(start, start, "Big ")).unwrap();
+
+        let blocks = &parser.filter().blocks;
+        assert_eq!(blocks[0], before[0]);
+        assert_eq!(blocks[2].span, before[2].span.shifted(4));
+        assert_same_as_full_parse(&parser);
+    }
+
+    #[test]
+    fn test_add_block() {
+        let mut parser = IncrementalParser::new(CONTENT.to_string()).unwrap();
+        let start = CONTENT.find("Hide").unwrap();
+        let new_block = "Show\n\tRarity Unique\n";
+        let reparsed = parser.apply_edit(&edit(start, start, new_block)).unwrap();
+
+        assert_eq!(reparsed, 0..3);
+        assert_eq!(parser.filter().blocks.len(), 4);
+        assert_eq!(ids(&parser), [1, 4, 2, 3]);
+        assert_same_as_full_parse(&parser);
+    }
+
+    #[test]
+    fn test_remove_block_name() {
+        let mut parser = IncrementalParser::new(CONTENT.to_string()).unwrap();
+        let start = CONTENT.find("Hide").unwrap();
+        parser.apply_edit(&edit(start, start + 4, "")).unwrap();
+
+        assert_eq!(parser.filter().blocks.len(), 2);
+        assert_eq!(ids(&parser), [1, 3]);
+        assert_same_as_full_parse(&parser);
+    }
+
+    #[test]
+    fn test_edit_keeps_block_ids() {
+        let mut parser = IncrementalParser::new(CONTENT.to_string()).unwrap();
+        let start = CONTENT.find("Hide").unwrap();
+        parser.apply_edit(&edit(start, start + 4, "Show")).unwrap();
+        parser.apply_edit(&edit(0, 0, "# New header\n")).unwrap();
+
+        assert_eq!(ids(&parser), [1, 2, 3]);
+        assert_same_as_full_parse(&parser);
+    }
+
+    #[test]
+    fn test_replace_content_keeps_ids_of_deleted_blocks_unused() {
+        let mut parser = IncrementalParser::new(CONTENT.to_string()).unwrap();
+        let start = CONTENT.rfind("Show").unwrap();
+        parser.apply_edit(&edit(start, CONTENT.len(), "")).unwrap();
+        assert_eq!(ids(&parser), [1, 2]);
+
+        let content = CONTENT.replace("\tSetFontSize 40\n", "\tSetFontSize 36\n");
+        let content = content.replace("Scroll of Wisdom", "Portal Scroll");
+        parser.replace_content(content).unwrap();
+
+        assert_eq!(ids(&parser), [1, 2, 4]);
+        assert_same_as_full_parse(&parser);
+    }
+
+    #[test]
+    fn test_edit_header() {
+        let mut parser = IncrementalParser::new(CONTENT.to_string()).unwrap();
+        parser.apply_edit(&edit(11, 16, "2.0.0")).unwrap();
+
+        let version = parser.filter().metadata.version.clone().unwrap();
+        assert_eq!(version.to_string(), "2.0.0");
+        assert_same_as_full_parse(&parser);
+    }
+
+    #[test]
+    fn test_invalid_edit() {
+        let mut parser = IncrementalParser::new(CONTENT.to_string()).unwrap();
+        let length = CONTENT.len();
+        assert!(parser.apply_edit(&edit(5, 2, "")).is_err());
+        assert!(parser.apply_edit(&edit(0, length + 1, "")).is_err());
+        assert_eq!(parser.content(), CONTENT);
+    }
+
+    fn filter_line() -> impl Strategy<Value = String> {
+        prop::sample::select(vec![
+            "Show",
+            "Hide",
+            "Show # $tier->t1",
+            "\tBaseType == \"Mirror\"",
+            "\tClass \"Rings\" \"Amulets\"",
+            "\tSetFontSize 40",
+            "# Comment",
+            "",
+            "  ",
+            "Sho",
+            "w",
+            "\tRarity Rare # Rare",
+            "Import \"Rules.filter\" Optional",
+            "Import",
+            "Minimal",
+            "ShowcaseItem",
+            "Hidden",
+            "#Show",
+            "#Hide # $tier->t2",
+            "#\tSetFontSize 40",
+            "#\tClass \"Rings\" # Rings",
+        ])
+        .prop_map(String::from)
+    }
+
+    fn filter_content() -> impl Strategy<Value = String> {
+        (
+            prop::collection::vec(filter_line(), 0..30),
+            prop::sample::select(vec!["\n", "\r\n"]),
+        )
+            .prop_map(|(lines, line_ending)| lines.join(line_ending))
+    }
+
+    proptest! {
+        #[test]
+        fn prop_incremental_parse_equals_full_parse(
+            content in filter_content(),
+            edits in prop::collection::vec(
+                (any::<prop::sample::Index>(), 0..40usize, filter_content()),
+                1..5,
+            ),
+        ) {
+            let mut parser = IncrementalParser::new(content).unwrap();
+
+            for (start, length, replacement) in edits {
+                let content_length = parser.content().len();
+                let start = start.index(content_length + 1);
+                let end = (start + length).min(content_length);
+                let mut edited = parser.content().to_string();
+                edited.replace_range(start..end, &replacement);
+                let result = parser.apply_edit(&edit(start, end, &replacement));
+
+                match parse_content(&edited) {
+                    Ok(expected) => {
+                        prop_assert!(result.is_ok());
+                        prop_assert_eq!(parser.content(), edited);
+                        prop_assert_eq!(with_fresh_ids(parser.filter()), expected);
+                        let mut block_ids = ids(&parser);
+                        block_ids.sort_unstable();
+                        block_ids.dedup();
+                        prop_assert_eq!(block_ids.len(), parser.filter().blocks.len());
+                    }
+                    Err(_) => prop_assert!(result.is_err()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use std::borrow::Cow;
+
+/// Name of a block item, such as `BaseType` or `SetFontSize`.
+///
+/// Names listed in [`KEYWORDS`] are interned and do not allocate.
+pub type Keyword = Cow<'static, str>;
+
+/// Conditions, actions and other statements that can be used inside loot filter blocks,
+/// sorted alphabetically.
+pub const KEYWORDS: [&str; 64] = [
+    "AlternateQuality",
+    "AnyEnchantment",
+    "ArchnemesisMod",
+    "AreaLevel",
+    "BaseArmour",
+    "BaseDefencePercentile",
+    "BaseEnergyShield",
+    "BaseEvasion",
+    "BaseType",
+    "BaseWard",
+    "BlightedMap",
+    "Class",
+    "Continue",
+    "Corrupted",
+    "CorruptedMods",
+    "CustomAlertSound",
+    "CustomAlertSoundOptional",
+    "DisableDropSound",
+    "DisableDropSoundIfAlertSound",
+    "DropLevel",
+    "ElderItem",
+    "ElderMap",
+    "EnableDropSound",
+    "EnableDropSoundIfAlertSound",
+    "EnchantmentPassiveNode",
+    "EnchantmentPassiveNum",
+    "FracturedItem",
+    "GemLevel",
+    "GemQualityType",
+    "HasCruciblePassiveTree",
+    "HasEaterOfWorldsImplicit",
+    "HasEnchantment",
+    "HasExplicitMod",
+    "HasImplicitMod",
+    "HasInfluence",
+    "HasSearingExarchImplicit",
+    "Height",
+    "Identified",
+    "ItemLevel",
+    "LinkedSockets",
+    "MapTier",
+    "MinimapIcon",
+    "Mirrored",
+    "PlayAlertSound",
+    "PlayAlertSoundPositional",
+    "PlayEffect",
+    "Quality",
+    "Rarity",
+    "Replica",
+    "Scourged",
+    "SetBackgroundColor",
+    "SetBorderColor",
+    "SetFontSize",
+    "SetTextColor",
+    "ShapedMap",
+    "ShaperItem",
+    "SocketGroup",
+    "Sockets",
+    "StackSize",
+    "SynthesisedItem",
+    "TransfiguredGem",
+    "UberBlightedMap",
+    "WaystoneTier",
+    "Width",
+];
+
+/// Keywords that change how a matched item is shown or heard, sorted alphabetically.
+/// The other keywords are conditions.
+pub const ACTIONS: [&str; 14] = [
+    "CustomAlertSound",
+    "CustomAlertSoundOptional",
+    "DisableDropSound",
+    "DisableDropSoundIfAlertSound",
+    "EnableDropSound",
+    "EnableDropSoundIfAlertSound",
+    "MinimapIcon",
+    "PlayAlertSound",
+    "PlayAlertSoundPositional",
+    "PlayEffect",
+    "SetBackgroundColor",
+    "SetBorderColor",
+    "SetFontSize",
+    "SetTextColor",
+];
+
+/// Returns true if `name` is one of the known keywords.
+pub fn is_keyword(name: &str) -> bool {
+    KEYWORDS.binary_search(&name).is_ok()
+}
+
+/// Returns true if `name` is an action keyword, such as `SetFontSize`.
+pub fn is_action(name: &str) -> bool {
+    ACTIONS.binary_search(&name).is_ok()
+}
+
+/// Returns the interned keyword for `name`, or an owned copy if the name is not a known keyword.
+pub fn intern_keyword(name: &str) -> Keyword {
+    match KEYWORDS.binary_search(&name) {
+        Ok(index) => Cow::Borrowed(KEYWORDS[index]),
+        Err(_) => Cow::Owned(name.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keywords_are_sorted() {
+        assert!(KEYWORDS.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(ACTIONS.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(ACTIONS.iter().all(|action| is_keyword(action)));
+    }
+
+    #[test]
+    fn test_intern_known_keyword() {
+        let keyword = intern_keyword("SetFontSize");
+        assert!(matches!(keyword, Cow::Borrowed(_)));
+        assert_eq!(keyword, "SetFontSize");
+    }
+
+    #[test]
+    fn test_intern_unknown_keyword() {
+        let keyword = intern_keyword("UnknownName");
+        assert!(matches!(keyword, Cow::Owned(_)));
+        assert_eq!(keyword, "UnknownName");
+    }
+
+    #[test]
+    fn test_is_keyword() {
+        assert!(is_keyword("BaseType"));
+        assert!(!is_keyword("Basetype"));
+    }
+}
@@ -1,25 +1,163 @@
-use super::remove_comment::remove_comment;
+use super::blocks_with_lines::BlockName;
+use super::keywords::is_keyword;
+use super::remove_comment::{comment, remove_comment};
+use super::span::Span;
+
+/// A line of the loot filter with comment and surrounding whitespace removed.
+/// The text is borrowed from the filter content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub text: &'a str,
+    // Position of the original line in the content, without the line ending
+    pub span: Span,
+    // The line is a commented out line of a disabled block
+    pub disabled: bool,
+    // Comment at the end of the line, without the # symbol
+    pub comment: &'a str,
+}
 
 /// Parses content of a loot filter file into a vector of lines.
-pub fn content_to_lines(content: &str) -> Vec<String> {
+///
+/// Comment lines are skipped, apart from the commented out lines of disabled blocks,
+/// which are returned with `disabled` set. A disabled block is written by NeverSink as:
+///
+///   #Show # $tier->t1
+///   #\tBaseType == "Mirror of Kalandra"
+///
+/// The block name line must start the comment lines, after an empty or uncommented line,
+/// and all of them must be commented out keyword lines or notes. Otherwise the comment lines
+/// are ordinary comments.
+pub fn content_to_lines(content: &str) -> Vec<Line<'_>> {
+    let mut raw_lines = Vec::new();
+    let mut start = 0;
+
+    for raw_line in content.split_inclusive('\n') {
+        let line = match raw_line.strip_suffix('\n') {
+            Some(line) => line.strip_suffix('\r').unwrap_or(line),
+            None => raw_line,
+        };
+
+        raw_lines.push((line, Span::new(start, start + line.len())));
+        start += raw_line.len();
+    }
+
     let mut lines = Vec::new();
+    let mut rest = raw_lines.as_slice();
 
-    for line in content.lines() {
-        let line = remove_comment(line);
-        let line = line.trim();
+    while let Some(&(line, span)) = rest.first() {
+        let text = remove_comment(line).trim();
 
-        if !line.is_empty() {
-            lines.push(line.to_string());
+        if !text.is_empty() {
+            lines.push(Line {
+                text,
+                span,
+                disabled: false,
+                comment: comment(line),
+            });
+            rest = &rest[1..];
+            continue;
         }
+
+        // Consecutive comment lines are either disabled blocks or ordinary comments
+        let comments: Vec<(CommentLine<'_>, Span)> = rest
+            .iter()
+            .map_while(|&(line, span)| Some((comment_line(line)?, span)))
+            .collect();
+
+        rest = &rest[comments.len().max(1)..];
+
+        if !matches!(comments.first(), Some((CommentLine::Name(_), _)))
+            || comments.iter().any(|(line, _)| *line == CommentLine::Other)
+        {
+            continue;
+        }
+
+        lines.extend(comments.into_iter().filter_map(|(line, span)| match line {
+            CommentLine::Name(line) | CommentLine::Keyword(line) => Some(Line { span, ..line }),
+            _ => None,
+        }));
     }
 
     lines
 }
 
+/// Returns true if the line at `start` doesn't continue the comment lines before it, so
+/// that the content from `start` on can be split into lines without the content before it.
+pub fn is_comment_boundary(content: &str, start: usize) -> bool {
+    let line = content[start..].lines().next().unwrap_or_default();
+
+    match content[..start].lines().next_back() {
+        Some(previous) => comment_line(line).is_none() || comment_line(previous).is_none(),
+        None => true,
+    }
+}
+
+/// A comment line, as a part of a disabled block or as an ordinary comment.
+#[derive(Debug, PartialEq)]
+enum CommentLine<'a> {
+    // Block name directly after `#` at the start of the line, as in `#Show`
+    Name(Line<'a>),
+    // Line of a block after `#` at the start of the line, as in `#\tBaseType "Mirror"`
+    Keyword(Line<'a>),
+    // Comment without text, as in `#\t# note`, or indented like the lines of a block
+    Note,
+    // Any other text, such as `# Quality gems below`
+    Other,
+}
+
+/// Returns the kind of a comment line, or `None` if the line is not a comment.
+///
+/// Consecutive comment lines are disabled blocks if they start with a block name line and
+/// have no `Other` lines, so ordinary comments and a `#Show` within a section header are not
+/// mistaken for disabled blocks. Notes are kept as comments of the blocks.
+fn comment_line(line: &str) -> Option<CommentLine<'_>> {
+    let commented = line.trim_start().strip_prefix('#')?;
+    let text = remove_comment(commented).trim();
+    let word = text.split_whitespace().next();
+
+    let disabled_line = Line {
+        text,
+        span: Span::default(),
+        disabled: true,
+        comment: comment(commented),
+    };
+
+    let kind = if line.starts_with(char::is_whitespace) || text.is_empty() {
+        CommentLine::Note
+    } else if !commented.starts_with(char::is_whitespace) && text.parse::<BlockName>().is_ok() {
+        CommentLine::Name(disabled_line)
+    } else if word.is_some_and(is_keyword) || has_keyword_shape(commented) {
+        CommentLine::Keyword(disabled_line)
+    } else {
+        CommentLine::Other
+    };
+
+    Some(kind)
+}
+
+/// Returns true if the text after `#` looks like a commented out line of a block, even with
+/// a keyword the parser doesn't know: a capitalized word such as `ZanaMemory`, directly after
+/// the `#` or indented with a tab or several spaces.
+///
+/// A single space after `#` is how ordinary comments are written, as in `# Rings below`.
+fn has_keyword_shape(commented: &str) -> bool {
+    let text = commented.trim_start();
+    let indent = &commented[..commented.len() - text.len()];
+    let word = text.split_whitespace().next().unwrap_or_default();
+
+    indent != " "
+        && word.starts_with(|c: char| c.is_ascii_uppercase())
+        && word.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn texts<'a>(lines: &[Line<'a>]) -> Vec<&'a str> {
+        lines.iter().map(|line| line.text).collect()
+    }
+
     #[test]
     fn test_basic_parsing() {
         let content = r#"Show
@@ -32,7 +170,7 @@ BaseType "Scroll of Wisdom""#;
         let lines = content_to_lines(content);
 
         assert_eq!(
-            lines,
+            texts(&lines),
             vec![
                 "Show",
                 "BaseType \"Mirror of Kalandra\"",
@@ -54,7 +192,7 @@ BaseType "Scroll of Wisdom""#;
     fn test_tabs_and_spaces() {
         let content = "\t  Show  \t\n\t\tBaseType \"Mirror\"  \t";
         let lines = content_to_lines(content);
-        assert_eq!(lines, vec!["Show", "BaseType \"Mirror\""]);
+        assert_eq!(texts(&lines), vec!["Show", "BaseType \"Mirror\""]);
     }
 
     #[test]
@@ -68,6 +206,52 @@ BaseType "Scroll of Wisdom""#;
 "#;
 
         let lines = content_to_lines(content);
-        assert_eq!(lines, vec!["Show", "BaseType \"Mirror\""]);
+        assert_eq!(texts(&lines), vec!["Show", "BaseType \"Mirror\""]);
+    }
+
+    #[test]
+    fn test_line_spans() {
+        let content = "Show # Comment\r\n\r\n\tBaseType \"Mirror\"\n";
+        let lines = content_to_lines(content);
+
+        assert_eq!(lines[0].span, Span::new(0, 14));
+        assert_eq!(&content[0..14], "Show # Comment");
+        assert_eq!(lines[1].span, Span::new(18, 36));
+        assert_eq!(&content[18..36], "\tBaseType \"Mirror\"");
+    }
+
+    #[test]
+    fn test_disabled_lines() {
+        let content =
+            "#Show # $tier->t1\n#\tBaseType \"Mirror\"\n#\t# Note\n\n# Show all rings\n#\n#====\n";
+        let lines = content_to_lines(content);
+
+        assert_eq!(texts(&lines), vec!["Show", "BaseType \"Mirror\""]);
+        assert!(lines.iter().all(|line| line.disabled));
+        assert_eq!(lines[0].comment, "$tier->t1");
+        assert_eq!(lines[1].span, Span::new(18, 37));
+    }
+
+    #[test]
+    fn test_comments_are_not_disabled_blocks() {
+        let content = r#"# Quality gems below
+#Show
+Show
+	Class "Gems"
+
+#Show gems with quality
+#	Quality > 0
+
+#Show
+# Rings are next
+#	Class "Rings"
+
+#Hide
+#	Class "Flasks"
+#===="#;
+
+        let lines = content_to_lines(content);
+        assert_eq!(texts(&lines), vec!["Show", "Class \"Gems\""]);
+        assert!(lines.iter().all(|line| !line.disabled));
     }
 }
@@ -0,0 +1,334 @@
+use std::fmt;
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+
+/// Information about the filter taken from the comment header at the top of the file.
+///
+/// NeverSink's filters start with a header like:
+///
+///   # VERSION:  0.52.0b
+///   # TYPE:     2-SEMI-STRICT
+///   # STYLE:    DEFAULT
+///   # AUTHOR:   NeverSink
+///
+/// Fields that are missing from the header are `None`.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct FilterMetadata {
+    pub version: Option<FilterVersion>,
+    pub strictness: Option<Strictness>,
+    pub style: Option<String>,
+    pub author: Option<String>,
+    pub build_notes: Option<String>,
+}
+
+impl FilterMetadata {
+    /// Returns a short human readable summary, for example "NeverSink 0.52.0b Semi-Strict".
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+
+        if let Some(author) = &self.author {
+            parts.push(author.clone());
+        }
+
+        if let Some(version) = &self.version {
+            parts.push(version.to_string());
+        }
+
+        if let Some(strictness) = &self.strictness {
+            parts.push(strictness.to_string());
+        }
+
+        parts.join(" ")
+    }
+}
+
+/// Version of the filter, for example `0.52.0b`.
+///
+/// Versions are ordered by major, minor and patch numbers first and then by suffix,
+/// so `0.52.0` < `0.52.0b` < `0.52.1`.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize, TS,
+)]
+#[ts(export)]
+pub struct FilterVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    // Letters after the patch number, for example "b" in 0.52.0b
+    pub suffix: Option<String>,
+}
+
+impl FilterVersion {
+    /// Parses a version string such as "0.52.0b" or "1.2".
+    ///
+    /// Returns `None` if the string does not start with a number.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let suffix_start = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+
+        let (numbers, suffix) = s.split_at(suffix_start);
+
+        let mut numbers = numbers
+            .split('.')
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse::<u32>());
+
+        let major = numbers.next()?.ok()?;
+        let minor = numbers.next().unwrap_or(Ok(0)).ok()?;
+        let patch = numbers.next().unwrap_or(Ok(0)).ok()?;
+
+        if numbers.next().is_some() {
+            return None;
+        }
+
+        let suffix = suffix.trim_start_matches(['-', '+']);
+
+        Some(FilterVersion {
+            major,
+            minor,
+            patch,
+            suffix: (!suffix.is_empty()).then(|| suffix.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for FilterVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+
+        if let Some(suffix) = &self.suffix {
+            write!(f, "{}", suffix)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Strictness level of a NeverSink filter, from the most permissive to the most strict.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Display,
+    EnumString,
+    serde::Serialize,
+    serde::Deserialize,
+    TS,
+)]
+#[ts(export)]
+pub enum Strictness {
+    #[strum(to_string = "Soft", serialize = "SOFT")]
+    Soft,
+    #[strum(to_string = "Regular", serialize = "REGULAR")]
+    Regular,
+    #[strum(to_string = "Semi-Strict", serialize = "SEMI-STRICT")]
+    SemiStrict,
+    #[strum(to_string = "Strict", serialize = "STRICT")]
+    Strict,
+    #[strum(to_string = "Very Strict", serialize = "VERY-STRICT")]
+    VeryStrict,
+    #[strum(to_string = "Uber Strict", serialize = "UBER-STRICT")]
+    UberStrict,
+    #[strum(to_string = "Uber Plus Strict", serialize = "UBER-PLUS-STRICT")]
+    UberPlusStrict,
+}
+
+impl Strictness {
+    /// All strictness levels, from the most permissive to the most strict.
+    pub const ALL: [Strictness; 7] = [
+        Strictness::Soft,
+        Strictness::Regular,
+        Strictness::SemiStrict,
+        Strictness::Strict,
+        Strictness::VeryStrict,
+        Strictness::UberStrict,
+        Strictness::UberPlusStrict,
+    ];
+
+    /// Number of the level used in the header and in the `%H3` and `%D3` block tags,
+    /// 0 for Soft.
+    pub fn level(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the `TYPE` value of the header, for example "2-SEMI-STRICT".
+    pub fn header_value(self) -> String {
+        let name = self.to_string().to_uppercase().replace(' ', "-");
+        format!("{}-{}", self.level(), name)
+    }
+
+    /// Parses the `TYPE` value of the header, for example "2-SEMI-STRICT".
+    ///
+    /// The leading number is optional.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_uppercase();
+        let name = s.trim_start_matches(|c: char| c.is_ascii_digit());
+        let name = name.strip_prefix('-').unwrap_or(name);
+        name.parse().ok()
+    }
+}
+
+/// Extracts the filter metadata from the comment header at the top of the filter content.
+///
+/// Only the comment lines before the first rule are examined.
+pub fn parse_metadata(content: &str) -> FilterMetadata {
+    let mut metadata = FilterMetadata::default();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let Some(comment) = line.strip_prefix('#') else {
+            // The header ends at the first rule
+            break;
+        };
+
+        let Some((key, value)) = comment.split_once(':') else {
+            continue;
+        };
+
+        let value = value.trim();
+
+        if value.is_empty() {
+            continue;
+        }
+
+        match key.trim().to_uppercase().as_str() {
+            "VERSION" => metadata.version = FilterVersion::parse(value),
+            "TYPE" => metadata.strictness = Strictness::parse(value),
+            "STYLE" => metadata.style = Some(value.to_string()),
+            "AUTHOR" => metadata.author = Some(value.to_string()),
+            "BUILDNOTES" => metadata.build_notes = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metadata() {
+        let content = r#"#==========================================
+# NeverSink's Indepth Loot Filter - for Path of Exile 2
+#==========================================
+# VERSION:  0.52.0b
+# TYPE:     2-SEMI-STRICT
+# STYLE:    DEFAULT
+# AUTHOR:   NeverSink
+# BUILDNOTES: Filter generated with NeverSink's FilterpolishZ and the domainlanguage Exo.
+#
+# EDIT/CUSTOMIZE FILTER ON: 	https://www.FilterBlade.xyz
+
+Show
+    BaseType == "Gold""#;
+
+        let metadata = parse_metadata(content);
+
+        assert_eq!(
+            metadata.version,
+            Some(FilterVersion {
+                major: 0,
+                minor: 52,
+                patch: 0,
+                suffix: Some("b".to_string())
+            })
+        );
+
+        assert_eq!(metadata.strictness, Some(Strictness::SemiStrict));
+        assert_eq!(metadata.style, Some("DEFAULT".to_string()));
+        assert_eq!(metadata.author, Some("NeverSink".to_string()));
+
+        assert_eq!(
+            metadata.build_notes,
+            Some(
+                "Filter generated with NeverSink's FilterpolishZ and the domainlanguage Exo."
+                    .to_string()
+            )
+        );
+
+        assert_eq!(metadata.summary(), "NeverSink 0.52.0b Semi-Strict");
+    }
+
+    #[test]
+    fn test_parse_metadata_stops_at_first_rule() {
+        let content = "Show\n# VERSION: 1.0.0";
+        let metadata = parse_metadata(content);
+        assert_eq!(metadata, FilterMetadata::default());
+    }
+
+    #[test]
+    fn test_parse_metadata_without_header() {
+        let metadata = parse_metadata("");
+        assert_eq!(metadata, FilterMetadata::default());
+        assert_eq!(metadata.summary(), "");
+    }
+
+    #[test]
+    fn test_parse_version() {
+        let version = FilterVersion::parse("1.2").unwrap();
+        assert_eq!(version.to_string(), "1.2.0");
+
+        assert_eq!(
+            FilterVersion::parse("0.52.0b").unwrap().to_string(),
+            "0.52.0b"
+        );
+
+        assert_eq!(FilterVersion::parse("beta"), None);
+        assert_eq!(FilterVersion::parse("1.2.3.4"), None);
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        let v = |s| FilterVersion::parse(s).unwrap();
+        assert!(v("0.52.0") < v("0.52.0b"));
+        assert!(v("0.52.0b") < v("0.52.1"));
+        assert!(v("0.9.9") < v("0.52.0"));
+        assert!(v("1.0.0") > v("0.99.99"));
+    }
+
+    #[test]
+    fn test_parse_strictness() {
+        assert_eq!(Strictness::parse("0-SOFT"), Some(Strictness::Soft));
+
+        assert_eq!(
+            Strictness::parse("2-SEMI-STRICT"),
+            Some(Strictness::SemiStrict)
+        );
+
+        assert_eq!(
+            Strictness::parse("6-UBER-PLUS-STRICT"),
+            Some(Strictness::UberPlusStrict)
+        );
+
+        assert_eq!(Strictness::parse("strict"), Some(Strictness::Strict));
+        assert_eq!(Strictness::parse("unknown"), None);
+        assert!(Strictness::Soft < Strictness::UberStrict);
+    }
+
+    #[test]
+    fn test_strictness_header_value() {
+        for strictness in Strictness::ALL {
+            assert_eq!(
+                Strictness::parse(&strictness.header_value()),
+                Some(strictness)
+            );
+        }
+
+        assert_eq!(Strictness::VeryStrict.header_value(), "4-VERY-STRICT");
+    }
+}
@@ -1,8 +1,21 @@
+pub mod block_id;
 pub mod block_item;
 pub mod blocks;
 pub mod blocks_with_lines;
+pub mod diagnostics;
+pub mod encoding;
 pub mod errors;
+pub mod import;
+pub mod include_graph;
+pub mod incremental;
+pub mod keywords;
 pub mod lines;
+pub mod metadata;
 pub mod parse_file;
 pub mod read_from_disk;
+pub mod reference_index;
 pub mod remove_comment;
+pub mod sections;
+pub mod span;
+pub mod tags;
+pub mod write_to_disk;
@@ -1,24 +1,74 @@
 use anyhow::Result;
 use std::path::Path;
+use ts_rs::TS;
 
-use crate::filter::parser::blocks::{parse_block_with_lines, Block};
+use crate::filter::parser::blocks::{
+    parse_block_with_lines, parse_block_with_lines_parallel, Block,
+};
 use crate::filter::parser::blocks_with_lines::parse_lines;
+use crate::filter::parser::diagnostics::Diagnostic;
+use crate::filter::parser::import::Import;
+use crate::filter::parser::include_graph::{resolve_imports, IncludeGraph};
 use crate::filter::parser::lines::content_to_lines;
+use crate::filter::parser::metadata::{parse_metadata, FilterMetadata};
 use crate::filter::parser::read_from_disk::read_filter_from_disk;
+use crate::filter::parser::reference_index::ReferenceIndex;
+
+/// Filters with at least this many blocks are parsed on multiple threads.
+/// For smaller filters the cost of distributing the work is higher than the gain.
+const PARALLEL_PARSE_MIN_BLOCKS: usize = 1000;
+
+/// A parsed loot filter: the metadata from its header and its blocks.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct ParsedFilter {
+    pub metadata: FilterMetadata,
+    pub blocks: Vec<Block>,
+    // `Import` directives of the filter, the imported files are not read
+    pub imports: Vec<Import>,
+    // Problems that did not stop the filter from being parsed
+    pub diagnostics: Vec<Diagnostic>,
+    // Files of the filter, empty if the filter was not read from a file
+    pub include_graph: IncludeGraph,
+    // Blocks by the base types and classes they match, it is not sent to the UI
+    #[serde(skip)]
+    #[ts(skip)]
+    pub reference_index: ReferenceIndex,
+}
 
 /// Parses a loot filter file.
 ///
 /// This function reads the file from disk, processes its raw content into lines,
 /// and then parses the remaining lines into blocks.
+/// Blocks of the imported files are inlined, see [`resolve_imports`].
 ///
-/// Returns a vector of parsed Block on success or an error if processing fails.
-pub async fn parse_file(path: &Path) -> Result<Vec<Block>> {
+/// Returns the parsed filter on success or an error if processing fails.
+pub async fn parse_file(path: &Path) -> Result<ParsedFilter> {
     let content = read_filter_from_disk(path).await?;
-    let lines = content_to_lines(&content);
-    let blocks_with_lines = parse_lines(&lines);
-    let blocks: Vec<Block> = parse_block_with_lines(&blocks_with_lines)?;
+    resolve_imports(path, parse_content(&content)?)
+}
+
+/// Parses the content of a loot filter into its metadata and blocks.
+pub fn parse_content(content: &str) -> Result<ParsedFilter> {
+    let metadata = parse_metadata(content);
+    let lines = content_to_lines(content);
+    let parsed_lines = parse_lines(&lines);
+    let blocks_with_lines = &parsed_lines.blocks;
+
+    let blocks: Vec<Block> = if blocks_with_lines.len() >= PARALLEL_PARSE_MIN_BLOCKS {
+        parse_block_with_lines_parallel(blocks_with_lines)?
+    } else {
+        parse_block_with_lines(blocks_with_lines)?
+    };
 
-    Ok(blocks)
+    Ok(ParsedFilter {
+        metadata,
+        reference_index: ReferenceIndex::build(&blocks),
+        blocks,
+        imports: parsed_lines.imports,
+        diagnostics: parsed_lines.diagnostics,
+        include_graph: IncludeGraph::default(),
+    })
 }
 
 #[cfg(test)]
@@ -35,7 +85,13 @@ mod tests {
         let duration = start.elapsed();
         println!("parse_file() took {:?}", duration);
         assert!(result.is_ok());
-        let blocks = result.unwrap();
-        assert_eq!(blocks.len(), 305);
+        let filter = result.unwrap();
+        // 305 enabled blocks and the 75 `#Show` and `#Hide` paragraphs that NeverSink
+        // ships commented out
+        assert_eq!(filter.blocks.len(), 380);
+        assert_eq!(filter.blocks.iter().filter(|block| block.enabled).count(), 305);
+        assert_eq!(filter.metadata.summary(), "NeverSink 0.52.0b Semi-Strict");
+        assert_eq!(filter.diagnostics, vec![]);
+        assert_eq!(filter.include_graph.files.len(), 1);
     }
 }
@@ -1,13 +1,32 @@
+use crate::filter::parser::encoding::{decode, TextFormat};
 use std::path::Path;
 use tokio::fs;
 
+/// Reads a filter file, detecting its encoding and removing the byte order mark.
 pub async fn read_filter_from_disk<P: AsRef<Path>>(path: P) -> Result<String, std::io::Error> {
-    fs::read_to_string(path).await
+    Ok(read_filter_with_format(path).await?.0)
+}
+
+/// Same as [`read_filter_from_disk`] but also returns the format of the file,
+/// which is needed to write it back the same way.
+pub async fn read_filter_with_format<P: AsRef<Path>>(
+    path: P,
+) -> Result<(String, TextFormat), std::io::Error> {
+    let bytes = fs::read(path).await?;
+    Ok(decode(&bytes))
+}
+
+/// Blocking version of [`read_filter_from_disk`] for code running outside of the async runtime.
+pub fn read_filter_from_disk_blocking<P: AsRef<Path>>(path: P) -> Result<String, std::io::Error> {
+    let bytes = std::fs::read(path)?;
+    Ok(decode(&bytes).0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::filter::parser::encoding::{LineEnding, TextEncoding};
+    use crate::filter::parser::parse_file::parse_content;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -26,6 +45,50 @@ mod tests {
         assert_eq!(result.unwrap(), test_content);
     }
 
+    #[tokio::test]
+    async fn test_read_filter_with_bom() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file
+            .write_all(b"\xEF\xBB\xBFShow\r\n\tRarity Rare")
+            .unwrap();
+
+        let (content, format) = read_filter_with_format(temp_file.path()).await.unwrap();
+
+        assert_eq!(content, "Show\r\n\tRarity Rare");
+        assert!(format.bom);
+        assert_eq!(format.line_ending, LineEnding::CrLf);
+        let filter = parse_content(&content).unwrap();
+        assert_eq!(filter.blocks.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_read_utf16_filter() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let bytes: Vec<u8> = "\u{FEFF}Show\n\tRarity Rare"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+
+        temp_file.write_all(&bytes).unwrap();
+
+        let (content, format) = read_filter_with_format(temp_file.path()).await.unwrap();
+
+        assert_eq!(content, "Show\n\tRarity Rare");
+        assert_eq!(format.encoding, TextEncoding::Utf16Le);
+    }
+
+    #[tokio::test]
+    async fn test_read_windows_1252_filter() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file
+            .write_all(b"Show\n\tBaseType \"Mj\xF6lnir\"")
+            .unwrap();
+
+        let content = read_filter_from_disk_blocking(temp_file.path()).unwrap();
+
+        assert_eq!(content, "Show\n\tBaseType \"Mjölnir\"");
+    }
+
     #[tokio::test]
     async fn test_read_filter_from_disk_nonexistent_file() {
         // Try to read a file that doesn't exist
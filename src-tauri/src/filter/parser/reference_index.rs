@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use ts_rs::TS;
+
+use crate::filter::parser::block_id::BlockId;
+use crate::filter::parser::block_item::BlockItem;
+use crate::filter::parser::blocks::Block;
+use crate::filter::parser::keywords::is_action;
+
+/// Condition that names item types, looked up by [`ReferenceIndex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub enum ReferenceKind {
+    BaseType,
+    Class,
+}
+
+impl ReferenceKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            ReferenceKind::BaseType => "BaseType",
+            ReferenceKind::Class => "Class",
+        }
+    }
+}
+
+/// A block whose `BaseType` or `Class` condition matches the looked up name.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct BlockReference {
+    pub order: usize,
+    pub id: BlockId,
+    // The block lists the name with `==`, otherwise one of its values is a part of the name
+    pub exact: bool,
+    // False if the block is disabled or an earlier block always catches the items first
+    pub can_fire: bool,
+    // Order of the earlier block that catches the items first
+    pub shadowed_by: Option<usize>,
+}
+
+/// Blocks of a filter by the `BaseType` and `Class` values they list.
+///
+/// It is built when the filter is parsed, so looking up a name does not scan every block.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReferenceIndex {
+    base_types: ValueIndex,
+    classes: ValueIndex,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ValueIndex {
+    // Indices of the blocks by the lowercase values they match with `==`
+    exact: HashMap<String, Vec<usize>>,
+    // Indices of the blocks by the lowercase values they match as a part of the name
+    partial: HashMap<String, Vec<usize>>,
+}
+
+impl ReferenceIndex {
+    pub fn build(blocks: &[Block]) -> Self {
+        let mut index = ReferenceIndex::default();
+
+        for (position, block) in blocks.iter().enumerate() {
+            for kind in [ReferenceKind::BaseType, ReferenceKind::Class] {
+                let values = index.values_mut(kind);
+
+                for item in block.items.get(kind.keyword()).into_iter().flatten() {
+                    let Some((exact, names)) = item_values(item) else {
+                        continue;
+                    };
+
+                    let map = match exact {
+                        true => &mut values.exact,
+                        false => &mut values.partial,
+                    };
+
+                    for name in names {
+                        let positions = map.entry(name.to_lowercase()).or_default();
+
+                        if positions.last() != Some(&position) {
+                            positions.push(position);
+                        }
+                    }
+                }
+            }
+        }
+
+        index
+    }
+
+    fn values(&self, kind: ReferenceKind) -> &ValueIndex {
+        match kind {
+            ReferenceKind::BaseType => &self.base_types,
+            ReferenceKind::Class => &self.classes,
+        }
+    }
+
+    fn values_mut(&mut self, kind: ReferenceKind) -> &mut ValueIndex {
+        match kind {
+            ReferenceKind::BaseType => &mut self.base_types,
+            ReferenceKind::Class => &mut self.classes,
+        }
+    }
+
+    /// Returns the indices of the blocks that match the name, in file order,
+    /// and whether each of them matches it exactly.
+    fn matching_blocks(&self, kind: ReferenceKind, name: &str) -> Vec<(usize, bool)> {
+        let values = self.values(kind);
+        let name = name.to_lowercase();
+        let mut matches: HashMap<usize, bool> = HashMap::new();
+
+        for (value, positions) in &values.partial {
+            if name.contains(value.as_str()) {
+                matches.extend(positions.iter().map(|&position| (position, false)));
+            }
+        }
+
+        for &position in values.exact.get(&name).into_iter().flatten() {
+            matches.insert(position, true);
+        }
+
+        let mut matches: Vec<(usize, bool)> = matches.into_iter().collect();
+        matches.sort_unstable();
+        matches
+    }
+}
+
+/// Lists the blocks that reference the base type or class `name`.
+///
+/// A reference can fire if the block is enabled and no earlier block catches all of its
+/// items. An earlier block catches them if it has no `Continue`, matches the name, and every
+/// other condition of it is also a condition of the referencing block.
+pub fn find_references(
+    blocks: &[Block],
+    index: &ReferenceIndex,
+    kind: ReferenceKind,
+    name: &str,
+) -> Vec<BlockReference> {
+    index
+        .matching_blocks(kind, name)
+        .into_iter()
+        .map(|(position, exact)| {
+            let block = &blocks[position];
+
+            let shadowed_by = blocks[..position]
+                .iter()
+                .find(|earlier| catches_first(earlier, block, kind, name))
+                .map(|earlier| earlier.order);
+
+            BlockReference {
+                order: block.order,
+                id: block.id,
+                exact,
+                can_fire: block.enabled && shadowed_by.is_none(),
+                shadowed_by,
+            }
+        })
+        .collect()
+}
+
+/// Returns true if every item with the name that reaches `block` is caught by `earlier`.
+fn catches_first(earlier: &Block, block: &Block, kind: ReferenceKind, name: &str) -> bool {
+    if !earlier.enabled || earlier.items.contains_key("Continue") {
+        return false;
+    }
+
+    let name = name.to_lowercase();
+
+    earlier
+        .items
+        .iter()
+        .filter(|(keyword, _)| !is_action(keyword) && *keyword != "Continue")
+        .all(|(keyword, items)| {
+            if keyword == kind.keyword() {
+                return items.iter().all(|item| matches_name(item, &name));
+            }
+
+            let conditions = block.items.get(keyword);
+            items
+                .iter()
+                .all(|item| conditions.is_some_and(|c| c.contains(item)))
+        })
+}
+
+/// Returns true if the `BaseType` or `Class` item matches the lowercase name.
+fn matches_name(item: &BlockItem, name: &str) -> bool {
+    let Some((exact, values)) = item_values(item) else {
+        return false;
+    };
+
+    values.iter().any(|value| match exact {
+        true => value.to_lowercase() == name,
+        false => name.contains(&value.to_lowercase()),
+    })
+}
+
+/// Returns whether the item matches its values with `==` and the values.
+/// Returns `None` for negated items, such as `BaseType != "Ring"`.
+fn item_values(item: &BlockItem) -> Option<(bool, &[String])> {
+    match item.params.split_first() {
+        Some((operator, values)) if operator == "==" => Some((true, values)),
+        Some((operator, values)) if operator == "=" => Some((false, values)),
+        Some((operator, _)) if operator == "!=" || operator == "!" => None,
+        _ => Some((false, &item.params)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::parse_file::parse_content;
+
+    const CONTENT: &str = "\
+Show
+\tBaseType == \"Time-Lost Diamond\"
+\tSetFontSize 45
+
+Show
+\tBaseType \"Diamond\"
+\tRarity Rare
+
+Show
+\tClass \"Jewels\"
+\tBaseType \"Time-Lost\"
+
+#Show
+#\tBaseType == \"Time-Lost Diamond\"
+
+Show
+\tBaseType != \"Time-Lost Diamond\"
+";
+
+    fn lookup(kind: ReferenceKind, name: &str) -> Vec<BlockReference> {
+        let filter = parse_content(CONTENT).unwrap();
+        find_references(&filter.blocks, &filter.reference_index, kind, name)
+    }
+
+    #[test]
+    fn test_find_base_type_references() {
+        let references = lookup(ReferenceKind::BaseType, "time-lost diamond");
+        let orders: Vec<usize> = references.iter().map(|r| r.order).collect();
+        assert_eq!(orders, [1, 2, 3, 4]);
+
+        let exact: Vec<bool> = references.iter().map(|r| r.exact).collect();
+        assert_eq!(exact, [true, false, false, true]);
+
+        // The first block catches all Time-Lost Diamonds
+        assert!(references[0].can_fire);
+        assert_eq!(references[1].shadowed_by, Some(1));
+        assert_eq!(references[2].shadowed_by, Some(1));
+        assert!(!references[3].can_fire);
+    }
+
+    #[test]
+    fn test_earlier_block_with_other_conditions_does_not_shadow() {
+        let filter = parse_content(
+            "Show\n\tBaseType \"Ring\"\n\tRarity Rare\nShow\n\tBaseType \"Ring\"\n\tQuality > 5\n",
+        )
+        .unwrap();
+
+        let references = find_references(
+            &filter.blocks,
+            &filter.reference_index,
+            ReferenceKind::BaseType,
+            "Gold Ring",
+        );
+
+        assert!(references.iter().all(|reference| reference.can_fire));
+    }
+
+    #[test]
+    fn test_find_class_references() {
+        let references = lookup(ReferenceKind::Class, "Jewels");
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].order, 3);
+        assert!(references[0].can_fire);
+        assert!(lookup(ReferenceKind::Class, "Rings").is_empty());
+    }
+}
@@ -1,8 +1,16 @@
 /// Removes comments from a line. Comments start with # symbol.
-pub fn remove_comment(line: &str) -> String {
+pub fn remove_comment(line: &str) -> &str {
     match line.split_once('#') {
-        Some((content, _)) => content.to_string(),
-        None => line.to_string(),
+        Some((content, _)) => content,
+        None => line,
+    }
+}
+
+/// Returns the comment of a line without the # symbol and surrounding whitespace.
+pub fn comment(line: &str) -> &str {
+    match line.split_once('#') {
+        Some((_, comment)) => comment.trim(),
+        None => "",
     }
 }
 
@@ -35,6 +43,12 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_comment() {
+        assert_eq!(comment("Show # $tier->t1 "), "$tier->t1");
+        assert_eq!(comment("Show"), "");
+    }
+
     #[test]
     fn test_empty_line() {
         assert_eq!(remove_comment(""), "");
@@ -0,0 +1,60 @@
+use crate::filter::parser::blocks::Block;
+
+/// Returns the section heading of each block, or `None` for blocks before the first heading.
+///
+/// Sections are marked by comment lines such as `# [[0100]] Gold` in NeverSink filters.
+/// The returned heading is `[[0100]] Gold`. The blocks must be in file order.
+pub fn block_sections<'a>(content: &'a str, blocks: &[Block]) -> Vec<Option<&'a str>> {
+    let mut headings = section_headings(content).peekable();
+    let mut section = None;
+
+    blocks
+        .iter()
+        .map(|block| {
+            while let Some(heading) = headings.next_if(|(start, _)| *start < block.span.start) {
+                section = Some(heading.1);
+            }
+
+            section
+        })
+        .collect()
+}
+
+/// Returns the positions and texts of the section heading lines.
+fn section_headings(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = 0;
+
+    content.split_inclusive('\n').filter_map(move |line| {
+        let line_start = start;
+        start += line.len();
+        let heading = line.strip_prefix('#')?.trim();
+        heading.starts_with("[[").then_some((line_start, heading))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::parse_file::parse_content;
+
+    #[test]
+    fn test_block_sections() {
+        let content = "Show\n\tRarity Unique\n\
+            #=====\n# [[0100]] Gold\n#=====\n\
+            Show\n\tBaseType \"Gold\"\nShow\n\tStackSize > 10\n\
+            # [[0200]] Exotic Bases\n\
+            Hide\n\tRarity Normal\n";
+
+        let blocks = parse_content(content).unwrap().blocks;
+
+        assert_eq!(
+            block_sections(content, &blocks),
+            [
+                None,
+                Some("[[0100]] Gold"),
+                Some("[[0100]] Gold"),
+                Some("[[0200]] Exotic Bases")
+            ]
+        );
+    }
+}
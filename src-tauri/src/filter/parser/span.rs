@@ -0,0 +1,49 @@
+use ts_rs::TS;
+
+/// Byte range of a part of the loot filter content, `start` is inclusive and `end` is exclusive.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, TS,
+)]
+#[ts(export)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    // Index of the file in the include graph of the filter, 0 is the opened filter file
+    pub file: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span {
+            start,
+            end,
+            file: 0,
+        }
+    }
+
+    /// Returns the span moved by `offset` bytes.
+    pub fn shifted(self, offset: isize) -> Self {
+        Span {
+            start: self.start.saturating_add_signed(offset),
+            end: self.end.saturating_add_signed(offset),
+            file: self.file,
+        }
+    }
+
+    /// Returns the same byte range in the file with index `file`.
+    pub fn in_file(self, file: usize) -> Self {
+        Span { file, ..self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shifted() {
+        assert_eq!(Span::new(10, 20).shifted(5), Span::new(15, 25));
+        assert_eq!(Span::new(10, 20).shifted(-10), Span::new(0, 10));
+        assert_eq!(Span::new(10, 20).in_file(2).shifted(5).file, 2);
+    }
+}
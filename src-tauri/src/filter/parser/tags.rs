@@ -0,0 +1,73 @@
+use ts_rs::TS;
+
+/// A tag written in the comment of a block name line.
+///
+/// NeverSink tags blocks as `Show # %D6 $type->exoticbases $tier->commonexoticbaseshigh`.
+/// `$type->exoticbases` is the tag `$type` with the value `exoticbases`, and `%D6` is
+/// the tag `%D` with the value `6`, the strictness level that disables the block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct BlockTag {
+    pub name: String,
+    pub value: String,
+}
+
+/// Returns the tags of a block name line comment, other words of the comment are ignored.
+pub fn parse_tags(comment: &str) -> Vec<BlockTag> {
+    comment.split_whitespace().filter_map(parse_tag).collect()
+}
+
+fn parse_tag(word: &str) -> Option<BlockTag> {
+    if word.starts_with('$') {
+        let (name, value) = word.split_once("->")?;
+
+        return Some(BlockTag {
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+    }
+
+    let letters = word.strip_prefix('%')?;
+    let value_start = letters.find(|c: char| !c.is_ascii_alphabetic())?;
+    let (name, value) = word.split_at(value_start + 1);
+
+    if name.len() == 1 || !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(BlockTag {
+        name: name.to_string(),
+        value: value.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str, value: &str) -> BlockTag {
+        BlockTag {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        let tags = parse_tags("%D6 $type->endgame->flasks $tier->t1 ordinary comment");
+
+        assert_eq!(
+            tags,
+            [
+                tag("%D", "6"),
+                tag("$type", "endgame->flasks"),
+                tag("$tier", "t1")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_words_that_are_not_tags() {
+        assert_eq!(parse_tags("100% $ %5 %H $tier"), []);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::filter::parser::encoding::{encode, TextFormat};
+use std::path::Path;
+
+/// Writes the filter content to disk in the format it was read with,
+/// see [`read_filter_with_format`](crate::filter::parser::read_from_disk::read_filter_with_format).
+///
+/// The line endings of the content are replaced with the line ending of the format.
+/// Documents are saved and strictness variants are exported with this function.
+pub fn write_filter_to_disk<P: AsRef<Path>>(
+    path: P,
+    content: &str,
+    format: &TextFormat,
+) -> Result<(), std::io::Error> {
+    let bytes = encode(content, format)?;
+    std::fs::write(path, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::read_from_disk::read_filter_with_format;
+    use tempfile::tempdir;
+
+    async fn assert_round_trip(bytes: &[u8]) {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.filter");
+        std::fs::write(&path, bytes).unwrap();
+
+        let (content, format) = read_filter_with_format(&path).await.unwrap();
+
+        write_filter_to_disk(&path, &content, &format).unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), bytes);
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_utf8_lf() {
+        assert_round_trip(b"Show\n\tRarity Rare\n").await;
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_utf8_bom_crlf() {
+        assert_round_trip(b"\xEF\xBB\xBFShow\r\n\tRarity Rare\r\n").await;
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_utf16() {
+        let bytes: Vec<u8> = "\u{FEFF}Show\r\n\tRarity Rare"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+
+        assert_round_trip(&bytes).await;
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_windows_1252() {
+        assert_round_trip(b"Show\r\n\tBaseType \"Mj\xF6lnir\"\r\n").await;
+    }
+
+    #[tokio::test]
+    async fn test_write_keeps_original_line_endings() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.filter");
+        std::fs::write(&path, b"Show\r\n\tRarity Rare\r\n").unwrap();
+
+        let (content, format) = read_filter_with_format(&path).await.unwrap();
+        let content = format!("{}Hide\n\tRarity Normal\n", content);
+
+        write_filter_to_disk(&path, &content, &format).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+
+        assert_eq!(
+            written,
+            "Show\r\n\tRarity Rare\r\nHide\r\n\tRarity Normal\r\n"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use ts_rs::TS;
+
+/// Error returned when a query can not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct QueryError {
+    pub message: String,
+    // Byte positions of the part of the query that caused the error
+    pub start: usize,
+    pub end: usize,
+}
+
+impl QueryError {
+    pub fn new(message: impl Into<String>, range: Range<usize>) -> Self {
+        QueryError {
+            message: message.into(),
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.start + 1)
+    }
+}
+
+impl Error for QueryError {}
@@ -0,0 +1,150 @@
+use std::cmp::Ordering;
+
+use super::lexer::Operator;
+use super::parser::{Comparison, Query};
+use crate::filter::parser::blocks::Block;
+
+/// Operators written before the values of block items, such as `>=` in `ItemLevel >= 80`.
+const ITEM_OPERATORS: [&str; 8] = ["=", "==", "!", "!=", "<", "<=", ">", ">="];
+
+/// Rarities in increasing order, compared by rank instead of by name.
+const RARITIES: [&str; 4] = ["Normal", "Magic", "Rare", "Unique"];
+
+impl Query {
+    /// Returns true if the block is matched by the query.
+    ///
+    /// Conditions compare the values written in the block and ignore the block's own
+    /// operators, so `Rarity = Rare` matches both `Rarity Rare` and `Rarity <= Rare`.
+    pub fn matches(&self, block: &Block) -> bool {
+        match self {
+            Query::And(left, right) => left.matches(block) && right.matches(block),
+            Query::Or(left, right) => left.matches(block) || right.matches(block),
+            Query::Not(query) => !query.matches(block),
+            Query::Name(name) => block.name == *name,
+            Query::Enabled(enabled) => block.enabled == *enabled,
+            Query::Item { name, comparison } => {
+                let Some(items) = block.items.get(name) else {
+                    return false;
+                };
+
+                let values = items
+                    .iter()
+                    .flat_map(|item| &item.params)
+                    .map(String::as_str)
+                    .filter(|param| !ITEM_OPERATORS.contains(param));
+
+                compare_values(values, comparison.as_ref())
+            }
+            Query::Tag { name, comparison } => {
+                let mut values = block
+                    .tags
+                    .iter()
+                    .filter(|tag| tag.name == *name)
+                    .map(|tag| tag.value.as_str())
+                    .peekable();
+
+                values.peek().is_some() && compare_values(values, comparison.as_ref())
+            }
+        }
+    }
+}
+
+/// Returns true if any value matches the comparison, or if there is no comparison.
+///
+/// `!=` is true if none of the values is equal.
+fn compare_values<'a>(
+    mut values: impl Iterator<Item = &'a str>,
+    comparison: Option<&Comparison>,
+) -> bool {
+    let Some(Comparison { operator, value }) = comparison else {
+        return true;
+    };
+
+    match operator {
+        Operator::NotEqual => values.all(|v| !v.eq_ignore_ascii_case(value)),
+        _ => values.any(|v| compare(v, *operator, value)),
+    }
+}
+
+fn compare(value: &str, operator: Operator, expected: &str) -> bool {
+    match operator {
+        Operator::Equal => value.eq_ignore_ascii_case(expected),
+        Operator::NotEqual => !value.eq_ignore_ascii_case(expected),
+        Operator::Contains => value.to_lowercase().contains(&expected.to_lowercase()),
+        Operator::Less => order(value, expected) == Some(Ordering::Less),
+        Operator::LessOrEqual => order(value, expected).is_some_and(Ordering::is_le),
+        Operator::Greater => order(value, expected) == Some(Ordering::Greater),
+        Operator::GreaterOrEqual => order(value, expected).is_some_and(Ordering::is_ge),
+    }
+}
+
+/// Compares numbers or rarities, returns `None` for other values.
+fn order(value: &str, expected: &str) -> Option<Ordering> {
+    if let (Ok(value), Ok(expected)) = (value.parse::<f64>(), expected.parse::<f64>()) {
+        return value.partial_cmp(&expected);
+    }
+
+    let rank = |rarity: &str| RARITIES.iter().position(|r| r.eq_ignore_ascii_case(rarity));
+    Some(rank(value)?.cmp(&rank(expected)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filter::parser::parse_file::parse_content;
+    use crate::filter::query::parser::parse_query;
+
+    const CONTENT: &str = "\
+Show # $tier->t1
+\tBaseType == \"Ruby Ring\" \"Gold Ring\"
+\tRarity <= Rare
+\tSetFontSize 45
+
+Hide # $tier->t2
+\tClass \"Rings\"
+\tRarity Normal Magic
+\tSetFontSize 35
+
+#Show
+#\tItemLevel >= 80
+";
+
+    fn search(query: &str) -> Vec<usize> {
+        let blocks = parse_content(CONTENT).unwrap().blocks;
+        let query = parse_query(query).unwrap();
+
+        blocks
+            .iter()
+            .filter(|block| query.matches(block))
+            .map(|block| block.order)
+            .collect()
+    }
+
+    #[test]
+    fn test_item_comparisons() {
+        assert_eq!(search("BaseType ~ ruby"), [1]);
+        assert_eq!(search("BaseType = \"Gold Ring\""), [1]);
+        assert_eq!(search("Rarity = Rare"), [1]);
+        assert_eq!(search("Rarity != Rare"), [2]);
+        assert_eq!(search("Rarity > Magic"), [1]);
+        assert_eq!(search("SetFontSize >= 40"), [1]);
+        assert_eq!(search("SetFontSize < 40"), [2]);
+        assert_eq!(search("ItemLevel"), [3]);
+    }
+
+    #[test]
+    fn test_tags_and_block_state() {
+        assert_eq!(search("tag:$tier = T1"), [1]);
+        assert_eq!(search("tag:$tier"), [1, 2]);
+        assert_eq!(search("not tag:$tier"), [3]);
+        assert_eq!(search("Hide or disabled"), [2, 3]);
+        assert_eq!(search("Show and enabled"), [1]);
+    }
+
+    #[test]
+    fn test_example_query() {
+        let query = "BaseType ~ \"Ruby\" and Rarity = Rare and action:SetFontSize >= 40 \
+            and tag:$tier=t1";
+
+        assert_eq!(search(query), [1]);
+    }
+}
@@ -0,0 +1,167 @@
+use super::error::QueryError;
+
+/// Comparison operator of a query condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Equal,
+    NotEqual,
+    // Case-insensitive substring match
+    Contains,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    // A word that is not quoted, such as `BaseType`, `tag:$tier` or `and`
+    Word(String),
+    // A quoted string without the quotes
+    Text(String),
+    Operator(Operator),
+    OpenParen,
+    CloseParen,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    // Byte positions of the token in the query
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Characters that end an unquoted word.
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || "()\"=!~<>".contains(c)
+}
+
+/// Splits the query into tokens.
+pub fn tokenize(query: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let kind = match c {
+            _ if c.is_whitespace() => continue,
+            '(' => TokenKind::OpenParen,
+            ')' => TokenKind::CloseParen,
+            '"' => {
+                let text_start = start + 1;
+
+                let Some(length) = query[text_start..].find('"') else {
+                    let range = start..query.len();
+                    return Err(QueryError::new("The string is not closed", range));
+                };
+
+                // Skip the text and the closing quote
+                let text_end = text_start + length;
+                while chars.next_if(|(i, _)| *i <= text_end).is_some() {}
+                TokenKind::Text(query[text_start..text_end].to_string())
+            }
+            '=' | '!' | '~' | '<' | '>' => {
+                let equals = chars.next_if(|(_, next)| *next == '=').is_some();
+
+                let operator = match (c, equals) {
+                    ('=', _) => Operator::Equal,
+                    ('!', true) => Operator::NotEqual,
+                    ('~', false) => Operator::Contains,
+                    ('<', false) => Operator::Less,
+                    ('<', true) => Operator::LessOrEqual,
+                    ('>', false) => Operator::Greater,
+                    ('>', true) => Operator::GreaterOrEqual,
+                    _ => {
+                        let end = start + 1 + equals as usize;
+                        let message = format!("Unknown operator `{}`", &query[start..end]);
+                        return Err(QueryError::new(message, start..end));
+                    }
+                };
+
+                TokenKind::Operator(operator)
+            }
+            _ => {
+                while chars.next_if(|(_, next)| !is_separator(*next)).is_some() {}
+                let end = chars.peek().map_or(query.len(), |(i, _)| *i);
+                TokenKind::Word(query[start..end].to_string())
+            }
+        };
+
+        let end = chars.peek().map_or(query.len(), |(i, _)| *i);
+        tokens.push(Token { kind, start, end });
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(query: &str) -> Vec<TokenKind> {
+        tokenize(query)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    fn word(text: &str) -> TokenKind {
+        TokenKind::Word(text.to_string())
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            kinds("(BaseType ~ \"Ruby Ring\") and tag:$tier=t1"),
+            [
+                TokenKind::OpenParen,
+                word("BaseType"),
+                TokenKind::Operator(Operator::Contains),
+                TokenKind::Text("Ruby Ring".to_string()),
+                TokenKind::CloseParen,
+                word("and"),
+                word("tag:$tier"),
+                TokenKind::Operator(Operator::Equal),
+                word("t1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(
+            kinds("= == != < <= > >="),
+            [
+                Operator::Equal,
+                Operator::Equal,
+                Operator::NotEqual,
+                Operator::Less,
+                Operator::LessOrEqual,
+                Operator::Greater,
+                Operator::GreaterOrEqual,
+            ]
+            .map(TokenKind::Operator)
+        );
+    }
+
+    #[test]
+    fn test_token_positions() {
+        let tokens = tokenize("Rarity >= \"Rare\"").unwrap();
+        let positions: Vec<_> = tokens.iter().map(|t| (t.start, t.end)).collect();
+        assert_eq!(positions, [(0, 6), (7, 9), (10, 16)]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            tokenize("BaseType ~ \"Ruby").unwrap_err(),
+            QueryError::new("The string is not closed", 11..16)
+        );
+
+        assert_eq!(
+            tokenize("Quality ! 5").unwrap_err(),
+            QueryError::new("Unknown operator `!`", 8..9)
+        );
+    }
+}
@@ -0,0 +1,25 @@
+pub mod error;
+pub mod eval;
+pub mod lexer;
+pub mod parser;
+
+use crate::filter::parser::blocks::Block;
+use error::QueryError;
+use parser::parse_query;
+
+/// Returns the orders of the blocks matched by the query, see [`parse_query`] for its syntax.
+///
+/// A query of only whitespace matches all blocks.
+pub fn search(blocks: &[Block], query: &str) -> Result<Vec<usize>, QueryError> {
+    if query.trim().is_empty() {
+        return Ok(blocks.iter().map(|block| block.order).collect());
+    }
+
+    let query = parse_query(query)?;
+
+    Ok(blocks
+        .iter()
+        .filter(|block| query.matches(block))
+        .map(|block| block.order)
+        .collect())
+}
@@ -0,0 +1,303 @@
+use super::error::QueryError;
+use super::lexer::{tokenize, Operator, Token, TokenKind};
+use crate::filter::parser::blocks_with_lines::BlockName;
+use crate::filter::parser::keywords::{intern_keyword, is_action, is_keyword, Keyword};
+
+/// A parsed query, see [`parse_query`] for the syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    /// Blocks with the name, such as `Show`.
+    Name(BlockName),
+    /// `enabled` or `disabled` blocks.
+    Enabled(bool),
+    /// Blocks with the condition or action, such as `BaseType ~ "Ruby"` or just `BaseType`.
+    Item {
+        name: Keyword,
+        comparison: Option<Comparison>,
+    },
+    /// Blocks with the tag, such as `tag:$tier = t1` or just `tag:$tier`.
+    Tag {
+        name: String,
+        comparison: Option<Comparison>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub operator: Operator,
+    pub value: String,
+}
+
+/// Parses a query such as `BaseType ~ "Ruby" and (Rarity = Rare or tag:$tier = t1)`.
+///
+/// A condition is a keyword, optionally prefixed with `action:` or `condition:`, or a tag
+/// prefixed with `tag:`, followed by an operator (`=`, `!=`, `~`, `<`, `<=`, `>`, `>=`)
+/// and a value. Without the operator it matches blocks that have the keyword or the tag.
+/// The words `Show`, `Hide`, `Minimal`, `enabled` and `disabled` match blocks by their name
+/// and state. Conditions are combined with `and`, `or`, `not` and parentheses.
+pub fn parse_query(query: &str) -> Result<Query, QueryError> {
+    let tokens = tokenize(query)?;
+
+    if tokens.is_empty() {
+        return Err(QueryError::new("The query is empty", 0..query.len()));
+    }
+
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        query_length: query.len(),
+    };
+
+    let parsed = parser.or()?;
+
+    match parser.next() {
+        Some(token) => Err(error_at("Expected `and` or `or`", &token)),
+        None => Ok(parsed),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    query_length: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Skips the next token if it is the word, ignoring case.
+    fn skip_word(&mut self, word: &str) -> bool {
+        let matches = matches!(
+            self.tokens.get(self.position),
+            Some(Token { kind: TokenKind::Word(w), .. }) if w.eq_ignore_ascii_case(word)
+        );
+
+        self.position += matches as usize;
+        matches
+    }
+
+    fn or(&mut self) -> Result<Query, QueryError> {
+        let mut query = self.and()?;
+
+        while self.skip_word("or") {
+            query = Query::Or(Box::new(query), Box::new(self.and()?));
+        }
+
+        Ok(query)
+    }
+
+    fn and(&mut self) -> Result<Query, QueryError> {
+        let mut query = self.not()?;
+
+        while self.skip_word("and") {
+            query = Query::And(Box::new(query), Box::new(self.not()?));
+        }
+
+        Ok(query)
+    }
+
+    fn not(&mut self) -> Result<Query, QueryError> {
+        if self.skip_word("not") {
+            return Ok(Query::Not(Box::new(self.not()?)));
+        }
+
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Query, QueryError> {
+        let Some(token) = self.next() else {
+            let end = self.query_length;
+            return Err(QueryError::new("Expected a condition", end..end));
+        };
+
+        match &token.kind {
+            TokenKind::OpenParen => {
+                let query = self.or()?;
+
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::CloseParen,
+                        ..
+                    }) => Ok(query),
+                    _ => Err(error_at("The parenthesis is not closed", &token)),
+                }
+            }
+            TokenKind::Word(word) => self.condition(word, &token),
+            _ => Err(error_at("Expected a condition", &token)),
+        }
+    }
+
+    fn condition(&mut self, word: &str, token: &Token) -> Result<Query, QueryError> {
+        let error = |message: String| Err(error_at(message, token));
+
+        if word.eq_ignore_ascii_case("enabled") || word.eq_ignore_ascii_case("disabled") {
+            return Ok(Query::Enabled(word.eq_ignore_ascii_case("enabled")));
+        }
+
+        if let Ok(name) = word.parse::<BlockName>() {
+            return Ok(Query::Name(name));
+        }
+
+        let (prefix, name) = word.split_once(':').unwrap_or(("", word));
+
+        if prefix == "tag" {
+            if name.is_empty() {
+                return error("Expected a tag name after `tag:`".to_string());
+            }
+
+            return Ok(Query::Tag {
+                name: name.to_string(),
+                comparison: self.comparison()?,
+            });
+        }
+
+        if !is_keyword(name) {
+            return error(format!("Unknown keyword `{}`", name));
+        }
+
+        match prefix {
+            "" => {}
+            "action" if !is_action(name) => return error(format!("`{}` is not an action", name)),
+            "condition" if is_action(name) => {
+                return error(format!("`{}` is not a condition", name))
+            }
+            "action" | "condition" => {}
+            _ => return error(format!("Unknown prefix `{}:`", prefix)),
+        }
+
+        Ok(Query::Item {
+            name: intern_keyword(name),
+            comparison: self.comparison()?,
+        })
+    }
+
+    /// Parses the optional operator and value after a keyword or a tag.
+    fn comparison(&mut self) -> Result<Option<Comparison>, QueryError> {
+        let Some(token) = self.tokens.get(self.position).cloned() else {
+            return Ok(None);
+        };
+
+        let TokenKind::Operator(operator) = token.kind else {
+            return Ok(None);
+        };
+
+        self.position += 1;
+
+        match self.next().map(|token| token.kind) {
+            Some(TokenKind::Word(value) | TokenKind::Text(value)) => {
+                Ok(Some(Comparison { operator, value }))
+            }
+            _ => Err(error_at("Expected a value after the operator", &token)),
+        }
+    }
+}
+
+fn error_at(message: impl Into<String>, token: &Token) -> QueryError {
+    QueryError::new(message, token.start..token.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &'static str, operator: Operator, value: &str) -> Query {
+        Query::Item {
+            name: intern_keyword(name),
+            comparison: Some(Comparison {
+                operator,
+                value: value.to_string(),
+            }),
+        }
+    }
+
+    fn error_message(query: &str) -> String {
+        parse_query(query).unwrap_err().message
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query = parse_query("BaseType ~ \"Ruby\" and Rarity = Rare or not Show").unwrap();
+
+        let expected = Query::Or(
+            Box::new(Query::And(
+                Box::new(item("BaseType", Operator::Contains, "Ruby")),
+                Box::new(item("Rarity", Operator::Equal, "Rare")),
+            )),
+            Box::new(Query::Not(Box::new(Query::Name(BlockName::Show)))),
+        );
+
+        assert_eq!(query, expected);
+    }
+
+    #[test]
+    fn test_parse_prefixes() {
+        let query = parse_query("action:SetFontSize >= 40 AND tag:$tier=t1").unwrap();
+
+        let expected = Query::And(
+            Box::new(item("SetFontSize", Operator::GreaterOrEqual, "40")),
+            Box::new(Query::Tag {
+                name: "$tier".to_string(),
+                comparison: Some(Comparison {
+                    operator: Operator::Equal,
+                    value: "t1".to_string(),
+                }),
+            }),
+        );
+
+        assert_eq!(query, expected);
+    }
+
+    #[test]
+    fn test_parentheses() {
+        let query = parse_query("disabled and (Class or BaseType)").unwrap();
+
+        let expected = Query::And(
+            Box::new(Query::Enabled(false)),
+            Box::new(Query::Or(
+                Box::new(Query::Item {
+                    name: intern_keyword("Class"),
+                    comparison: None,
+                }),
+                Box::new(Query::Item {
+                    name: intern_keyword("BaseType"),
+                    comparison: None,
+                }),
+            )),
+        );
+
+        assert_eq!(query, expected);
+    }
+
+    #[test]
+    fn test_errors() {
+        let cases = [
+            ("", "The query is empty"),
+            ("Basetype ~ Ruby", "Unknown keyword `Basetype`"),
+            ("action:BaseType", "`BaseType` is not an action"),
+            ("condition:SetFontSize", "`SetFontSize` is not a condition"),
+            ("item:BaseType", "Unknown prefix `item:`"),
+            ("Quality >", "Expected a value after the operator"),
+            ("Quality and", "Expected a condition"),
+            ("(Quality", "The parenthesis is not closed"),
+            ("Quality Rarity", "Expected `and` or `or`"),
+        ];
+
+        for (query, message) in cases {
+            assert_eq!(error_message(query), message, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_error_position() {
+        let error = parse_query("Rarity = Rare and Qualty > 5").unwrap_err();
+        assert_eq!((error.start, error.end), (18, 24));
+        assert_eq!(error.to_string(), "Unknown keyword `Qualty` at position 19");
+    }
+}
@@ -0,0 +1,293 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use ts_rs::TS;
+
+use crate::capture::history::CaptureHistory;
+use crate::capture::item::Item;
+use crate::filter::matcher::{match_item, ItemMatch};
+use crate::filter::parser::blocks::Block;
+use crate::filter::parser::include_graph::resolve_imports;
+use crate::filter::parser::parse_file::{parse_content, ParsedFilter};
+use crate::filter::parser::read_from_disk::read_filter_from_disk_blocking;
+
+/// An item of a corpus that filters are compared with.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct CorpusItem {
+    // Where the item comes from, such as `drops.txt #2` or `capture 14`
+    pub name: String,
+    pub item: Item,
+    // Level of the area the item drops in, if known
+    #[serde(default)]
+    pub area_level: Option<u32>,
+}
+
+/// An item that looks different with the second filter.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct ItemChange {
+    pub name: String,
+    pub item: Item,
+    pub before: ItemMatch,
+    pub after: ItemMatch,
+    // Blocks that decided the visibility of the item, `None` if no block matched
+    pub before_block: Option<Block>,
+    pub after_block: Option<Block>,
+}
+
+/// Items of a corpus whose visibility or style differs between two filters.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct RegressionReport {
+    // Number of items in the corpus
+    pub items: usize,
+    pub changes: Vec<ItemChange>,
+}
+
+/// Files of a corpus in JSON.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum CorpusFile {
+    Items(Vec<CorpusItem>),
+    Parsed(Vec<Item>),
+    History(CaptureHistory),
+}
+
+/// Reads a filter file with its imports, without an async runtime.
+pub fn load_filter(path: &Path) -> Result<ParsedFilter> {
+    let content = read_filter_from_disk_blocking(path)
+        .with_context(|| format!("Failed to read filter {}", path.display()))?;
+
+    resolve_imports(path, parse_content(&content)?)
+}
+
+/// Reads a corpus from a file or from the `.txt` and `.json` files of a directory.
+///
+/// Text files hold item descriptions as copied from the game, one after another. JSON
+/// files hold a list of [`CorpusItem`]s or [`Item`]s, or a capture history.
+pub fn load_corpus(path: &Path) -> Result<Vec<CorpusItem>> {
+    if !path.is_dir() {
+        return load_corpus_file(path);
+    }
+
+    let mut files: Vec<_> = std::fs::read_dir(path)
+        .with_context(|| format!("Failed to read corpus {}", path.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|file| {
+            let extension = file.extension().unwrap_or_default();
+            extension == "txt" || extension == "json"
+        })
+        .collect();
+
+    files.sort();
+    let mut corpus = Vec::new();
+
+    for file in files {
+        corpus.extend(load_corpus_file(&file)?);
+    }
+
+    Ok(corpus)
+}
+
+fn load_corpus_file(path: &Path) -> Result<Vec<CorpusItem>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read corpus {}", path.display()))?;
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let is_json = path.extension().is_some_and(|e| e == "json");
+
+    if is_json {
+        let file: CorpusFile = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid corpus {}", path.display()))?;
+
+        return Ok(match file {
+            CorpusFile::Items(items) => items,
+            CorpusFile::Parsed(items) => named_items(&name, items),
+            CorpusFile::History(history) => history_corpus(&history),
+        });
+    }
+
+    let items = split_descriptions(&content)
+        .iter()
+        .filter_map(|text| Item::parse(text))
+        .collect();
+
+    Ok(named_items(&name, items))
+}
+
+/// Names the items after the file, numbered if there are several.
+fn named_items(file: &str, items: Vec<Item>) -> Vec<CorpusItem> {
+    let count = items.len();
+
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| CorpusItem {
+            name: match count {
+                1 => file.to_string(),
+                _ => format!("{} #{}", file, index + 1),
+            },
+            item,
+            area_level: None,
+        })
+        .collect()
+}
+
+/// Splits text with several item descriptions at their `Item Class` lines.
+fn split_descriptions(content: &str) -> Vec<String> {
+    let mut descriptions: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        match descriptions.last_mut() {
+            Some(text) if !line.starts_with("Item Class: ") => {
+                text.push_str(line);
+                text.push('\n');
+            }
+            _ => descriptions.push(format!("{}\n", line)),
+        }
+    }
+
+    descriptions
+}
+
+/// Returns the captured items of the history as a corpus.
+pub fn history_corpus(history: &CaptureHistory) -> Vec<CorpusItem> {
+    history
+        .list()
+        .into_iter()
+        .map(|entry| CorpusItem {
+            name: format!("capture {}", entry.id),
+            item: entry.item.clone(),
+            area_level: entry.area_level,
+        })
+        .collect()
+}
+
+/// Matches every item of the corpus with both filters and reports the items that are
+/// shown or hidden differently or get different actions.
+///
+/// Items that are matched by another block with the same result are not reported.
+pub fn compare_filters(
+    before: &[Block],
+    after: &[Block],
+    corpus: &[CorpusItem],
+) -> RegressionReport {
+    let block = |blocks: &[Block], matched: &ItemMatch| {
+        blocks
+            .iter()
+            .find(|block| Some(block.id) == matched.block)
+            .cloned()
+    };
+
+    let changes = corpus
+        .iter()
+        .filter_map(|corpus_item| {
+            let old = match_item(before, &corpus_item.item, corpus_item.area_level);
+            let new = match_item(after, &corpus_item.item, corpus_item.area_level);
+
+            let changed = old.visible != new.visible || old.actions != new.actions;
+
+            changed.then(|| ItemChange {
+                name: corpus_item.name.clone(),
+                item: corpus_item.item.clone(),
+                before_block: block(before, &old),
+                after_block: block(after, &new),
+                before: old,
+                after: new,
+            })
+        })
+        .collect();
+
+    RegressionReport {
+        items: corpus.len(),
+        changes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const DROPS: &str = "\
+Item Class: Stackable Currency
+Rarity: Currency
+Divine Orb
+--------
+Stack Size: 1/20
+Item Class: Stackable Currency
+Rarity: Currency
+Chaos Orb
+--------
+Stack Size: 3/20
+";
+
+    #[test]
+    fn test_load_corpus() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("drops.txt"), DROPS).unwrap();
+        std::fs::write(dir.path().join("notes.md"), "not an item").unwrap();
+
+        let ring = Item {
+            class: "Rings".to_string(),
+            base_type: "Ruby Ring".to_string(),
+            ..Item::default()
+        };
+
+        let json = serde_json::to_string(&[ring]).unwrap();
+        std::fs::write(dir.path().join("generated.json"), json).unwrap();
+
+        let corpus = load_corpus(dir.path()).unwrap();
+        let names: Vec<&str> = corpus.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["drops.txt #1", "drops.txt #2", "generated.json"]);
+        assert_eq!(corpus[1].item.stack_size, Some(3));
+    }
+
+    #[test]
+    fn test_compare_filters() {
+        let before = parse_content(
+            "\
+Show
+\tBaseType \"Divine Orb\"
+\tSetFontSize 45
+Show
+\tBaseType \"Chaos Orb\"
+\tSetFontSize 40
+",
+        )
+        .unwrap();
+
+        let after = parse_content(
+            "\
+Show
+\tBaseType \"Divine Orb\" \"Mirror of Kalandra\"
+\tSetFontSize 45
+Hide
+\tBaseType \"Chaos Orb\"
+\tSetFontSize 40
+",
+        )
+        .unwrap();
+
+        let items = split_descriptions(DROPS)
+            .iter()
+            .map(|text| Item::parse(text).unwrap())
+            .collect();
+
+        let corpus = named_items("drops.txt", items);
+        let report = compare_filters(&before.blocks, &after.blocks, &corpus);
+        assert_eq!(report.items, 2);
+        assert_eq!(report.changes.len(), 1);
+
+        let chaos = &report.changes[0];
+        assert_eq!(chaos.item.base_type, "Chaos Orb");
+        assert!(chaos.before.visible);
+        assert!(!chaos.after.visible);
+        assert_eq!(chaos.after_block.as_ref().unwrap().order, 2);
+    }
+}
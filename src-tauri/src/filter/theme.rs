@@ -0,0 +1,518 @@
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+use ts_rs::TS;
+
+use crate::filter::document::edit_command::EditCommand;
+use crate::filter::document::FilterDocument;
+use crate::filter::parser::block_item::BlockItem;
+use crate::filter::parser::blocks::Block;
+use crate::filter::parser::keywords::intern_keyword;
+
+/// Colors, font size, effect, minimap icon and sound of the blocks of the filter by tier.
+///
+/// The styles are looked up by the `$type` and `$tier` tags of a block, first with the
+/// `type/tier` key, such as `currency->stackable/t1`, then with the `tier` key alone.
+///
+/// Themes are stored in TOML or JSON files:
+///
+/// ```toml
+/// [styles.t1]
+/// text_color = [255, 0, 0, 255]
+/// font_size = 45
+/// effect = { color = "Red" }
+/// minimap_icon = { size = 0, color = "Red", shape = "Star" }
+/// sound = { type = "Alert", id = "1", volume = 300 }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct Theme {
+    pub styles: BTreeMap<String, TierStyle>,
+}
+
+/// Style of the blocks of a tier. Missing fields leave the block as it is.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct TierStyle {
+    // Colors are `[red, green, blue]` or `[red, green, blue, alpha]`
+    #[serde(default)]
+    pub text_color: Option<Vec<u8>>,
+    #[serde(default)]
+    pub border_color: Option<Vec<u8>>,
+    #[serde(default)]
+    pub background_color: Option<Vec<u8>>,
+    #[serde(default)]
+    pub font_size: Option<u32>,
+    #[serde(default)]
+    pub effect: Option<Effect>,
+    #[serde(default)]
+    pub minimap_icon: Option<MinimapIcon>,
+    #[serde(default)]
+    pub sound: Option<Sound>,
+}
+
+/// Beam of `PlayEffect`, such as `PlayEffect Red Temp`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct Effect {
+    pub color: String,
+    // The beam is only shown when the item drops
+    #[serde(default)]
+    pub temp: bool,
+}
+
+/// Icon of `MinimapIcon`, such as `MinimapIcon 0 Red Star`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct MinimapIcon {
+    // 0 is the largest size and 2 the smallest
+    pub size: u8,
+    pub color: String,
+    pub shape: String,
+}
+
+/// Sound played when an item of the tier drops.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum Sound {
+    /// `PlayAlertSound` or `PlayAlertSoundPositional` with a built-in sound.
+    Alert {
+        id: String,
+        #[serde(default)]
+        volume: Option<u32>,
+        #[serde(default)]
+        positional: bool,
+    },
+    /// `CustomAlertSound` or `CustomAlertSoundOptional` with a sound file.
+    Custom {
+        path: String,
+        #[serde(default)]
+        volume: Option<u32>,
+        #[serde(default)]
+        optional: bool,
+    },
+}
+
+const COLOR_KEYWORDS: [&str; 3] = ["SetTextColor", "SetBorderColor", "SetBackgroundColor"];
+
+const SOUND_KEYWORDS: [&str; 4] = [
+    "PlayAlertSound",
+    "PlayAlertSoundPositional",
+    "CustomAlertSound",
+    "CustomAlertSoundOptional",
+];
+
+impl Theme {
+    /// Reads a theme from a `.toml` or `.json` file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read theme {}", path.display()))?;
+
+        let theme: Theme = match is_toml(path) {
+            true => toml::from_str(&content)?,
+            false => serde_json::from_str(&content)?,
+        };
+
+        Ok(theme)
+    }
+
+    /// Writes the theme to a `.toml` or `.json` file.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = match is_toml(path) {
+            true => toml::to_string_pretty(self)?,
+            false => serde_json::to_string_pretty(self)?,
+        };
+
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write theme {}", path.display()))
+    }
+
+    /// Collects the styles of the blocks with a `$tier` tag.
+    ///
+    /// The first block of a tier defines the style of the tier. Blocks of the tier with
+    /// another style add a `type/tier` style, so applying the theme to the filter it was
+    /// extracted from keeps the style of the first block of every type and tier.
+    pub fn extract(blocks: &[Block]) -> Self {
+        let mut styles = BTreeMap::new();
+
+        for block in blocks {
+            let Some(tier) = block.tag("$tier") else {
+                continue;
+            };
+
+            let style = TierStyle::from_block(block);
+
+            if style == TierStyle::default() {
+                continue;
+            }
+
+            match styles.get(tier) {
+                None => {
+                    styles.insert(tier.to_string(), style);
+                }
+                Some(tier_style) if *tier_style != style => {
+                    if let Some(block_type) = block.tag("$type") {
+                        let key = format!("{}/{}", block_type, tier);
+                        styles.entry(key).or_insert(style);
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        Theme { styles }
+    }
+
+    /// Returns the style for the block, or `None` if the block has no `$tier` tag or the
+    /// theme has no style for it.
+    pub fn style_of(&self, block: &Block) -> Option<&TierStyle> {
+        let tier = block.tag("$tier")?;
+
+        block
+            .tag("$type")
+            .and_then(|block_type| self.styles.get(&format!("{}/{}", block_type, tier)))
+            .or_else(|| self.styles.get(tier))
+    }
+}
+
+impl Sound {
+    /// Reads the first alert sound action of the block.
+    pub fn from_block(block: &Block) -> Option<Self> {
+        SOUND_KEYWORDS.iter().find_map(|&name| {
+            let item = block.items.get(name)?.first()?;
+            parse_sound(name, &item.params)
+        })
+    }
+}
+
+impl TierStyle {
+    /// Reads the style from the actions of the block.
+    pub fn from_block(block: &Block) -> Self {
+        let item = |name: &str| block.items.get(name).and_then(|items| items.first());
+        let params = |name: &str| item(name).map(|item| item.params.as_slice());
+        let color = |name: &str| params(name).and_then(parse_color);
+
+        TierStyle {
+            text_color: color("SetTextColor"),
+            border_color: color("SetBorderColor"),
+            background_color: color("SetBackgroundColor"),
+            font_size: params("SetFontSize").and_then(|p| p.first()?.parse().ok()),
+            effect: params("PlayEffect").and_then(parse_effect),
+            minimap_icon: params("MinimapIcon").and_then(parse_minimap_icon),
+            sound: Sound::from_block(block),
+        }
+    }
+
+    /// Returns the items that give a block the style, and the names of the items that
+    /// need to be removed, such as a `CustomAlertSound` replaced by a `PlayAlertSound`.
+    fn items(&self) -> Result<(Vec<BlockItem>, Vec<&'static str>)> {
+        let mut items = Vec::new();
+        let mut removed = Vec::new();
+
+        let colors = [&self.text_color, &self.border_color, &self.background_color];
+
+        for (name, color) in COLOR_KEYWORDS.iter().zip(colors) {
+            if let Some(color) = color {
+                if !(3..=4).contains(&color.len()) {
+                    bail!("{} needs 3 or 4 color values, got {}", name, color.len());
+                }
+
+                items.push(item(name, color.iter().map(u8::to_string).collect()));
+            }
+        }
+
+        if let Some(font_size) = self.font_size {
+            items.push(item("SetFontSize", vec![font_size.to_string()]));
+        }
+
+        if let Some(effect) = &self.effect {
+            let mut params = vec![effect.color.clone()];
+
+            if effect.temp {
+                params.push("Temp".to_string());
+            }
+
+            items.push(item("PlayEffect", params));
+        }
+
+        if let Some(icon) = &self.minimap_icon {
+            let size = icon.size.to_string();
+            let params = vec![size, icon.color.clone(), icon.shape.clone()];
+            items.push(item("MinimapIcon", params));
+        }
+
+        if let Some(sound) = &self.sound {
+            let (name, mut params, volume) = match sound {
+                Sound::Alert {
+                    id,
+                    volume,
+                    positional,
+                } => {
+                    let name = match positional {
+                        true => "PlayAlertSoundPositional",
+                        false => "PlayAlertSound",
+                    };
+
+                    (name, vec![id.clone()], volume)
+                }
+                Sound::Custom {
+                    path,
+                    volume,
+                    optional,
+                } => {
+                    let name = match optional {
+                        true => "CustomAlertSoundOptional",
+                        false => "CustomAlertSound",
+                    };
+
+                    (name, vec![path.clone()], volume)
+                }
+            };
+
+            params.extend(volume.map(|volume| volume.to_string()));
+            items.push(item(name, params));
+            removed.extend(SOUND_KEYWORDS.iter().filter(|&&other| other != name));
+        }
+
+        Ok((items, removed))
+    }
+}
+
+impl FilterDocument {
+    /// Sets the actions of every block with a style in the theme as a single step of the
+    /// undo history. The conditions of the blocks are not changed.
+    ///
+    /// Returns the number of changed blocks.
+    pub fn apply_theme(&mut self, theme: &Theme) -> Result<usize> {
+        let mut commands = Vec::new();
+        let mut count = 0;
+
+        for block in &self.filter().blocks {
+            let Some(style) = theme.style_of(block) else {
+                continue;
+            };
+
+            let (items, removed) = style.items()?;
+            let before = commands.len();
+
+            for item in items {
+                let current = block.items.get(&item.name).and_then(|items| items.first());
+
+                if current != Some(&item) {
+                    commands.push(EditCommand::SetItem { id: block.id, item });
+                }
+            }
+
+            for name in removed {
+                if block.items.contains_key(name) {
+                    let name = name.to_string();
+                    commands.push(EditCommand::RemoveItem { id: block.id, name });
+                }
+            }
+
+            count += (commands.len() > before) as usize;
+        }
+
+        self.apply(&EditCommand::Batch { commands })?;
+        Ok(count)
+    }
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"))
+}
+
+fn item(name: &str, params: Vec<String>) -> BlockItem {
+    BlockItem {
+        name: intern_keyword(name),
+        params,
+    }
+}
+
+fn parse_color(params: &[String]) -> Option<Vec<u8>> {
+    let color: Result<Vec<u8>, _> = params.iter().map(|p| p.parse()).collect();
+    let color = color.ok()?;
+    (3..=4).contains(&color.len()).then_some(color)
+}
+
+fn parse_effect(params: &[String]) -> Option<Effect> {
+    let color = params.first()?.clone();
+    let temp = params.get(1).is_some_and(|p| p == "Temp");
+    Some(Effect { color, temp })
+}
+
+fn parse_minimap_icon(params: &[String]) -> Option<MinimapIcon> {
+    let [size, color, shape] = params else {
+        return None;
+    };
+
+    Some(MinimapIcon {
+        size: size.parse().ok()?,
+        color: color.clone(),
+        shape: shape.clone(),
+    })
+}
+
+fn parse_sound(name: &str, params: &[String]) -> Option<Sound> {
+    let first = params.first()?.clone();
+    let volume = params.get(1).and_then(|p| p.parse().ok());
+
+    let sound = match name {
+        "PlayAlertSound" | "PlayAlertSoundPositional" => Sound::Alert {
+            id: first,
+            volume,
+            positional: name == "PlayAlertSoundPositional",
+        },
+        _ => Sound::Custom {
+            path: first,
+            volume,
+            optional: name == "CustomAlertSoundOptional",
+        },
+    };
+
+    Some(sound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::keywords::is_action;
+    use crate::filter::parser::parse_file::parse_content;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    const FILTER: &str = "\
+Show # $type->currency $tier->t1
+\tBaseType \"Divine Orb\"
+\tSetTextColor 255 0 0 255
+\tSetFontSize 45
+\tCustomAlertSound \"divine.mp3\" 200
+Show # $type->currency $tier->t2
+\tBaseType \"Chaos Orb\"
+\tSetFontSize 40 # Keep
+Show # $type->uniques $tier->t1
+\tRarity Unique
+\tSetFontSize 40
+Show
+\tRarity Rare
+\tSetFontSize 32
+";
+
+    fn style(font_size: u32) -> TierStyle {
+        TierStyle {
+            font_size: Some(font_size),
+            ..TierStyle::default()
+        }
+    }
+
+    #[test]
+    fn test_extract_theme() {
+        let filter = parse_content(FILTER).unwrap();
+        let theme = Theme::extract(&filter.blocks);
+
+        let keys: Vec<&str> = theme.styles.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["t1", "t2", "uniques/t1"]);
+        assert_eq!(theme.styles["uniques/t1"], style(40));
+
+        let t1 = &theme.styles["t1"];
+        assert_eq!(t1.text_color, Some(vec![255, 0, 0, 255]));
+
+        let sound = Sound::Custom {
+            path: "divine.mp3".to_string(),
+            volume: Some(200),
+            optional: false,
+        };
+
+        assert_eq!(t1.sound, Some(sound));
+    }
+
+    #[test]
+    fn test_apply_theme() {
+        let mut document = FilterDocument::new(FILTER.to_string()).unwrap();
+
+        let t1 = TierStyle {
+            sound: Some(Sound::Alert {
+                id: "1".to_string(),
+                volume: Some(300),
+                positional: false,
+            }),
+            effect: Some(Effect {
+                color: "Red".to_string(),
+                temp: true,
+            }),
+            ..style(45)
+        };
+
+        let styles = [("t1", t1), ("t2", style(38)), ("uniques/t1", style(40))];
+        let styles = styles.map(|(key, style)| (key.to_string(), style));
+        let theme = Theme {
+            styles: styles.into(),
+        };
+
+        assert_eq!(document.apply_theme(&theme).unwrap(), 2);
+
+        let expected = FILTER
+            .replace(
+                "\tCustomAlertSound \"divine.mp3\" 200\n",
+                "\tPlayEffect Red Temp\n\tPlayAlertSound 1 300\n",
+            )
+            .replace("SetFontSize 40 # Keep", "SetFontSize 38 # Keep");
+
+        assert_eq!(document.content(), expected);
+
+        // The theme is a single step of the undo history
+        document.undo().unwrap();
+        assert_eq!(document.content(), FILTER);
+    }
+
+    #[test]
+    fn test_extracted_theme_keeps_filter() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("filter_examples/example_001.filter");
+
+        // Applying the extracted theme to the filter only changes actions
+        let content = std::fs::read_to_string(path).unwrap();
+        let mut document = FilterDocument::new(content.clone()).unwrap();
+        let theme = Theme::extract(&document.filter().blocks);
+        assert!(theme.styles.len() > 10);
+
+        document.apply_theme(&theme).unwrap();
+        let before = parse_content(&content).unwrap().blocks;
+        let after = &document.filter().blocks;
+        assert_eq!(before.len(), after.len());
+
+        for (before, after) in before.iter().zip(after) {
+            assert_eq!(conditions(before), conditions(after));
+        }
+    }
+
+    fn conditions(block: &Block) -> Vec<&BlockItem> {
+        let mut items: Vec<&BlockItem> = block
+            .items
+            .values()
+            .flatten()
+            .filter(|item| !is_action(&item.name))
+            .collect();
+
+        items.sort_by_key(|item| &item.name);
+        items
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempdir().unwrap();
+        let filter = parse_content(FILTER).unwrap();
+        let theme = Theme::extract(&filter.blocks);
+
+        for name in ["theme.toml", "theme.json"] {
+            let path = dir.path().join(name);
+            theme.save(&path).unwrap();
+            assert_eq!(Theme::load(&path).unwrap(), theme);
+        }
+
+        let toml = std::fs::read_to_string(dir.path().join("theme.toml")).unwrap();
+        assert!(toml.contains("[styles.t1]"), "{}", toml);
+    }
+}
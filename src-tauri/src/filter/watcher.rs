@@ -0,0 +1,263 @@
+use crate::filter::parser::parse_file::ParsedFilter;
+use notify_debouncer_mini::notify::{self, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use ts_rs::TS;
+
+/// Name of the Tauri event emitted when the watched filter file is re-parsed.
+pub const FILTER_RELOADED_EVENT: &str = "filter-reloaded";
+
+/// How long the file needs to stay unchanged before it is reported as changed.
+/// Editors often write a file in several steps, this avoids parsing half-written content.
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Result of re-parsing the watched filter file after it changed on disk.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct FilterReload {
+    pub path: String,
+    // The re-parsed filter, `None` if the file could not be read or parsed
+    pub filter: Option<ParsedFilter>,
+    // Describes why the file could not be read or parsed
+    pub error: Option<String>,
+}
+
+/// Watches a filter file and the files it imports, and reports every time one of them
+/// changes on disk, so that the filter can be re-parsed.
+///
+/// Watching stops when the watcher is dropped.
+pub struct FilterWatcher {
+    path: PathBuf,
+    _watch: Arc<Mutex<Watch>>,
+}
+
+/// The debouncer and the files it watches, shared with the event handler so that files
+/// imported after a reload are watched as well.
+#[derive(Default)]
+struct Watch {
+    debouncer: Option<Debouncer<RecommendedWatcher>>,
+    // Files of the filter, in the form of `watched_path`
+    files: HashSet<PathBuf>,
+    directories: HashSet<PathBuf>,
+}
+
+impl Watch {
+    /// Watches the directories of the files that are not watched yet.
+    ///
+    /// The parent directories are watched instead of the files themselves, because many
+    /// editors save by replacing the file, which would end a watch on the original file.
+    fn add_files<'a>(&mut self, paths: impl Iterator<Item = &'a Path>) -> notify::Result<()> {
+        let Some(debouncer) = &mut self.debouncer else {
+            return Ok(());
+        };
+
+        for path in paths {
+            let Some(path) = watched_path(path) else {
+                continue;
+            };
+
+            let directory = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+            if !self.directories.contains(&directory) {
+                let watcher = debouncer.watcher();
+                watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+                self.directories.insert(directory);
+            }
+
+            self.files.insert(path);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the path of the file in its canonical directory, so that the paths of the
+/// events match the files however their directories were named.
+fn watched_path(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let directory = std::fs::canonicalize(directory).unwrap_or_else(|_| directory.to_path_buf());
+    Some(directory.join(name))
+}
+
+/// Returns the files of the filter, which always include the filter file itself.
+pub fn filter_files<'a>(
+    path: &'a Path,
+    filter: &'a ParsedFilter,
+) -> impl Iterator<Item = &'a Path> {
+    let imports = filter.include_graph.files.iter().map(Path::new);
+    std::iter::once(path).chain(imports)
+}
+
+impl FilterWatcher {
+    /// Starts watching the filter file at `path`, whose currently loaded filter is `filter`,
+    /// and the files of its include graph.
+    ///
+    /// `on_change` is called from a background thread with the changed files of the filter
+    /// and returns the files of the filter after it has been re-parsed, see
+    /// [`filter_files`]. Files imported by the re-parsed filter are watched from then on.
+    pub fn start<F>(path: &Path, filter: &ParsedFilter, on_change: F) -> notify::Result<Self>
+    where
+        F: Fn(&[PathBuf]) -> Vec<PathBuf> + Send + 'static,
+    {
+        let path = path.to_path_buf();
+        let watch = Arc::new(Mutex::new(Watch::default()));
+        let handler_watch = Arc::downgrade(&watch);
+
+        let debouncer = new_debouncer(DEBOUNCE_TIMEOUT, move |result: DebounceEventResult| {
+            let (Ok(events), Some(watch)) = (result, handler_watch.upgrade()) else {
+                return;
+            };
+
+            let changed: Vec<PathBuf> = {
+                let watch = watch.lock().unwrap();
+                let paths = events.iter().filter_map(|event| watched_path(&event.path));
+                paths.filter(|path| watch.files.contains(path)).collect()
+            };
+
+            if changed.is_empty() {
+                return;
+            }
+
+            let files = on_change(&changed);
+            let files = files.iter().map(PathBuf::as_path);
+
+            let result = watch.lock().unwrap().add_files(files);
+
+            if let Err(e) = result {
+                eprintln!("Failed to watch imported filter file: {}", e);
+            }
+        })?;
+
+        let mut state = watch.lock().unwrap();
+        state.debouncer = Some(debouncer);
+        state.add_files(filter_files(&path, filter))?;
+        drop(state);
+
+        Ok(FilterWatcher {
+            path,
+            _watch: watch,
+        })
+    }
+
+    /// Path of the watched filter file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Holds the watcher of the currently opened filter in Tauri state.
+///
+/// Replacing the watcher stops watching the previously opened file.
+#[derive(Default)]
+pub struct FilterWatcherState(pub Mutex<Option<FilterWatcher>>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::include_graph::resolve_imports;
+    use crate::filter::parser::parse_file::parse_content;
+    use std::sync::mpsc;
+    use tempfile::tempdir;
+
+    /// Parses the filter on disk and returns its files, as the document does after a change.
+    fn files_on_disk(path: &Path) -> Vec<PathBuf> {
+        let content = std::fs::read_to_string(path).unwrap();
+        let filter = resolve_imports(path, parse_content(&content).unwrap()).unwrap();
+        filter_files(path, &filter).map(Path::to_path_buf).collect()
+    }
+
+    /// Waits until the change of the file is reported, skipping the reports before it.
+    fn wait_for_change(rx: &mpsc::Receiver<Vec<PathBuf>>, path: &Path) -> bool {
+        let path = watched_path(path).unwrap();
+
+        while let Ok(changed) = rx.recv_timeout(Duration::from_secs(10)) {
+            if changed.contains(&path) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    #[test]
+    fn test_reports_changed_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.filter");
+        std::fs::write(&path, "Show\n\tBaseType \"Mirror\"").unwrap();
+        let (tx, rx) = mpsc::channel();
+        let filter = parse_content("").unwrap();
+
+        let on_change_path = path.clone();
+        let watcher = FilterWatcher::start(&path, &filter, move |changed| {
+            let _ = tx.send(changed.to_vec());
+            files_on_disk(&on_change_path)
+        })
+        .unwrap();
+
+        assert_eq!(watcher.path(), path);
+        let content = "Show\n\tBaseType \"Mirror\"\nHide\n\tClass \"Rings\"";
+        std::fs::write(&path, content).unwrap();
+        assert!(wait_for_change(&rx, &path));
+    }
+
+    #[test]
+    fn test_reports_changed_imported_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("main.filter");
+        let shared = dir.path().join("rules").join("shared.filter");
+        let added = dir.path().join("other").join("added.filter");
+        std::fs::create_dir_all(shared.parent().unwrap()).unwrap();
+        std::fs::create_dir_all(added.parent().unwrap()).unwrap();
+        std::fs::write(&path, "Import \"rules/shared.filter\"\n").unwrap();
+        std::fs::write(&shared, "Show\n\tRarity Unique\n").unwrap();
+        std::fs::write(&added, "Hide\n\tRarity Normal\n").unwrap();
+
+        let filter = parse_content("Import \"rules/shared.filter\"\n").unwrap();
+        let filter = resolve_imports(&path, filter).unwrap();
+        let (tx, rx) = mpsc::channel();
+
+        let on_change_path = path.clone();
+        let _watcher = FilterWatcher::start(&path, &filter, move |changed| {
+            let _ = tx.send(changed.to_vec());
+            files_on_disk(&on_change_path)
+        })
+        .unwrap();
+
+        std::fs::write(&shared, "Show\n\tRarity Rare\n").unwrap();
+        assert!(wait_for_change(&rx, &shared));
+
+        // Files imported after a change are watched as well
+        let content = "Import \"rules/shared.filter\"\nImport \"other/added.filter\"\n";
+        std::fs::write(&path, content).unwrap();
+        assert!(wait_for_change(&rx, &path));
+
+        std::fs::write(&added, "Hide\n\tRarity Normal\nHide\n\tRarity Magic\n").unwrap();
+        assert!(wait_for_change(&rx, &added));
+    }
+
+    #[test]
+    fn test_ignores_other_files_in_directory() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.filter");
+        std::fs::write(&path, "Show\n\tBaseType \"Mirror\"").unwrap();
+        let (tx, rx) = mpsc::channel();
+
+        let _watcher = FilterWatcher::start(&path, &parse_content("").unwrap(), move |changed| {
+            let _ = tx.send(changed.to_vec());
+            Vec::new()
+        })
+        .unwrap();
+
+        std::fs::write(dir.path().join("other.filter"), "Hide").unwrap();
+        assert!(rx.recv_timeout(Duration::from_secs(1)).is_err());
+    }
+}
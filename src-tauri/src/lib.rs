@@ -1,10 +1,41 @@
+pub mod audio;
+pub mod capture;
+pub mod cli;
 mod commands;
 pub mod filter;
+pub mod render;
+pub mod sounds;
 
-use commands::copy_item_desc::copy_item_description_under_cursor;
+use audio::{AudioEngine, AudioState};
+use commands::capture::{get_capture_settings, set_capture_settings, setup_capture};
+use commands::capture_history::{
+    delete_capture, list_capture_history, pin_capture, reevaluate_captures, search_capture_history,
+};
+use commands::coverage::filter_coverage_report;
+use commands::edit_filter::{
+    apply_bulk_edit, edit_filter, preview_bulk_edit, redo_filter_edit, save_filter,
+    undo_filter_edit,
+};
+use commands::export_strictness_variant::export_strictness_variant;
+use commands::find_block_references::find_block_references;
 use commands::greet::greet;
+use commands::lint_sound_files::lint_sound_files;
 use commands::open_file::open_file;
-use commands::play_sound::play_sound;
+use commands::play_sound::{play_sound, set_sound_volume, stop_sounds};
+use commands::preview_block_sound::preview_block_sound;
+use commands::regression::item_regression_report;
+use commands::render_icons::{
+    list_beam_images, list_minimap_icon_images, render_beam_preview, render_minimap_icon_preview,
+};
+use commands::render_label::{export_block_label, render_block_label, render_label_preview};
+use commands::search_blocks::search_blocks;
+use commands::sound_packs::{
+    export_filter_bundle, import_sound_pack, list_sound_packs, list_tier_sounds, use_sound_pack,
+};
+use commands::theme::{apply_theme, export_theme};
+use filter::document::FilterDocumentState;
+use filter::watcher::FilterWatcherState;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -12,11 +43,59 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
+        .manage(FilterWatcherState::default())
+        .manage(FilterDocumentState::default())
+        .setup(|app| {
+            let resource_dir = app.path().resource_dir()?;
+            app.manage(AudioState(AudioEngine::new(&resource_dir)));
+
+            // A taken shortcut should not keep the app from starting
+            if let Err(e) = setup_capture(app.handle()) {
+                eprintln!("{}", e);
+            }
+
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
-            copy_item_description_under_cursor,
+            get_capture_settings,
+            set_capture_settings,
+            list_capture_history,
+            search_capture_history,
+            pin_capture,
+            delete_capture,
+            reevaluate_captures,
+            item_regression_report,
+            filter_coverage_report,
+            render_label_preview,
+            render_block_label,
+            export_block_label,
+            render_minimap_icon_preview,
+            render_beam_preview,
+            list_minimap_icon_images,
+            list_beam_images,
             play_sound,
-            open_file
+            stop_sounds,
+            set_sound_volume,
+            preview_block_sound,
+            lint_sound_files,
+            list_sound_packs,
+            import_sound_pack,
+            use_sound_pack,
+            list_tier_sounds,
+            export_filter_bundle,
+            open_file,
+            edit_filter,
+            preview_bulk_edit,
+            apply_bulk_edit,
+            undo_filter_edit,
+            redo_filter_edit,
+            save_filter,
+            search_blocks,
+            find_block_references,
+            export_strictness_variant,
+            apply_theme,
+            export_theme
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
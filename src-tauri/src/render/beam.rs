@@ -0,0 +1,127 @@
+use anyhow::Result;
+use tiny_skia::{ColorU8, Pixmap};
+use ts_rs::TS;
+
+use super::{named_color, COLORS};
+use crate::filter::theme::Effect;
+
+/// Width and height in pixels of beam swatches.
+pub const BEAM_WIDTH: u32 = 24;
+pub const BEAM_HEIGHT: u32 = 96;
+
+/// A rendered beam swatch.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct BeamImage {
+    pub effect: Effect,
+    // PNG image as a `data:` URL
+    pub url: String,
+}
+
+/// Draws a swatch of the beam of `PlayEffect`: a column of light in the color with a
+/// white core, fading out towards the top.
+///
+/// `Temp` beams only show for a moment when the item drops, their swatch fades out
+/// halfway up.
+pub fn render_beam(effect: &Effect) -> Result<Pixmap> {
+    let [red, green, blue, _] = named_color(&effect.color)?;
+    let mut pixmap = Pixmap::new(BEAM_WIDTH, BEAM_HEIGHT).unwrap();
+    let center = BEAM_WIDTH as f32 / 2.0;
+
+    let (width, pixels) = (BEAM_WIDTH as usize, pixmap.pixels_mut());
+
+    for (index, pixel) in pixels.iter_mut().enumerate() {
+        let (x, y) = ((index % width) as f32 + 0.5, (index / width) as f32 + 0.5);
+
+        // From 0 at the center to 1 at the sides, and from 0 at the top to 1 at the bottom
+        let distance = ((x - center).abs() / center).min(1.0);
+        let height = y / BEAM_HEIGHT as f32;
+
+        let fade = match effect.temp {
+            true => ((height - 0.5) * 2.0).max(0.0),
+            false => height.sqrt(),
+        };
+
+        let glow = (1.0 - distance).powi(2);
+        let core = (1.0 - distance * 4.0).max(0.0);
+        let alpha = glow * fade;
+
+        let channel = |value: u8| {
+            let value = value as f32 + (255.0 - value as f32) * core;
+            value.round() as u8
+        };
+
+        let color = ColorU8::from_rgba(
+            channel(red),
+            channel(green),
+            channel(blue),
+            (alpha * 255.0).round() as u8,
+        );
+
+        *pixel = color.premultiply();
+    }
+
+    Ok(pixmap)
+}
+
+/// Returns every beam: each color, lasting and `Temp`.
+pub fn all_beams() -> Vec<Effect> {
+    let temps = [false, true];
+
+    temps
+        .iter()
+        .flat_map(|temp| {
+            COLORS.iter().map(|(color, _)| Effect {
+                color: color.to_string(),
+                temp: *temp,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{assert_golden, sheet};
+
+    fn effect(color: &str, temp: bool) -> Effect {
+        Effect {
+            color: color.to_string(),
+            temp,
+        }
+    }
+
+    #[test]
+    fn test_render_beam() {
+        let beam = render_beam(&effect("Red", false)).unwrap();
+        assert_eq!((beam.width(), beam.height()), (BEAM_WIDTH, BEAM_HEIGHT));
+
+        // Almost white core, colored glow and transparent sides at the bottom
+        let bottom = BEAM_HEIGHT - 1;
+        let core = beam.pixel(BEAM_WIDTH / 2, bottom).unwrap().demultiply();
+        assert!(core.red() > 250 && core.green() > 200 && core.blue() > 200);
+
+        let glow = beam.pixel(BEAM_WIDTH / 4, bottom).unwrap().demultiply();
+        assert!(glow.red() > 240 && glow.green() < 70 && glow.blue() < 70);
+        assert_eq!(beam.pixel(0, bottom).unwrap().alpha(), 0);
+
+        // Only the lower half of temporary beams is visible
+        let temp = render_beam(&effect("Red", true)).unwrap();
+        let (x, y) = (BEAM_WIDTH / 2, BEAM_HEIGHT / 3);
+        assert_eq!(temp.pixel(x, y).unwrap().alpha(), 0);
+        assert!(beam.pixel(x, y).unwrap().alpha() > 0);
+
+        assert_eq!(all_beams().len(), 22);
+        assert!(render_beam(&effect("None", false)).is_err());
+    }
+
+    #[test]
+    fn test_golden_beams() {
+        let beams: Vec<Pixmap> = all_beams()
+            .iter()
+            .map(|effect| render_beam(effect).unwrap())
+            .collect();
+
+        assert_golden("beams", &sheet(&beams));
+    }
+}
@@ -0,0 +1,313 @@
+use ab_glyph::{point, Font, FontRef, Glyph, PxScale, ScaleFont};
+use std::collections::BTreeMap;
+use tiny_skia::{Mask, Paint, PathBuilder, Pixmap, Rect, Stroke, Transform};
+use ts_rs::TS;
+
+use super::{parse_rgba, Rgba};
+use crate::capture::item::Item;
+use crate::filter::parser::blocks::Block;
+
+/// Font of the labels, the app uses the same font to show the filter.
+const FONT: &[u8] = include_bytes!("../../../public/fonts/fontin-bold.ttf");
+
+/// Font size of labels without `SetFontSize`.
+pub const DEFAULT_FONT_SIZE: u32 = 32;
+
+/// The game draws smaller or larger font sizes with the closest of these.
+const MIN_FONT_SIZE: u32 = 18;
+const MAX_FONT_SIZE: u32 = 45;
+
+/// Background of labels without `SetBackgroundColor`.
+const DEFAULT_BACKGROUND_COLOR: Rgba = [0, 0, 0, 240];
+
+/// Text colors of labels without `SetTextColor` by rarity, other rarities are Normal.
+const RARITY_COLORS: [(&str, Rgba); 6] = [
+    ("Normal", [200, 200, 200, 255]),
+    ("Magic", [136, 136, 255, 255]),
+    ("Rare", [255, 255, 119, 255]),
+    ("Unique", [175, 96, 37, 255]),
+    ("Currency", [170, 158, 130, 255]),
+    ("Gem", [27, 162, 155, 255]),
+];
+
+/// Everything that decides how the label of an item looks.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct LabelStyle {
+    // Name of the item as shown on the label
+    pub text: String,
+    pub text_color: Rgba,
+    // `None` if the label has no border
+    pub border_color: Option<Rgba>,
+    pub background_color: Rgba,
+    pub font_size: u32,
+}
+
+impl LabelStyle {
+    /// Returns the style of the label of an item with the actions of the filter, such as
+    /// the actions of an [`ItemMatch`](crate::filter::matcher::ItemMatch).
+    pub fn for_item(item: &Item, actions: &BTreeMap<String, Vec<String>>) -> Self {
+        let text = match item.stack_size.filter(|size| *size > 1) {
+            Some(size) => format!("{}x {}", size, item.base_type),
+            None => item.name.clone().unwrap_or_else(|| item.base_type.clone()),
+        };
+
+        let params = |name: &str| actions.get(name).map(Vec::as_slice);
+        Self::with_actions(text, &item.rarity, params)
+    }
+
+    /// Returns the style of a block for an item that it matches, named after the first base
+    /// type or class of the block.
+    pub fn for_block(block: &Block) -> Self {
+        let params = |name: &str| {
+            let items = block.items.get(name)?;
+            items.first().map(|item| item.params.as_slice())
+        };
+
+        let values = |name: &str| condition_values(params(name));
+        let text = values("BaseType")
+            .chain(values("Class"))
+            .next()
+            .cloned()
+            .unwrap_or_else(|| "Item".to_string());
+
+        let rarity = match values("Class").next() {
+            Some(class) if class.contains("Currency") => "Currency",
+            Some(class) if class.contains("Gem") => "Gem",
+            _ => values("Rarity")
+                .next_back()
+                .map_or("Normal", String::as_str),
+        };
+
+        Self::with_actions(text, rarity, params)
+    }
+
+    fn with_actions<'a>(
+        text: String,
+        rarity: &str,
+        params: impl Fn(&str) -> Option<&'a [String]>,
+    ) -> Self {
+        let color = |name: &str| params(name).and_then(parse_rgba);
+
+        let font_size = params("SetFontSize")
+            .and_then(|params| params.first()?.parse().ok())
+            .unwrap_or(DEFAULT_FONT_SIZE);
+
+        LabelStyle {
+            text,
+            text_color: color("SetTextColor").unwrap_or_else(|| rarity_color(rarity)),
+            border_color: color("SetBorderColor"),
+            background_color: color("SetBackgroundColor").unwrap_or(DEFAULT_BACKGROUND_COLOR),
+            font_size,
+        }
+    }
+}
+
+/// Draws the label as the game shows it on the ground: the name on the background
+/// color, inside a border if there is one.
+///
+/// The font size is in pixels, so labels have about the size they have in the game at a
+/// resolution of 1080p.
+pub fn render_label(style: &LabelStyle) -> Pixmap {
+    let font = FontRef::try_from_slice(FONT).expect("The bundled font is valid");
+    let font_size = style.font_size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
+    let scale = PxScale::from(font_size as f32);
+    let font = font.as_scaled(scale);
+
+    let padding_x = font_size / 3;
+    let padding_y = font_size / 8;
+    let border_width = match style.border_color {
+        Some(_) => (font_size / 16).max(1),
+        None => 0,
+    };
+
+    let (glyphs, text_width) = layout(&font, &style.text);
+    let text_height = font.height().ceil() as u32;
+    let inset = padding_x + border_width;
+    let width = text_width + 2 * inset;
+    let height = text_height + 2 * (padding_y + border_width);
+
+    let mut pixmap = Pixmap::new(width, height).expect("Labels are never empty");
+    let bounds = Rect::from_xywh(0.0, 0.0, width as f32, height as f32).unwrap();
+    let identity = Transform::identity();
+    pixmap.fill_rect(bounds, &paint(style.background_color), identity, None);
+
+    if let Some(border_color) = style.border_color {
+        let half = border_width as f32 / 2.0;
+        let rect = Rect::from_ltrb(half, half, width as f32 - half, height as f32 - half);
+        let path = PathBuilder::from_rect(rect.unwrap());
+        let stroke = Stroke {
+            width: border_width as f32,
+            ..Stroke::default()
+        };
+
+        pixmap.stroke_path(&path, &paint(border_color), &stroke, identity, None);
+    }
+
+    let mut mask = Mask::new(width, height).unwrap();
+    let offset = point(inset as f32, (padding_y + border_width) as f32);
+
+    for mut glyph in glyphs {
+        glyph.position += offset;
+
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+
+        let min = outlined.px_bounds().min;
+        let data = mask.data_mut();
+
+        outlined.draw(|x, y, coverage| {
+            let x = min.x as i64 + x as i64;
+            let y = min.y as i64 + y as i64;
+
+            if (0..width as i64).contains(&x) && (0..height as i64).contains(&y) {
+                let pixel = &mut data[(y * width as i64 + x) as usize];
+                *pixel = (*pixel).max((coverage * 255.0).round() as u8);
+            }
+        });
+    }
+
+    pixmap.fill_rect(bounds, &paint(style.text_color), identity, Some(&mask));
+    pixmap
+}
+
+/// Positions the glyphs of the text on a line that starts at 0, with the top of the line
+/// at 0. Returns the glyphs and the width of the line.
+fn layout<F: Font>(font: &impl ScaleFont<F>, text: &str) -> (Vec<Glyph>, u32) {
+    let mut glyphs = Vec::new();
+    let mut x = 0.0;
+    let mut previous = None;
+
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+
+        if let Some(previous) = previous {
+            x += font.kern(previous, id);
+        }
+
+        glyphs.push(id.with_scale_and_position(font.scale(), point(x, font.ascent())));
+        x += font.h_advance(id);
+        previous = Some(id);
+    }
+
+    (glyphs, (x.ceil() as u32).max(1))
+}
+
+fn paint(color: Rgba) -> Paint<'static> {
+    let [red, green, blue, alpha] = color;
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(red, green, blue, alpha);
+    paint
+}
+
+fn rarity_color(rarity: &str) -> Rgba {
+    RARITY_COLORS
+        .iter()
+        .find(|(name, _)| *name == rarity)
+        .map_or(RARITY_COLORS[0].1, |(_, color)| *color)
+}
+
+/// Returns the values of a condition without its operator.
+fn condition_values(params: Option<&[String]>) -> impl DoubleEndedIterator<Item = &String> {
+    let is_operator = |value: &&String| value.chars().all(|c| "<>=!".contains(c));
+    params
+        .unwrap_or_default()
+        .iter()
+        .filter(move |value| !is_operator(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::parse_file::parse_content;
+    use crate::render::assert_golden;
+
+    fn style(text: &str, font_size: u32) -> LabelStyle {
+        LabelStyle {
+            text: text.to_string(),
+            text_color: [200, 200, 200, 255],
+            border_color: None,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            font_size,
+        }
+    }
+
+    #[test]
+    fn test_for_block() {
+        let filter = parse_content(
+            "\
+Show
+\tClass == \"Stackable Currency\"
+\tBaseType == \"Divine Orb\" \"Mirror of Kalandra\"
+\tSetTextColor 255 0 0
+\tSetBorderColor 255 0 0 200
+\tSetFontSize 45
+Show
+\tRarity <= Rare
+",
+        )
+        .unwrap();
+
+        let divine = LabelStyle::for_block(&filter.blocks[0]);
+        assert_eq!(divine.text, "Divine Orb");
+        assert_eq!(divine.text_color, [255, 0, 0, 255]);
+        assert_eq!(divine.border_color, Some([255, 0, 0, 200]));
+        assert_eq!(divine.background_color, DEFAULT_BACKGROUND_COLOR);
+        assert_eq!(divine.font_size, 45);
+
+        let rare = LabelStyle::for_block(&filter.blocks[1]);
+        assert_eq!(rare.text, "Item");
+        assert_eq!(rare.text_color, rarity_color("Rare"));
+        assert_eq!(rare.border_color, None);
+        assert_eq!(rare.font_size, DEFAULT_FONT_SIZE);
+    }
+
+    #[test]
+    fn test_for_item() {
+        let item = Item {
+            class: "Stackable Currency".to_string(),
+            rarity: "Currency".to_string(),
+            base_type: "Chaos Orb".to_string(),
+            stack_size: Some(7),
+            ..Item::default()
+        };
+
+        let actions = BTreeMap::from([("SetFontSize".to_string(), vec!["40".to_string()])]);
+        let style = LabelStyle::for_item(&item, &actions);
+        assert_eq!(style.text, "7x Chaos Orb");
+        assert_eq!(style.text_color, rarity_color("Currency"));
+        assert_eq!(style.font_size, 40);
+    }
+
+    #[test]
+    fn test_label_size() {
+        let small = render_label(&style("Chaos Orb", 18));
+        let large = render_label(&style("Chaos Orb", 45));
+        let clamped = render_label(&style("Chaos Orb", 60));
+        assert!(small.width() < large.width() && small.height() < large.height());
+        assert_eq!(large.data(), clamped.data());
+
+        let longer = render_label(&style("Chaos Orb of Chaos", 18));
+        assert!(longer.width() > small.width());
+        assert_eq!(longer.height(), small.height());
+    }
+
+    #[test]
+    fn test_golden_labels() {
+        let mut divine = style("Divine Orb", 45);
+        divine.text_color = [255, 0, 0, 255];
+        divine.border_color = Some([255, 0, 0, 255]);
+        divine.background_color = [255, 255, 255, 255];
+
+        let mut translucent = style("3x Chaos Orb", 32);
+        translucent.text_color = [170, 158, 130, 200];
+        translucent.background_color = [20, 40, 60, 120];
+
+        let ruby = style("Ruby Ring", DEFAULT_FONT_SIZE);
+        assert_golden("label_default", &render_label(&ruby));
+        assert_golden("label_border", &render_label(&divine));
+        assert_golden("label_translucent", &render_label(&translucent));
+        assert_golden("label_small", &render_label(&style("Scroll of Wisdom", 18)));
+    }
+}
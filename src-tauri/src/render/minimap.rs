@@ -0,0 +1,246 @@
+use anyhow::{bail, Result};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+use tiny_skia::{FillRule, Paint, Path, PathBuilder, Pixmap, Stroke, Transform};
+use ts_rs::TS;
+
+use super::{named_color, COLORS};
+use crate::filter::theme::MinimapIcon;
+
+/// Shapes of `MinimapIcon`.
+pub const SHAPES: [&str; 12] = [
+    "Circle",
+    "Diamond",
+    "Hexagon",
+    "Square",
+    "Star",
+    "Triangle",
+    "Cross",
+    "Moon",
+    "Raindrop",
+    "Kite",
+    "Pentagon",
+    "UpsideDownHouse",
+];
+
+/// Width and height in pixels of the icons by size, 0 is the largest.
+pub const ICON_SIZES: [u32; 3] = [32, 24, 16];
+
+/// Points on the outline of curved shapes.
+const CURVE_POINTS: usize = 48;
+
+/// A rendered minimap icon.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct MinimapIconImage {
+    pub icon: MinimapIcon,
+    // PNG image as a `data:` URL
+    pub url: String,
+}
+
+/// Draws the icon as the game shows it on the minimap: the shape filled with the color
+/// inside a dark outline, on a transparent background.
+pub fn render_minimap_icon(icon: &MinimapIcon) -> Result<Pixmap> {
+    let Some(&size) = ICON_SIZES.get(icon.size as usize) else {
+        bail!("Unknown minimap icon size {}", icon.size);
+    };
+
+    let color = named_color(&icon.color)?;
+    let path = shape_path(&icon.shape)?;
+
+    let mut pixmap = Pixmap::new(size, size).unwrap();
+    let transform = Transform::from_scale(size as f32, size as f32);
+
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color[0], color[1], color[2], color[3]);
+    paint.anti_alias = true;
+    pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None);
+
+    // The width is in units of the shape, which is scaled to the icon
+    let stroke = Stroke {
+        width: 1.5 / size as f32,
+        ..Stroke::default()
+    };
+
+    paint.set_color_rgba8(20, 20, 20, 255);
+    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+    Ok(pixmap)
+}
+
+/// Returns every icon: each shape in each color and size.
+pub fn all_minimap_icons() -> Vec<MinimapIcon> {
+    let mut icons = Vec::new();
+
+    for size in 0..ICON_SIZES.len() as u8 {
+        for (color, _) in COLORS {
+            for shape in SHAPES {
+                icons.push(MinimapIcon {
+                    size,
+                    color: color.to_string(),
+                    shape: shape.to_string(),
+                });
+            }
+        }
+    }
+
+    icons
+}
+
+/// Returns the outline of the shape in a square from 0 to 1, with y pointing down.
+fn shape_path(shape: &str) -> Result<Path> {
+    let points = match shape {
+        "Circle" => ellipse_arc((0.5, 0.5), (0.44, 0.44), 0.0, TAU),
+        "Diamond" => vec![(0.5, 0.04), (0.96, 0.5), (0.5, 0.96), (0.04, 0.5)],
+        "Hexagon" => regular_polygon(6, 0.46),
+        "Square" => vec![(0.1, 0.1), (0.9, 0.1), (0.9, 0.9), (0.1, 0.9)],
+        "Star" => star(5, 0.48, 0.2),
+        "Triangle" => vec![(0.5, 0.06), (0.95, 0.9), (0.05, 0.9)],
+        "Cross" => cross(0.15, 0.44),
+        "Moon" => {
+            // Crescent between the left halves of a circle and of a narrower ellipse
+            let (center, bottom, top) = ((0.5, 0.5), FRAC_PI_2, 3.0 * FRAC_PI_2);
+            let mut points = ellipse_arc(center, (0.44, 0.44), bottom, top);
+            points.extend(ellipse_arc(center, (0.16, 0.44), top, bottom));
+            points
+        }
+        "Raindrop" => {
+            // Round bottom with lines from the tip that touch the circle
+            let (center, radius, tip) = ((0.5, 0.64), 0.32_f32, (0.5, 0.04));
+            let touch = (radius / (center.1 - tip.1)).acos();
+            let start = -FRAC_PI_2 + touch;
+            let end = 3.0 * FRAC_PI_2 - touch;
+
+            let mut points = vec![tip];
+            points.extend(ellipse_arc(center, (radius, radius), start, end));
+            points
+        }
+        "Kite" => vec![(0.5, 0.04), (0.84, 0.38), (0.5, 0.96), (0.16, 0.38)],
+        "Pentagon" => regular_polygon(5, 0.48),
+        "UpsideDownHouse" => vec![
+            (0.1, 0.08),
+            (0.9, 0.08),
+            (0.9, 0.56),
+            (0.5, 0.94),
+            (0.1, 0.56),
+        ],
+        _ => bail!("Unknown minimap icon shape {}", shape),
+    };
+
+    let mut builder = PathBuilder::new();
+    let (first, rest) = points.split_first().unwrap();
+    builder.move_to(first.0, first.1);
+
+    for (x, y) in rest {
+        builder.line_to(*x, *y);
+    }
+
+    builder.close();
+    Ok(builder.finish().unwrap())
+}
+
+/// Points on an ellipse from the `start` to the `end` angle, clockwise on screen.
+fn ellipse_arc(center: (f32, f32), radius: (f32, f32), start: f32, end: f32) -> Vec<(f32, f32)> {
+    // Counterclockwise arcs go the other way around
+    let end = if end < start { end + TAU } else { end };
+    let steps = (CURVE_POINTS as f32 * (end - start) / TAU).ceil() as usize;
+    let ((x, y), (radius_x, radius_y)) = (center, radius);
+
+    (0..=steps)
+        .map(|step| start + (end - start) * step as f32 / steps as f32)
+        .map(|angle| (x + radius_x * angle.cos(), y + radius_y * angle.sin()))
+        .collect()
+}
+
+/// Corners of a polygon with equal sides and a corner at the top.
+fn regular_polygon(corners: usize, radius: f32) -> Vec<(f32, f32)> {
+    star(corners, radius, radius)
+        .into_iter()
+        .step_by(2)
+        .collect()
+}
+
+/// Alternating outer and inner corners of a star with a point at the top.
+fn star(points: usize, outer: f32, inner: f32) -> Vec<(f32, f32)> {
+    (0..points * 2)
+        .map(|corner| {
+            let radius = if corner % 2 == 0 { outer } else { inner };
+            let angle = -FRAC_PI_2 + PI * corner as f32 / points as f32;
+            (0.5 + radius * angle.cos(), 0.5 + radius * angle.sin())
+        })
+        .collect()
+}
+
+/// Corners of a plus sign with arms of half width `arm` and length `length` from the center.
+fn cross(arm: f32, length: f32) -> Vec<(f32, f32)> {
+    let (near, far) = (0.5 - arm, 0.5 + arm);
+    let (start, end) = (0.5 - length, 0.5 + length);
+
+    vec![
+        (near, start),
+        (far, start),
+        (far, near),
+        (end, near),
+        (end, far),
+        (far, far),
+        (far, end),
+        (near, end),
+        (near, far),
+        (start, far),
+        (start, near),
+        (near, near),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{assert_golden, sheet};
+
+    fn icon(size: u8, color: &str, shape: &str) -> MinimapIcon {
+        MinimapIcon {
+            size,
+            color: color.to_string(),
+            shape: shape.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_minimap_icon() {
+        for icon in all_minimap_icons() {
+            let pixmap = render_minimap_icon(&icon).unwrap();
+            let expected = ICON_SIZES[icon.size as usize];
+            assert_eq!((pixmap.width(), pixmap.height()), (expected, expected));
+
+            // Every shape is filled with the color
+            let [red, green, blue, _] = named_color(&icon.color).unwrap();
+            let filled = pixmap.pixels().iter().any(|pixel| {
+                let color = pixel.demultiply();
+                (color.red(), color.green(), color.blue(), color.alpha()) == (red, green, blue, 255)
+            });
+
+            assert!(filled, "{:?}", icon);
+        }
+
+        assert_eq!(all_minimap_icons().len(), 3 * 11 * 12);
+        assert!(render_minimap_icon(&icon(3, "Red", "Star")).is_err());
+        assert!(render_minimap_icon(&icon(0, "Black", "Star")).is_err());
+        assert!(render_minimap_icon(&icon(0, "Red", "Heart")).is_err());
+    }
+
+    #[test]
+    fn test_golden_minimap_icons() {
+        // Every shape of the largest size, in a color each
+        let shapes: Vec<Pixmap> = SHAPES
+            .iter()
+            .zip(COLORS.iter().cycle())
+            .map(|(shape, (color, _))| render_minimap_icon(&icon(0, color, shape)).unwrap())
+            .collect();
+
+        assert_golden("minimap_shapes", &sheet(&shapes));
+
+        let sizes: Vec<Pixmap> = (0..3)
+            .map(|size| render_minimap_icon(&icon(size, "Yellow", "Star")).unwrap())
+            .collect();
+
+        assert_golden("minimap_sizes", &sheet(&sizes));
+    }
+}
@@ -0,0 +1,138 @@
+pub mod beam;
+pub mod label;
+pub mod minimap;
+
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use tiny_skia::Pixmap;
+
+/// Red, green, blue and alpha, as in `SetTextColor 255 0 0 255`.
+pub type Rgba = [u8; 4];
+
+/// Colors of `MinimapIcon` and `PlayEffect` by name, as close to the game as possible.
+pub const COLORS: [(&str, Rgba); 11] = [
+    ("Red", [250, 60, 60, 255]),
+    ("Green", [80, 220, 80, 255]),
+    ("Blue", [70, 130, 255, 255]),
+    ("Brown", [170, 110, 60, 255]),
+    ("White", [245, 245, 245, 255]),
+    ("Yellow", [250, 230, 70, 255]),
+    ("Cyan", [60, 230, 230, 255]),
+    ("Grey", [150, 150, 150, 255]),
+    ("Orange", [255, 150, 40, 255]),
+    ("Pink", [255, 120, 200, 255]),
+    ("Purple", [170, 80, 230, 255]),
+];
+
+/// Returns the color of a `MinimapIcon` or `PlayEffect` color name, such as `Red`.
+pub fn named_color(name: &str) -> Result<Rgba> {
+    match COLORS.iter().find(|(color, _)| *color == name) {
+        Some((_, rgba)) => Ok(*rgba),
+        None => bail!("Unknown color {}", name),
+    }
+}
+
+/// Encodes the image as PNG.
+pub fn encode_png(pixmap: &Pixmap) -> Result<Vec<u8>> {
+    pixmap.encode_png().context("Failed to encode PNG")
+}
+
+/// Returns a `data:` URL of a PNG image that the frontend can use as image source.
+pub fn png_data_url(png: &[u8]) -> String {
+    format!("data:image/png;base64,{}", STANDARD.encode(png))
+}
+
+/// Reads a color from the parameters of a color action, the alpha defaults to 255.
+pub fn parse_rgba(params: &[String]) -> Option<Rgba> {
+    let values: Option<Vec<u8>> = params.iter().map(|p| p.parse().ok()).collect();
+
+    match values?[..] {
+        [red, green, blue] => Some([red, green, blue, 255]),
+        [red, green, blue, alpha] => Some([red, green, blue, alpha]),
+        _ => None,
+    }
+}
+
+/// Compares a rendered image with a golden PNG file next to this module, `golden/<name>.png`.
+///
+/// Set `UPDATE_GOLDEN=1` to write the rendered images as the new golden files after a
+/// deliberate change of the rendering.
+#[cfg(test)]
+pub(crate) fn assert_golden(name: &str, pixmap: &Pixmap) {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/render/golden")
+        .join(format!("{}.png", name));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, encode_png(pixmap).unwrap()).unwrap();
+        return;
+    }
+
+    let golden = Pixmap::load_png(&path)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+
+    assert_eq!(
+        (pixmap.width(), pixmap.height()),
+        (golden.width(), golden.height()),
+        "{}",
+        name
+    );
+
+    // Small differences of anti-aliasing between platforms are accepted
+    let differs = pixmap
+        .data()
+        .iter()
+        .zip(golden.data())
+        .any(|(actual, expected)| actual.abs_diff(*expected) > 2);
+
+    assert!(!differs, "{} differs from {}", name, path.display());
+}
+
+/// Draws the images side by side, to compare several images with one golden file.
+#[cfg(test)]
+pub(crate) fn sheet(pixmaps: &[Pixmap]) -> Pixmap {
+    let width = pixmaps.iter().map(Pixmap::width).sum();
+    let height = pixmaps.iter().map(Pixmap::height).max().unwrap();
+    let mut sheet = Pixmap::new(width, height).unwrap();
+    let mut x = 0;
+
+    for pixmap in pixmaps {
+        let paint = tiny_skia::PixmapPaint::default();
+        let transform = tiny_skia::Transform::identity();
+        sheet.draw_pixmap(x, 0, pixmap.as_ref(), &paint, transform, None);
+        x += pixmap.width() as i32;
+    }
+
+    sheet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rgba() {
+        let params = |text: &str| text.split(' ').map(str::to_string).collect::<Vec<_>>();
+
+        assert_eq!(parse_rgba(&params("255 0 0")), Some([255, 0, 0, 255]));
+        assert_eq!(parse_rgba(&params("10 20 30 40")), Some([10, 20, 30, 40]));
+        assert_eq!(parse_rgba(&params("10 20")), None);
+        assert_eq!(parse_rgba(&params("10 20 300")), None);
+    }
+
+    #[test]
+    fn test_named_color() {
+        assert_eq!(named_color("Red").unwrap(), COLORS[0].1);
+        assert!(named_color("red").is_err());
+        assert!(named_color("None").is_err());
+    }
+
+    #[test]
+    fn test_png_data_url() {
+        let pixmap = Pixmap::new(2, 1).unwrap();
+        let url = png_data_url(&encode_png(&pixmap).unwrap());
+        assert!(url.starts_with("data:image/png;base64,iVBORw0KGgo"));
+    }
+}
@@ -0,0 +1,317 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use ts_rs::TS;
+
+use super::PACKS_DIR;
+use crate::filter::document::edit_command::EditCommand;
+use crate::filter::document::FilterDocument;
+use crate::filter::parser::block_item::BlockItem;
+use crate::filter::parser::encoding::{encode, TextFormat};
+use crate::filter::parser::keywords::intern_keyword;
+use crate::filter::theme::Sound;
+
+/// Contents of an exported filter bundle.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct BundleReport {
+    // Files in the zip, the filter first
+    pub files: Vec<String>,
+    // Custom sounds of the filter that were not found and files imported by the filter,
+    // which are not in the zip
+    pub missing: Vec<String>,
+}
+
+/// Writes the filter and the custom sound files it plays to a zip file that can be
+/// extracted into another filter directory.
+///
+/// Sounds with relative paths inside of the filter directory keep their paths. Other
+/// sounds are stored in the `sounds` directory of the zip and the filter in the zip plays
+/// them from there. Different files with the same name get a number after the name, such
+/// as `sounds/alert-2.wav`. The filter is written in its `format`.
+///
+/// Files imported by the filter are not included and are reported as missing, as they
+/// can be shared by several filters.
+pub fn export_bundle(
+    filter_path: &Path,
+    content: &str,
+    format: &TextFormat,
+    destination: &Path,
+) -> Result<BundleReport> {
+    let filter_dir = filter_path.parent().unwrap_or(Path::new(""));
+    let mut document = FilterDocument::new(content.to_string())?;
+    let mut sounds: BTreeMap<String, PathBuf> = BTreeMap::new();
+    let mut commands = Vec::new();
+
+    let mut missing: Vec<String> = document
+        .filter()
+        .imports
+        .iter()
+        .map(|import| import.path.clone())
+        .collect();
+
+    let mut custom_sounds = Vec::new();
+
+    for block in &document.filter().blocks {
+        let Some(Sound::Custom { path, optional, .. }) = Sound::from_block(block) else {
+            continue;
+        };
+
+        let source = filter_dir.join(&path);
+
+        if !source.is_file() {
+            if !missing.contains(&path) {
+                missing.push(path);
+            }
+
+            continue;
+        }
+
+        custom_sounds.push((block, path, optional, source));
+    }
+
+    // Sounds inside of the directory keep their paths, so their entries are reserved
+    // before the other sounds get free names. An entry can only be kept once, by the
+    // first file with the path.
+    let mut outside = Vec::new();
+
+    for (block, path, optional, source) in custom_sounds {
+        let inside = is_inside(Path::new(&path))
+            .then(|| path.replace('\\', "/"))
+            .filter(|entry| match sounds.get(entry) {
+                Some(bundled) => same_file(bundled, &source),
+                None => true,
+            });
+
+        match inside {
+            Some(entry) => {
+                sounds.entry(entry).or_insert(source);
+            }
+            None => outside.push((block, optional, source)),
+        }
+    }
+
+    for (block, optional, source) in outside {
+        let entry = bundled_entry(&sounds, &source);
+
+        let keyword = match optional {
+            true => "CustomAlertSoundOptional",
+            false => "CustomAlertSound",
+        };
+
+        let mut params = block.items[keyword][0].params.clone();
+        params[0] = entry.clone();
+
+        let item = BlockItem {
+            name: intern_keyword(keyword),
+            params,
+        };
+
+        commands.push(EditCommand::SetItem { id: block.id, item });
+        sounds.entry(entry).or_insert(source);
+    }
+
+    document.apply(&EditCommand::Batch { commands })?;
+
+    let filter_name = filter_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "filter.filter".to_string());
+
+    let file = File::create(destination)
+        .with_context(|| format!("Failed to create bundle {}", destination.display()))?;
+
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    zip.start_file(filter_name.as_str(), options)?;
+    zip.write_all(&encode(document.content(), format)?)?;
+    let mut files = vec![filter_name];
+
+    for (entry, source) in sounds {
+        zip.start_file(entry.as_str(), options)?;
+        zip.write_all(&std::fs::read(&source)?)?;
+        files.push(entry);
+    }
+
+    zip.finish()?;
+    Ok(BundleReport { files, missing })
+}
+
+/// Returns the entry of a sound in the `sounds` directory of the zip: the entry of the same
+/// file if it is already bundled, or the first entry that no other file uses.
+fn bundled_entry(sounds: &BTreeMap<String, PathBuf>, source: &Path) -> String {
+    let bundled = sounds.iter().find(|(_, file)| same_file(file, source));
+
+    if let Some((entry, _)) = bundled {
+        return entry.clone();
+    }
+
+    let name = source.file_name().unwrap_or_default().to_string_lossy();
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+
+    let extension = match source.extension() {
+        Some(extension) => format!(".{}", extension.to_string_lossy()),
+        None => String::new(),
+    };
+
+    (1..)
+        .map(|number| match number {
+            1 => format!("{}/{}", PACKS_DIR, name),
+            _ => format!("{}/{}-{}{}", PACKS_DIR, stem, number, extension),
+        })
+        .find(|entry| !sounds.contains_key(entry))
+        .unwrap()
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Returns true if the relative path stays inside of the directory it is relative to.
+fn is_inside(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::encoding::{decode, LineEnding, TextEncoding};
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    fn read_entry(archive: &mut zip::ZipArchive<File>, name: &str) -> String {
+        let mut content = String::new();
+        let mut entry = archive.by_name(name).unwrap();
+        entry.read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_export_bundle() {
+        let dir = tempdir().unwrap();
+        let filter_dir = dir.path().join("filters");
+        std::fs::create_dir_all(filter_dir.join("sounds")).unwrap();
+        std::fs::write(filter_dir.join("sounds/divine.mp3"), "divine").unwrap();
+        std::fs::write(dir.path().join("shared.wav"), "shared").unwrap();
+
+        let content = "\
+Show
+\tBaseType \"Divine Orb\"
+\tCustomAlertSound \"sounds/divine.mp3\" 300
+Show
+\tBaseType \"Mirror of Kalandra\"
+\tCustomAlertSoundOptional \"../shared.wav\" 200
+Show
+\tBaseType \"Chaos Orb\"
+\tCustomAlertSound \"missing.mp3\"
+";
+
+        let filter_path = filter_dir.join("main.filter");
+        let destination = dir.path().join("bundle.zip");
+        let format = TextFormat::default();
+        let report = export_bundle(&filter_path, content, &format, &destination).unwrap();
+        let files = ["main.filter", "sounds/divine.mp3", "sounds/shared.wav"];
+        assert_eq!(report.files, files);
+        assert_eq!(report.missing, ["missing.mp3"]);
+
+        let mut archive = zip::ZipArchive::new(File::open(&destination).unwrap()).unwrap();
+        let expected = content.replace("\"../shared.wav\"", "sounds/shared.wav");
+        assert_eq!(read_entry(&mut archive, "main.filter"), expected);
+        assert_eq!(read_entry(&mut archive, "sounds/shared.wav"), "shared");
+    }
+
+    #[test]
+    fn test_export_sounds_with_the_same_name() {
+        let dir = tempdir().unwrap();
+        let filter_dir = dir.path().join("filters");
+
+        for (path, content) in [
+            ("filters/sounds/alert.wav", "inside"),
+            ("a/alert.wav", "a"),
+            ("b/alert.wav", "b"),
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let content = "\
+Show
+\tCustomAlertSound \"../a/alert.wav\"
+Show
+\tCustomAlertSound \"sounds/alert.wav\"
+Show
+\tCustomAlertSound \"../b/alert.wav\"
+Show
+\tCustomAlertSound \"../a/alert.wav\"
+";
+
+        let filter_path = filter_dir.join("main.filter");
+        let destination = dir.path().join("bundle.zip");
+        let format = TextFormat::default();
+        let report = export_bundle(&filter_path, content, &format, &destination).unwrap();
+
+        let files = [
+            "main.filter",
+            "sounds/alert-2.wav",
+            "sounds/alert-3.wav",
+            "sounds/alert.wav",
+        ];
+
+        assert_eq!(report.files, files);
+
+        let mut archive = zip::ZipArchive::new(File::open(&destination).unwrap()).unwrap();
+        assert_eq!(read_entry(&mut archive, "sounds/alert.wav"), "inside");
+        assert_eq!(read_entry(&mut archive, "sounds/alert-2.wav"), "a");
+        assert_eq!(read_entry(&mut archive, "sounds/alert-3.wav"), "b");
+
+        let sounds: Vec<String> = read_entry(&mut archive, "main.filter")
+            .lines()
+            .filter_map(|line| line.strip_prefix("\tCustomAlertSound "))
+            .map(str::to_string)
+            .collect();
+
+        let (a, b) = ("sounds/alert-2.wav", "sounds/alert-3.wav");
+        assert_eq!(sounds, [a, "\"sounds/alert.wav\"", b, a]);
+    }
+
+    #[test]
+    fn test_export_keeps_format_and_reports_imports() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("filters")).unwrap();
+        std::fs::write(dir.path().join("alert.wav"), "alert").unwrap();
+
+        let content = "\
+Import \"shared.filter\"\r
+Show\r
+\tCustomAlertSound \"../alert.wav\"\r
+";
+
+        let format = TextFormat {
+            encoding: TextEncoding::Utf16Le,
+            bom: true,
+            line_ending: LineEnding::CrLf,
+        };
+
+        let filter_path = dir.path().join("filters/main.filter");
+        let destination = dir.path().join("bundle.zip");
+        let report = export_bundle(&filter_path, content, &format, &destination).unwrap();
+        assert_eq!(report.missing, ["shared.filter"]);
+
+        let mut archive = zip::ZipArchive::new(File::open(&destination).unwrap()).unwrap();
+        let mut bytes = Vec::new();
+        let mut entry = archive.by_name("main.filter").unwrap();
+        entry.read_to_end(&mut bytes).unwrap();
+
+        let (bundled, bundled_format) = decode(&bytes);
+        let expected = content.replace("\"../alert.wav\"", "sounds/alert.wav");
+        assert_eq!(bundled_format, format);
+        assert_eq!(bundled, expected);
+    }
+}
@@ -0,0 +1,390 @@
+pub mod bundle;
+
+use anyhow::{bail, Context, Result};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::audio::lint::SOUND_EXTENSIONS;
+use crate::filter::parser::blocks::Block;
+use crate::filter::theme::{Sound, Theme};
+
+/// Directory in the filter directory that sound packs are imported into.
+pub const PACKS_DIR: &str = "sounds";
+
+/// Name of the pack of sounds bundled with the app.
+pub const BUILT_IN_PACK: &str = "filturd";
+
+/// A directory of sound files shared as a whole.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct SoundPack {
+    pub name: String,
+    pub dir: String,
+    // Sound files of the pack relative to its directory, with `/` separators
+    pub files: Vec<String>,
+    // The pack is bundled with the app
+    pub built_in: bool,
+}
+
+/// Sound of the blocks of a tier, shown next to the tier.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, TS)]
+#[ts(export)]
+pub struct TierSound {
+    pub tier: String,
+    pub sound: Option<Sound>,
+}
+
+impl SoundPack {
+    /// Lists the sound files in the directory and its subdirectories.
+    pub fn open(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            bail!("Sound pack {} is not a directory", dir.display());
+        }
+
+        let mut files = Vec::new();
+        list_sound_files(dir, dir, &mut files)?;
+        files.sort();
+
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Ok(SoundPack {
+            name,
+            dir: dir.to_string_lossy().to_string(),
+            files,
+            built_in: false,
+        })
+    }
+
+    /// Opens the sounds bundled with the app in `sounds_dir`.
+    pub fn built_in(sounds_dir: &Path) -> Result<Self> {
+        Ok(SoundPack {
+            name: BUILT_IN_PACK.to_string(),
+            built_in: true,
+            ..Self::open(sounds_dir)?
+        })
+    }
+
+    /// Returns the file of the pack with the name of `file`, ignoring the case, the
+    /// directory and the extension, so `Divine.wav` is found for `sounds/divine.mp3`.
+    pub fn find(&self, file: &str) -> Option<&str> {
+        let stem = file_stem(file);
+
+        self.files
+            .iter()
+            .find(|candidate| file_stem(candidate).eq_ignore_ascii_case(&stem))
+            .map(String::as_str)
+    }
+}
+
+/// Returns the built-in pack followed by the packs imported into the filter directory.
+///
+/// The copy of the built-in pack made by [`use_pack`] is not listed again.
+pub fn list_packs(sounds_dir: &Path, filter_dir: &Path) -> Result<Vec<SoundPack>> {
+    let mut packs = vec![SoundPack::built_in(sounds_dir)?];
+
+    let Ok(entries) = std::fs::read_dir(filter_dir.join(PACKS_DIR)) else {
+        return Ok(packs);
+    };
+
+    let mut imported: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !path.ends_with(BUILT_IN_PACK))
+        .collect();
+
+    imported.sort();
+
+    for dir in imported {
+        packs.push(SoundPack::open(&dir)?);
+    }
+
+    Ok(packs)
+}
+
+/// Copies the sound files of a directory or a `.zip` file into the `sounds` directory of
+/// the filter directory. The pack is named after the directory or the zip file.
+///
+/// Other files are skipped. Files of a pack that was imported before are overwritten.
+pub fn import_pack(source: &Path, filter_dir: &Path) -> Result<SoundPack> {
+    let name = source
+        .file_stem()
+        .with_context(|| format!("Invalid sound pack {}", source.display()))?;
+
+    copy_pack(source, Path::new(name), filter_dir)
+}
+
+/// Copies the sound files of a directory or a `.zip` file into the pack with the name
+/// in the `sounds` directory of the filter directory.
+fn copy_pack(source: &Path, name: &Path, filter_dir: &Path) -> Result<SoundPack> {
+    let target = filter_dir.join(PACKS_DIR).join(name);
+    std::fs::create_dir_all(&target)?;
+
+    if source.is_dir() {
+        let mut files = Vec::new();
+        list_sound_files(source, source, &mut files)?;
+
+        for file in files {
+            let path = target.join(&file);
+            std::fs::create_dir_all(path.parent().unwrap_or(&target))?;
+            std::fs::copy(source.join(&file), path)?;
+        }
+    } else {
+        extract_zip(source, &target)?;
+    }
+
+    SoundPack::open(&target)
+}
+
+/// Points the custom sounds of the theme at the files of the pack with the same name.
+///
+/// The paths are relative to the filter directory if the pack is inside of it. The game
+/// only plays sounds from the filter directory, so the built-in pack is first copied to
+/// `sounds/filturd` in it.
+///
+/// Returns the number of changed styles.
+pub fn use_pack(theme: &mut Theme, pack: &SoundPack, filter_dir: &Path) -> Result<usize> {
+    let imported;
+
+    let pack = match pack.built_in {
+        true => {
+            let name = Path::new(BUILT_IN_PACK);
+            imported = copy_pack(Path::new(&pack.dir), name, filter_dir)?;
+            &imported
+        }
+        false => pack,
+    };
+
+    let pack_dir = Path::new(&pack.dir);
+    let base = pack_dir.strip_prefix(filter_dir).unwrap_or(pack_dir);
+    let mut count = 0;
+
+    for style in theme.styles.values_mut() {
+        let Some(Sound::Custom { path, .. }) = &mut style.sound else {
+            continue;
+        };
+
+        let Some(file) = pack.find(path) else {
+            continue;
+        };
+
+        let pack_path = base.join(file).to_string_lossy().replace('\\', "/");
+
+        if *path != pack_path {
+            *path = pack_path;
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Returns the sound of each tier, taken from the first block of the tier.
+pub fn tier_sounds(blocks: &[Block]) -> Vec<TierSound> {
+    Theme::extract(blocks)
+        .styles
+        .into_iter()
+        .filter(|(key, _)| !key.contains('/'))
+        .map(|(tier, style)| TierSound {
+            tier,
+            sound: style.sound,
+        })
+        .collect()
+}
+
+/// Adds the sound files under `dir` to `files`, relative to `root`.
+fn list_sound_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            list_sound_files(root, &path, files)?;
+        } else if is_sound_file(&path) {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            files.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Extracts the sound files of the zip into `target`, keeping their directories.
+fn extract_zip(source: &Path, target: &Path) -> Result<()> {
+    let file = File::open(source)
+        .with_context(|| format!("Failed to open sound pack {}", source.display()))?;
+
+    let mut archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("Sound pack {} is not a zip file", source.display()))?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+
+        // Entries with absolute paths or `..` would be written outside of the target
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+
+        if entry.is_dir() || !is_sound_file(&name) {
+            continue;
+        }
+
+        let path = target.join(name);
+        std::fs::create_dir_all(path.parent().unwrap_or(target))?;
+        std::io::copy(&mut entry, &mut File::create(path)?)?;
+    }
+
+    Ok(())
+}
+
+fn is_sound_file(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| {
+        let extension = extension.to_string_lossy().to_lowercase();
+        SOUND_EXTENSIONS.contains(&extension.as_str())
+    })
+}
+
+fn file_stem(file: &str) -> String {
+    Path::new(&file.replace('\\', "/"))
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::parser::parse_file::parse_content;
+    use crate::filter::theme::TierStyle;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn custom(path: &str) -> TierStyle {
+        TierStyle {
+            sound: Some(Sound::Custom {
+                path: path.to_string(),
+                volume: Some(300),
+                optional: false,
+            }),
+            ..TierStyle::default()
+        }
+    }
+
+    #[test]
+    fn test_built_in_pack() {
+        let sounds_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/sounds");
+        let pack = SoundPack::built_in(&sounds_dir).unwrap();
+        assert_eq!(pack.name, BUILT_IN_PACK);
+        assert!(pack.files.contains(&"camera_snap1.mp3".to_string()));
+        assert!(pack.files.contains(&"alerts/AlertSound1.wav".to_string()));
+        let found = pack.find("sounds/CAMERA_SNAP1.wav");
+        assert_eq!(found, Some("camera_snap1.mp3"));
+    }
+
+    #[test]
+    fn test_import_folder() {
+        let source = tempdir().unwrap();
+        std::fs::create_dir(source.path().join("loot")).unwrap();
+        std::fs::write(source.path().join("loot/divine.mp3"), "mp3").unwrap();
+        std::fs::write(source.path().join("readme.txt"), "text").unwrap();
+        let pack_dir = source.path().join("loot");
+
+        let filter_dir = tempdir().unwrap();
+        let pack = import_pack(&pack_dir, filter_dir.path()).unwrap();
+        assert_eq!(pack.name, "loot");
+        assert_eq!(pack.files, ["divine.mp3"]);
+        assert!(filter_dir.path().join("sounds/loot/divine.mp3").exists());
+
+        let packs = list_packs(source.path(), filter_dir.path()).unwrap();
+        let names: Vec<&str> = packs.iter().map(|pack| pack.name.as_str()).collect();
+        assert_eq!(names, [BUILT_IN_PACK, "loot"]);
+    }
+
+    #[test]
+    fn test_import_zip() {
+        let dir = tempdir().unwrap();
+        let zip_path = dir.path().join("chimes.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+
+        for name in ["high/t1.wav", "t2.ogg", "cover.png", "../escape.wav"] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(b"sound").unwrap();
+        }
+
+        zip.finish().unwrap();
+
+        let filter_dir = tempdir().unwrap();
+        let pack = import_pack(&zip_path, filter_dir.path()).unwrap();
+        assert_eq!(pack.name, "chimes");
+        assert_eq!(pack.files, ["high/t1.wav", "t2.ogg"]);
+        assert!(!filter_dir.path().join("sounds/escape.wav").exists());
+    }
+
+    #[test]
+    fn test_use_pack() {
+        let filter_dir = Path::new("filters");
+
+        let pack = SoundPack {
+            name: "chimes".to_string(),
+            dir: "filters/sounds/chimes".to_string(),
+            files: vec!["high/T1.wav".to_string(), "t2.ogg".to_string()],
+            built_in: false,
+        };
+
+        let styles = [
+            ("t1", custom("t1.mp3")),
+            ("t2", custom("sounds/chimes/t2.ogg")),
+            ("t3", custom("t3.mp3")),
+        ];
+
+        let mut theme = Theme {
+            styles: styles.map(|(key, style)| (key.to_string(), style)).into(),
+        };
+
+        assert_eq!(use_pack(&mut theme, &pack, filter_dir).unwrap(), 1);
+        assert_eq!(theme.styles["t1"], custom("sounds/chimes/high/T1.wav"));
+        assert_eq!(theme.styles["t3"], custom("t3.mp3"));
+    }
+
+    #[test]
+    fn test_use_built_in_pack() {
+        let sounds_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/sounds");
+        let pack = SoundPack::built_in(&sounds_dir).unwrap();
+        let filter_dir = tempdir().unwrap();
+
+        let mut theme = Theme {
+            styles: [("t1".to_string(), custom("CAMERA_SNAP1.wav"))].into(),
+        };
+
+        assert_eq!(use_pack(&mut theme, &pack, filter_dir.path()).unwrap(), 1);
+        let path = "sounds/filturd/camera_snap1.mp3";
+        assert_eq!(theme.styles["t1"], custom(path));
+        assert!(filter_dir.path().join(path).is_file());
+
+        let packs = list_packs(&sounds_dir, filter_dir.path()).unwrap();
+        assert_eq!(packs.len(), 1);
+        assert!(packs[0].built_in);
+    }
+
+    #[test]
+    fn test_tier_sounds() {
+        let content = "\
+Show # $tier->t1
+\tRarity Unique
+\tPlayAlertSound 1 300
+Show # $tier->t2
+\tRarity Rare
+\tSetFontSize 40
+";
+
+        let filter = parse_content(content).unwrap();
+        let sounds = tier_sounds(&filter.blocks);
+        assert_eq!(sounds.len(), 2);
+        assert_eq!(sounds[0].tier, "t1");
+        assert!(matches!(&sounds[0].sound, Some(Sound::Alert { id, .. }) if id == "1"));
+        assert_eq!(sounds[1].sound, None);
+    }
+}
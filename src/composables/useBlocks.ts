@@ -1,24 +1,51 @@
-import { ref } from "vue";
+import { ref, onUnmounted } from "vue";
+import { listen } from "@tauri-apps/api/event";
 import { openFile } from "../utils/fileOpener";
 import type { Block } from "../../src-tauri/bindings/Block";
+import type { FilterMetadata } from "../../src-tauri/bindings/FilterMetadata";
+import type { FilterReload } from "../../src-tauri/bindings/FilterReload";
+import type { ParsedFilter } from "../../src-tauri/bindings/ParsedFilter";
 import { items_used_in_blocks } from "../utils/items";
 
 export function useBlocks() {
   const blocks = ref<Block[]>([]);
   const usedItemNames = ref<string[]>([]);
+  const metadata = ref<FilterMetadata | null>(null);
 
-  async function loadBlocks() {
-    const result = await openFile();
+  function setFilter(filter: ParsedFilter) {
+    blocks.value = filter.blocks;
+    metadata.value = filter.metadata;
+    usedItemNames.value = items_used_in_blocks(filter.blocks);
+  }
 
-    if (result) {
-      blocks.value = result;
-      usedItemNames.value = items_used_in_blocks(result);
+  async function loadBlocks() {
+    try {
+      const result = await openFile();
+      if (result) setFilter(result);
+    } catch (error) {
+      // Such as an imported file that is missing or can't be parsed
+      console.error(`Failed to open filter: ${error}`);
     }
   }
 
+  // The backend re-parses the opened filter when it or one of its imports is changed by
+  // another program
+  const unlisten = listen<FilterReload>("filter-reloaded", (event) => {
+    if (event.payload.filter) {
+      setFilter(event.payload.filter);
+    } else {
+      console.error(`Failed to reload ${event.payload.path}: ${event.payload.error}`);
+    }
+  });
+
+  onUnmounted(() => {
+    unlisten.then((stop) => stop());
+  });
+
   return {
     blocks,
     usedItemNames,
+    metadata,
     loadBlocks,
   };
 }
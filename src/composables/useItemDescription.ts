@@ -1,28 +1,32 @@
-import { ref } from "vue";
-import { invoke } from "@tauri-apps/api/core";
-import { register, ShortcutEvent, isRegistered } from '@tauri-apps/plugin-global-shortcut';
+import { ref, onUnmounted } from "vue";
 import { getCurrentWindow } from '@tauri-apps/api/window';
+import type { Item } from "../../src-tauri/bindings/Item";
+import type { ItemMatch } from "../../src-tauri/bindings/ItemMatch";
+import { onItemCaptured } from "../utils/capture";
+import { playSound } from "../utils/sound";
 
 export function useItemDescription() {
   const itemDescription = ref("");
-  const key = 'CommandOrControl+1'
+  const item = ref<Item | null>(null);
+  // What the opened filter does with the item
+  const matched = ref<ItemMatch | null>(null);
 
-  async function didPressCopyShortcut(event: ShortcutEvent) {
-    if (event.state !== 'Pressed') { return; }
-    let descriptionFromClipboard: string | null = await invoke('copy_item_description_under_cursor');
-    if (!descriptionFromClipboard) { return; }
-    itemDescription.value = descriptionFromClipboard;
-    invoke('play_sound', { file: 'camera_snap1.mp3' });
+  const unlisten = onItemCaptured(async (entry) => {
+    itemDescription.value = entry.text;
+    item.value = entry.item;
+    matched.value = entry.matched;
+    playSound('camera_snap1.mp3').catch(console.error);
     const window = getCurrentWindow();
     await window.setFocus();
-  }
+  });
 
-  isRegistered(key).then((isRegistered) => {
-    if (isRegistered) { return }
-    register(key, didPressCopyShortcut);
+  onUnmounted(() => {
+    unlisten.then((stop) => stop());
   });
 
   return {
-    itemDescription
+    itemDescription,
+    item,
+    matched
   }
 }
@@ -0,0 +1,18 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { BlockReference } from "../../src-tauri/bindings/BlockReference";
+import type { QueryError } from "../../src-tauri/bindings/QueryError";
+import type { ReferenceKind } from "../../src-tauri/bindings/ReferenceKind";
+
+// Returns the orders of the matching blocks, or the error that explains what is wrong with the query
+export async function searchBlocks(query: string): Promise<number[] | QueryError> {
+  try {
+    return await invoke<number[]>("search_blocks", { query });
+  } catch (error) {
+    return error as QueryError;
+  }
+}
+
+// Lists the blocks that mention the base type or class, for example "Time-Lost Diamond"
+export async function findBlockReferences(kind: ReferenceKind, name: string): Promise<BlockReference[]> {
+  return invoke<BlockReference[]>("find_block_references", { kind, name });
+}
@@ -0,0 +1,43 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { CaptureSettings } from "../../src-tauri/bindings/CaptureSettings";
+import type { HistoryEntry } from "../../src-tauri/bindings/HistoryEntry";
+
+// The capture shortcuts are registered by the backend, which copies the item under the cursor
+// and restores the previous clipboard text
+
+export async function getCaptureSettings(): Promise<CaptureSettings> {
+  return invoke<CaptureSettings>("get_capture_settings");
+}
+
+// Fails without changing the shortcuts if a shortcut is invalid or taken by another app
+export async function setCaptureSettings(settings: CaptureSettings): Promise<void> {
+  return invoke<void>("set_capture_settings", { settings });
+}
+
+// Captured items are added to the history and matched against the opened filter before the event
+export async function onItemCaptured(handler: (entry: HistoryEntry) => void): Promise<UnlistenFn> {
+  return listen<HistoryEntry>("item-captured", (event) => handler(event.payload));
+}
+
+// Pinned first and newest first
+export async function listCaptureHistory(): Promise<HistoryEntry[]> {
+  return invoke<HistoryEntry[]>("list_capture_history");
+}
+
+export async function searchCaptureHistory(query: string): Promise<HistoryEntry[]> {
+  return invoke<HistoryEntry[]>("search_capture_history", { query });
+}
+
+export async function pinCapture(id: number, pinned: boolean): Promise<void> {
+  return invoke<void>("pin_capture", { id, pinned });
+}
+
+export async function deleteCapture(id: number): Promise<void> {
+  return invoke<void>("delete_capture", { id });
+}
+
+// Matches the history against the opened filter after edits, returns the number of changed matches
+export async function reevaluateCaptures(): Promise<number> {
+  return invoke<number>("reevaluate_captures");
+}
@@ -0,0 +1,8 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { CoverageReport } from "../../src-tauri/bindings/CoverageReport";
+
+// Matches items generated from the base types with the opened filter, the base types are a JSON
+// file and default to the ones bundled with the app
+export async function filterCoverageReport(baseTypes: string | null = null): Promise<CoverageReport> {
+  return invoke<CoverageReport>("filter_coverage_report", { baseTypes });
+}
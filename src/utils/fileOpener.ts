@@ -1,9 +1,9 @@
 import { documentDir, join } from "@tauri-apps/api/path";
 import { open } from "@tauri-apps/plugin-dialog";
 import { invoke } from "@tauri-apps/api/core";
-import type { Block } from "../../src-tauri/bindings/Block";
+import type { ParsedFilter } from "../../src-tauri/bindings/ParsedFilter";
 
-export async function openFile(): Promise<Block[] | null> {
+export async function openFile(): Promise<ParsedFilter | null> {
   const documentsDir = await documentDir();
   const poe2Dir = await join(documentsDir, "My Games", "Path of Exile 2");
 
@@ -24,7 +24,7 @@ export async function openFile(): Promise<Block[] | null> {
   });
 
   if (!path) return null;
-  const blocks = await invoke<Block[]>("open_file", { path });
+  const filter = await invoke<ParsedFilter>("open_file", { path });
 
-  return blocks;
+  return filter;
 }
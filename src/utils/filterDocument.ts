@@ -0,0 +1,41 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { BulkEdit } from "../../src-tauri/bindings/BulkEdit";
+import type { BulkEditPreview } from "../../src-tauri/bindings/BulkEditPreview";
+import type { EditCommand } from "../../src-tauri/bindings/EditCommand";
+import type { FilterDocumentView } from "../../src-tauri/bindings/FilterDocumentView";
+import type { Strictness } from "../../src-tauri/bindings/Strictness";
+import type { StrictnessRule } from "../../src-tauri/bindings/StrictnessRule";
+
+// Edits of the opened filter are kept in the backend, which returns the updated filter
+
+export async function editFilter(command: EditCommand): Promise<FilterDocumentView> {
+  return invoke<FilterDocumentView>("edit_filter", { command });
+}
+
+export async function previewBulkEdit(edit: BulkEdit): Promise<BulkEditPreview> {
+  return invoke<BulkEditPreview>("preview_bulk_edit", { edit });
+}
+
+export async function applyBulkEdit(edit: BulkEdit): Promise<FilterDocumentView> {
+  return invoke<FilterDocumentView>("apply_bulk_edit", { edit });
+}
+
+export async function undoFilterEdit(): Promise<FilterDocumentView> {
+  return invoke<FilterDocumentView>("undo_filter_edit");
+}
+
+export async function redoFilterEdit(): Promise<FilterDocumentView> {
+  return invoke<FilterDocumentView>("redo_filter_edit");
+}
+
+export async function saveFilter(): Promise<FilterDocumentView> {
+  return invoke<FilterDocumentView>("save_filter");
+}
+
+export async function exportStrictnessVariant(
+  path: string,
+  strictness: Strictness,
+  rules?: StrictnessRule[],
+): Promise<void> {
+  return invoke<void>("export_strictness_variant", { path, strictness, rules });
+}
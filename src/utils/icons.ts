@@ -0,0 +1,26 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { BeamImage } from "../../src-tauri/bindings/BeamImage";
+import type { Effect } from "../../src-tauri/bindings/Effect";
+import type { MinimapIcon } from "../../src-tauri/bindings/MinimapIcon";
+import type { MinimapIconImage } from "../../src-tauri/bindings/MinimapIconImage";
+
+// Minimap icons and beams of PlayEffect are rendered in the backend, the images are PNG data: URLs
+// that can be used as image sources
+
+export async function renderMinimapIconPreview(icon: MinimapIcon): Promise<string> {
+  return invoke<string>("render_minimap_icon_preview", { icon });
+}
+
+export async function renderBeamPreview(effect: Effect): Promise<string> {
+  return invoke<string>("render_beam_preview", { effect });
+}
+
+// Every shape in every color and size, to render them once and look them up
+export async function listMinimapIconImages(): Promise<MinimapIconImage[]> {
+  return invoke<MinimapIconImage[]>("list_minimap_icon_images");
+}
+
+// Every color, lasting and Temp
+export async function listBeamImages(): Promise<BeamImage[]> {
+  return invoke<BeamImage[]>("list_beam_images");
+}
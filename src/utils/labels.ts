@@ -0,0 +1,20 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { BlockId } from "../../src-tauri/bindings/BlockId";
+import type { LabelStyle } from "../../src-tauri/bindings/LabelStyle";
+
+// Labels are rendered in the backend as PNG images that look like the labels in the game,
+// previews are data: URLs that can be used as image sources
+
+export async function renderLabelPreview(style: LabelStyle): Promise<string> {
+  return invoke<string>("render_label_preview", { style });
+}
+
+// Renders the label of a block of the opened filter, including unsaved changes
+export async function renderBlockLabel(id: BlockId): Promise<string> {
+  return invoke<string>("render_block_label", { id });
+}
+
+// Saves the label of a block of the opened filter as a PNG file, to share the look of a rule
+export async function exportBlockLabel(id: BlockId, path: string): Promise<void> {
+  return invoke<void>("export_block_label", { id, path });
+}
@@ -0,0 +1,26 @@
+import type { FilterMetadata } from "../../src-tauri/bindings/FilterMetadata";
+import type { FilterVersion } from "../../src-tauri/bindings/FilterVersion";
+import type { Strictness } from "../../src-tauri/bindings/Strictness";
+
+const strictnessNames: Record<Strictness, string> = {
+  Soft: "Soft",
+  Regular: "Regular",
+  SemiStrict: "Semi-Strict",
+  Strict: "Strict",
+  VeryStrict: "Very Strict",
+  UberStrict: "Uber Strict",
+  UberPlusStrict: "Uber Plus Strict",
+};
+
+function versionToString(version: FilterVersion): string {
+  return `${version.major}.${version.minor}.${version.patch}${version.suffix ?? ""}`;
+}
+
+// Returns a short filter description, for example "NeverSink 0.52.0b Semi-Strict"
+export function metadataSummary(metadata: FilterMetadata): string {
+  const parts: string[] = [];
+  if (metadata.author) parts.push(metadata.author);
+  if (metadata.version) parts.push(versionToString(metadata.version));
+  if (metadata.strictness) parts.push(strictnessNames[metadata.strictness]);
+  return parts.join(" ");
+}
@@ -0,0 +1,12 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { RegressionReport } from "../../src-tauri/bindings/RegressionReport";
+
+// Lists the items that look different with the after filter, which defaults to the opened filter
+// with its unsaved edits, the corpus is a folder or file of items and defaults to the capture history
+export async function itemRegressionReport(
+  before: string,
+  after: string | null = null,
+  corpus: string | null = null,
+): Promise<RegressionReport> {
+  return invoke<RegressionReport>("item_regression_report", { before, after, corpus });
+}
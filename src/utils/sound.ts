@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { BlockId } from "../../src-tauri/bindings/BlockId";
+import type { BlockSoundPreview } from "../../src-tauri/bindings/BlockSoundPreview";
+import type { SoundLintReport } from "../../src-tauri/bindings/SoundLintReport";
+
+// Sounds play in the backend without blocking, errors such as a missing audio device are returned
+
+export async function playSound(file: string): Promise<void> {
+  return invoke<void>("play_sound", { file });
+}
+
+export async function stopSounds(): Promise<void> {
+  return invoke<void>("stop_sounds");
+}
+
+export async function setSoundVolume(volume: number): Promise<void> {
+  return invoke<void>("set_sound_volume", { volume });
+}
+
+// Plays the alert sound of a block of the opened filter as the game would
+export async function previewBlockSound(id: BlockId): Promise<BlockSoundPreview> {
+  return invoke<BlockSoundPreview>("preview_block_sound", { id });
+}
+
+// Finds missing, unplayable, long and loud custom sound files and the unused files in the filter folder
+export async function lintSoundFiles(): Promise<SoundLintReport> {
+  return invoke<SoundLintReport>("lint_sound_files");
+}
@@ -0,0 +1,29 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { BundleReport } from "../../src-tauri/bindings/BundleReport";
+import type { SoundPack } from "../../src-tauri/bindings/SoundPack";
+import type { TierSound } from "../../src-tauri/bindings/TierSound";
+
+// Sound packs are folders of sound files in the "sounds" folder next to the opened filter
+
+export async function listSoundPacks(): Promise<SoundPack[]> {
+  return invoke<SoundPack[]>("list_sound_packs");
+}
+
+// Imports a folder or a .zip file of sounds
+export async function importSoundPack(source: string): Promise<SoundPack> {
+  return invoke<SoundPack>("import_sound_pack", { source });
+}
+
+// Points the custom sounds of a theme file at the files of the pack, returns the number of changed tiers
+export async function useSoundPack(themePath: string, pack: SoundPack): Promise<number> {
+  return invoke<number>("use_sound_pack", { themePath, pack });
+}
+
+export async function listTierSounds(): Promise<TierSound[]> {
+  return invoke<TierSound[]>("list_tier_sounds");
+}
+
+// Writes the opened filter and the sound files it plays to a .zip file
+export async function exportFilterBundle(destination: string): Promise<BundleReport> {
+  return invoke<BundleReport>("export_filter_bundle", { destination });
+}
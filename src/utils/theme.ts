@@ -0,0 +1,12 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { FilterDocumentView } from "../../src-tauri/bindings/FilterDocumentView";
+
+// Themes are .toml or .json files with the colors, effects and sounds of each $tier
+
+export async function applyTheme(path: string): Promise<FilterDocumentView> {
+  return invoke<FilterDocumentView>("apply_theme", { path });
+}
+
+export async function exportTheme(path: string): Promise<void> {
+  return invoke<void>("export_theme", { path });
+}